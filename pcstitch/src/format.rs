@@ -0,0 +1,58 @@
+//! The reader of the PAT format for the common pattern interface.
+
+use xsp_core::{PatternFormat, PatternReader};
+
+use crate::parsers::pat::*;
+use crate::schemas::*;
+
+#[cfg(test)]
+#[path = "format.test.rs"]
+mod tests;
+
+/// The PCStitch format.
+///
+/// The support is experimental, the parser hasn't been verified against files saved by PCStitch.
+pub struct PatFormat;
+
+impl PatternFormat for PatFormat {
+  fn name(&self) -> &'static str {
+    "PCStitch"
+  }
+
+  fn extensions(&self) -> &'static [&'static str] {
+    &["pat"]
+  }
+}
+
+impl PatternReader for PatFormat {
+  /// The format is chosen only by the file extension until the layout is verified.
+  fn sniff(&self, _buf: &[u8]) -> bool {
+    false
+  }
+
+  fn read(&self, buf: &[u8]) -> anyhow::Result<xsp_core::Pattern> {
+    Ok(parse_pat_pattern_from_bytes(buf)?.into())
+  }
+}
+
+impl From<Pattern> for xsp_core::Pattern {
+  fn from(pattern: Pattern) -> Self {
+    xsp_core::Pattern {
+      info: pattern.info,
+      fabric: pattern.fabric,
+      palette: pattern.palette,
+      fullstitches: pattern.fullstitches,
+      partstitches: pattern.partstitches,
+      linestitches: pattern.linestitches,
+      nodestitches: pattern.nodestitches,
+      specialstitches: Vec::new(),
+      special_stitch_models: Vec::new(),
+      display: xsp_core::DisplayHints {
+        symbols: pattern.symbols,
+        symbol_font: Some(pattern.symbol_font).filter(|font| !font.is_empty()),
+        ..Default::default()
+      },
+      extensions: Default::default(),
+    }
+  }
+}
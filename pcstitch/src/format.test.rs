@@ -0,0 +1,23 @@
+use super::*;
+
+fn load_pattern(name: &str) -> Pattern {
+  let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+    .join("testdata/patterns")
+    .join(name);
+  parse_pat_pattern(path).unwrap()
+}
+
+#[test]
+fn converts_to_common_pattern() {
+  let pattern = load_pattern("sample.pat");
+  let converted = xsp_core::Pattern::from(pattern.clone());
+
+  assert_eq!(converted.fabric, pattern.fabric);
+  assert_eq!(converted.palette, pattern.palette);
+  assert_eq!(converted.fullstitches, pattern.fullstitches);
+  assert_eq!(converted.partstitches, pattern.partstitches);
+  assert_eq!(converted.linestitches, pattern.linestitches);
+  assert_eq!(converted.nodestitches, pattern.nodestitches);
+  assert_eq!(converted.display.symbols, [Some('#'), Some('×')]);
+  assert_eq!(converted.display.symbol_font.as_deref(), Some("PCStitch Symbols"));
+}
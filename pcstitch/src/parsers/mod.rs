@@ -0,0 +1,4 @@
+mod read;
+use read::ReadPatExt;
+
+pub mod pat;
@@ -0,0 +1,460 @@
+//! An experimental parser for the proprietary PAT pattern format of PCStitch.
+//!
+//! The layout below is a best guess that hasn't been verified against files saved by PCStitch yet:
+//! the test data is hand-made to follow it, so real files may fail to parse or be read incorrectly.
+//!
+//! All numbers are little-endian and the strings are in CP1252 encoding.
+//! The palette indices are 1-based, so `0` means an empty cell.
+
+use std::io::{self, Read, Seek};
+
+use anyhow::Result;
+use byteorder::{LittleEndian, ReadBytesExt};
+
+use super::ReadPatExt as _;
+use crate::schemas::*;
+
+#[cfg(test)]
+#[path = "pat.test.rs"]
+mod tests;
+
+const SIGNATURE_LENGTH: usize = 255;
+const SIGNATURE_PREFIX: &str = "PCStitch ";
+const SIGNATURE_SUFFIX: &str = " Pattern File";
+
+const PALETTE_MARKER: &[u8] = b"PCStitch 5 Floss Palette!";
+
+const COLOR_BRAND_LENGTH: usize = 30;
+const COLOR_NUMBER_LENGTH: usize = 10;
+const COLOR_NAME_LENGTH: usize = 40;
+
+pub fn parse_pat_pattern<P: AsRef<std::path::Path>>(file_path: P) -> Result<Pattern> {
+  let buf = std::fs::read(file_path.as_ref())?;
+  parse_pat_pattern_from_bytes(&buf)
+}
+
+pub fn parse_pat_pattern_from_bytes(buf: &[u8]) -> Result<Pattern> {
+  let mut cursor = std::io::Cursor::new(buf);
+
+  let signature = read_signature(&mut cursor)?;
+  let Some(version) = parse_pcstitch_version(&signature) else {
+    anyhow::bail!("The signature of PCStitch is incorrect! Found: {signature:?}");
+  };
+  log::debug!("PCStitch version: {version}");
+
+  let (pattern_width, pattern_height, stitches_per_inch) = read_pattern_properties(&mut cursor)?;
+  let pattern_info = read_pattern_info(&mut cursor)?;
+  let (fabric_kind_name, fabric_color) = read_fabric_info(&mut cursor)?;
+  let (palette, symbols, symbol_font) = read_palette(&mut cursor)?;
+  let (fullstitches, partstitches) = read_stitches(&mut cursor, pattern_width, pattern_height, palette.len())?;
+  let (linestitches, nodestitches) = read_joints(&mut cursor, palette.len())?;
+
+  Ok(Pattern {
+    info: pattern_info,
+    fabric: Fabric {
+      width: pattern_width,
+      height: pattern_height,
+      stitches_per_inch,
+      kind: fabric_kind_name,
+      name: String::new(),
+      color: fabric_color,
+    },
+    palette,
+    symbols,
+    symbol_font,
+    fullstitches,
+    partstitches,
+    linestitches,
+    nodestitches,
+  })
+}
+
+fn read_signature<R: Read>(reader: &mut R) -> io::Result<String> {
+  reader.read_cstring(SIGNATURE_LENGTH)
+}
+
+/// Extracts the major version of PCStitch from the signature, e.g., `PCStitch 7 Pattern File`.
+fn parse_pcstitch_version(signature: &str) -> Option<u8> {
+  signature
+    .strip_prefix(SIGNATURE_PREFIX)?
+    .strip_suffix(SIGNATURE_SUFFIX)?
+    .parse()
+    .ok()
+}
+
+/// Reads the pattern size and the fabric count.
+fn read_pattern_properties<R: Read + Seek>(reader: &mut R) -> io::Result<(u16, u16, (u8, u8))> {
+  log::trace!("Reading pattern properties");
+  reader.seek_relative(4)?; // Skip the unknown data.
+  let pattern_width = reader.read_u16::<LittleEndian>()?;
+  let pattern_height = reader.read_u16::<LittleEndian>()?;
+  let stitches_per_inch = (
+    reader.read_u16::<LittleEndian>()? as u8,
+    reader.read_u16::<LittleEndian>()? as u8,
+  );
+  Ok((pattern_width, pattern_height, stitches_per_inch))
+}
+
+fn read_pattern_info<R: Read>(reader: &mut R) -> io::Result<PatternInfo> {
+  log::trace!("Reading pattern info");
+  let author = reader.read_string()?;
+  let copyright = reader.read_string()?;
+  let title = reader.read_string()?;
+  let description = reader.read_string()?;
+  Ok(PatternInfo {
+    title,
+    author,
+    company: String::new(),
+    copyright,
+    description,
+  })
+}
+
+/// Reads the fabric kind and color.
+fn read_fabric_info<R: Read>(reader: &mut R) -> io::Result<(String, Rgb)> {
+  log::trace!("Reading fabric info");
+  let fabric_kind_name = reader.read_string()?;
+  let fabric_color = reader.read_color()?;
+  Ok((fabric_kind_name, fabric_color))
+}
+
+type Palette = (Vec<PaletteItem>, Vec<Option<char>>, String);
+
+/// Reads the color palette of the pattern, the symbols of its items and the symbol font.
+fn read_palette<R: Read + Seek>(reader: &mut R) -> io::Result<Palette> {
+  log::trace!("Reading palette");
+
+  let mut marker = [0; PALETTE_MARKER.len()];
+  reader.read_exact(&mut marker)?;
+  if marker != PALETTE_MARKER {
+    return Err(io::Error::new(
+      io::ErrorKind::InvalidData,
+      "The floss palette marker is missing",
+    ));
+  }
+
+  let palette_size: usize = reader.read_u16::<LittleEndian>()?.into();
+  let mut palette = Vec::with_capacity(palette_size);
+  let mut symbols = Vec::with_capacity(palette_size);
+
+  for _ in 0..palette_size {
+    let (palitem, symbol) = read_palette_item(reader)?;
+    palette.push(palitem);
+    symbols.push(symbol);
+  }
+
+  let symbol_font = reader.read_string()?;
+
+  Ok((palette, symbols, symbol_font))
+}
+
+fn read_palette_item<R: Read + Seek>(reader: &mut R) -> io::Result<(PaletteItem, Option<char>)> {
+  reader.seek_relative(2)?;
+  let color = reader.read_color()?;
+  let brand = reader.read_cstring(COLOR_BRAND_LENGTH)?;
+  let number = reader.read_cstring(COLOR_NUMBER_LENGTH)?;
+  let name = reader.read_cstring(COLOR_NAME_LENGTH)?;
+  let strands = read_palette_item_strands(reader)?;
+
+  let symbol = match reader.read_u8()? {
+    0 => None,
+    code => encoding_rs::WINDOWS_1252.decode(&[code]).0.chars().next(),
+  };
+
+  let palitem = PaletteItem {
+    brand,
+    number,
+    name,
+    color,
+    blends: None,
+    bead: None,
+    strands,
+  };
+  Ok((palitem, symbol))
+}
+
+/// Reads the numbers of strands for the cross, back stitches and french knots.
+/// Zero means the default number of strands.
+fn read_palette_item_strands<R: Read>(reader: &mut R) -> io::Result<Option<StitchStrands<Option<u8>>>> {
+  let read_strands = |reader: &mut R| -> io::Result<Option<u8>> {
+    let strands = reader.read_u8()?;
+    Ok(if strands == 0 { None } else { Some(strands) })
+  };
+
+  let cross = read_strands(reader)?;
+  let back = read_strands(reader)?;
+  let french_knot = read_strands(reader)?;
+
+  if cross.is_none() && back.is_none() && french_knot.is_none() {
+    return Ok(None);
+  }
+
+  Ok(Some(StitchStrands {
+    full: cross,
+    petite: cross,
+    half: cross,
+    quarter: cross,
+    back,
+    straight: back,
+    french_knot,
+    special: cross,
+  }))
+}
+
+/// Reads the full and half stitches that fill the cells and the quarter and petite stitches.
+fn read_stitches<R: Read>(
+  reader: &mut R,
+  pattern_width: u16,
+  pattern_height: u16,
+  palette_size: usize,
+) -> io::Result<(Vec<FullStitch>, Vec<PartStitch>)> {
+  log::trace!("Reading stitches");
+  let (mut fullstitches, mut partstitches) = read_cells(reader, pattern_width, pattern_height, palette_size)?;
+  read_fractional_stitches(reader, palette_size, &mut fullstitches, &mut partstitches)?;
+  Ok((fullstitches, partstitches))
+}
+
+/// Reads the run-length encoded cells.
+/// The cells are stored column by column, each run consists of its length, the palette index and the stitch kind.
+fn read_cells<R: Read>(
+  reader: &mut R,
+  pattern_width: u16,
+  pattern_height: u16,
+  palette_size: usize,
+) -> io::Result<(Vec<FullStitch>, Vec<PartStitch>)> {
+  let mut fullstitches = Vec::new();
+  let mut partstitches = Vec::new();
+
+  let pattern_height = usize::from(pattern_height);
+  let total_cells_count = usize::from(pattern_width) * pattern_height;
+  let mut cell_index = 0;
+
+  while cell_index < total_cells_count {
+    let run_length: usize = reader.read_u16::<LittleEndian>()?.into();
+    let palindex = reader.read_u8()?;
+    let kind = PatCellKind::try_from(reader.read_u8()?)?;
+
+    if run_length == 0 || cell_index + run_length > total_cells_count {
+      return Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("The run of {run_length} cells at the cell {cell_index} is invalid"),
+      ));
+    }
+
+    let cells = cell_index..cell_index + run_length;
+    cell_index += run_length;
+
+    let palindex = match (kind, palindex.checked_sub(1)) {
+      (PatCellKind::Empty, _) | (_, None) => continue,
+      (_, Some(palindex)) => check_palindex(palindex, palette_size)?,
+    };
+    for index in cells {
+      let x = Coord::from_cells((index / pattern_height) as i32);
+      let y = Coord::from_cells((index % pattern_height) as i32);
+      if kind == PatCellKind::Full {
+        fullstitches.push(FullStitch {
+          x,
+          y,
+          palindex,
+          kind: FullStitchKind::Full,
+        });
+      } else {
+        partstitches.push(PartStitch {
+          x,
+          y,
+          palindex,
+          direction: if kind == PatCellKind::HalfForward {
+            PartStitchDirection::Forward
+          } else {
+            PartStitchDirection::Backward
+          },
+          kind: PartStitchKind::Half,
+        });
+      }
+    }
+  }
+
+  Ok((fullstitches, partstitches))
+}
+
+/// Reads the quarter and petite stitches that occupy a corner of the cell.
+fn read_fractional_stitches<R: Read>(
+  reader: &mut R,
+  palette_size: usize,
+  fullstitches: &mut Vec<FullStitch>,
+  partstitches: &mut Vec<PartStitch>,
+) -> io::Result<()> {
+  let fractional_stitches_count = reader.read_u16::<LittleEndian>()?;
+
+  for _ in 0..fractional_stitches_count {
+    let x = Coord::from_cells(reader.read_u16::<LittleEndian>()?.into());
+    let y = Coord::from_cells(reader.read_u16::<LittleEndian>()?.into());
+    let palindex = read_palindex(reader, palette_size)?;
+    let kind = reader.read_u8()?;
+    let corner = reader.read_u8()?;
+
+    let (x, y, direction) = match corner {
+      0 => (x, y, PartStitchDirection::Backward),
+      1 => (x + Coord::HALF, y, PartStitchDirection::Forward),
+      2 => (x, y + Coord::HALF, PartStitchDirection::Forward),
+      3 => (x + Coord::HALF, y + Coord::HALF, PartStitchDirection::Backward),
+      _ => {
+        return Err(io::Error::new(
+          io::ErrorKind::InvalidData,
+          format!("The cell corner {corner} is unknown"),
+        ));
+      }
+    };
+
+    match kind {
+      1 => partstitches.push(PartStitch {
+        x,
+        y,
+        palindex,
+        direction,
+        kind: PartStitchKind::Quarter,
+      }),
+      2 => fullstitches.push(FullStitch {
+        x,
+        y,
+        palindex,
+        kind: FullStitchKind::Petite,
+      }),
+      _ => {
+        return Err(io::Error::new(
+          io::ErrorKind::InvalidData,
+          format!("The fractional stitch kind {kind} is unknown"),
+        ));
+      }
+    }
+  }
+
+  Ok(())
+}
+
+fn read_coord<R: Read>(reader: &mut R) -> io::Result<Coord> {
+  Ok(Coord::from_halves(reader.read_u16::<LittleEndian>()?.into()))
+}
+
+/// Reads a 1-based palette index and converts it to a 0-based one.
+fn read_palindex<R: Read>(reader: &mut R, palette_size: usize) -> io::Result<u8> {
+  let palindex = reader
+    .read_u8()?
+    .checked_sub(1)
+    .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "The palette index 0 is invalid"))?;
+  check_palindex(palindex, palette_size)
+}
+
+/// Checks that the 0-based palette index refers to an existing palette item.
+fn check_palindex(palindex: u8, palette_size: usize) -> io::Result<u8> {
+  if usize::from(palindex) < palette_size {
+    Ok(palindex)
+  } else {
+    Err(io::Error::new(
+      io::ErrorKind::InvalidData,
+      format!("The palette index {} is out of the palette", u16::from(palindex) + 1),
+    ))
+  }
+}
+
+/// Reads the back stitches, long stitches, french knots and beads used in the pattern.
+fn read_joints<R: Read>(reader: &mut R, palette_size: usize) -> io::Result<(Vec<LineStitch>, Vec<NodeStitch>)> {
+  log::trace!("Reading joints");
+
+  let mut linestitches = Vec::new();
+  let mut nodestitches = Vec::new();
+
+  let joints_count = reader.read_u32::<LittleEndian>()?;
+  for _ in 0..joints_count {
+    let joint_kind = PatJointKind::try_from(reader.read_u8()?)?;
+    let palindex = read_palindex(reader, palette_size)?;
+    match joint_kind {
+      PatJointKind::Back | PatJointKind::Long => {
+        let x1 = read_coord(reader)?;
+        let y1 = read_coord(reader)?;
+        let x2 = read_coord(reader)?;
+        let y2 = read_coord(reader)?;
+        let kind = if joint_kind == PatJointKind::Back {
+          LineStitchKind::Back
+        } else {
+          LineStitchKind::Straight
+        };
+        linestitches.push(LineStitch {
+          x: (x1, x2),
+          y: (y1, y2),
+          palindex,
+          kind,
+        });
+      }
+
+      PatJointKind::FrenchKnot | PatJointKind::Bead => {
+        let x = read_coord(reader)?;
+        let y = read_coord(reader)?;
+        let kind = if joint_kind == PatJointKind::FrenchKnot {
+          NodeStitchKind::FrenchKnot
+        } else {
+          NodeStitchKind::Bead
+        };
+        nodestitches.push(NodeStitch {
+          x,
+          y,
+          rotated: false,
+          palindex,
+          kind,
+        });
+      }
+    }
+  }
+
+  Ok((linestitches, nodestitches))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PatCellKind {
+  Empty,
+  Full,
+  HalfForward,
+  HalfBackward,
+}
+
+impl TryFrom<u8> for PatCellKind {
+  type Error = io::Error;
+
+  fn try_from(value: u8) -> Result<Self, Self::Error> {
+    match value {
+      0 => Ok(PatCellKind::Empty),
+      1 => Ok(PatCellKind::Full),
+      2 => Ok(PatCellKind::HalfForward),
+      3 => Ok(PatCellKind::HalfBackward),
+      _ => Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("The cell kind {value} is unknown"),
+      )),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PatJointKind {
+  Back,
+  Long,
+  FrenchKnot,
+  Bead,
+}
+
+impl TryFrom<u8> for PatJointKind {
+  type Error = io::Error;
+
+  fn try_from(value: u8) -> Result<Self, Self::Error> {
+    match value {
+      1 => Ok(PatJointKind::Back),
+      2 => Ok(PatJointKind::Long),
+      3 => Ok(PatJointKind::FrenchKnot),
+      4 => Ok(PatJointKind::Bead),
+      _ => Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("The joint kind {value} is unknown"),
+      )),
+    }
+  }
+}
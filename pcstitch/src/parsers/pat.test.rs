@@ -0,0 +1,217 @@
+use std::fs::File;
+use std::io::Cursor;
+
+use super::*;
+
+fn load_fixture(name: &str) -> File {
+  let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+    .join("testdata/pat")
+    .join(name);
+  File::open(path).unwrap()
+}
+
+#[test]
+fn parses_pcstitch_version() {
+  assert_eq!(parse_pcstitch_version("PCStitch 5 Pattern File"), Some(5));
+  assert_eq!(parse_pcstitch_version("PCStitch 11 Pattern File"), Some(11));
+  assert_eq!(parse_pcstitch_version("PCStitch Pattern File"), None);
+  assert_eq!(parse_pcstitch_version("Pattern Maker"), None);
+}
+
+#[test]
+fn reads_pattern_properties() {
+  let (width, height, stitches_per_inch) = read_pattern_properties(&mut load_fixture("pattern_properties")).unwrap();
+  assert_eq!((width, height), (4, 3));
+  assert_eq!(stitches_per_inch, (14, 14));
+}
+
+#[test]
+fn reads_pattern_info() {
+  let loaded_pattern_info = read_pattern_info(&mut load_fixture("pattern_info")).unwrap();
+  let expected_pattern_info = PatternInfo {
+    title: String::from("Sample"),
+    author: String::from("Nazar Antoniuk"),
+    company: String::new(),
+    copyright: String::from("© Embroidery Studio"),
+    description: String::from("Stitch with two strands."),
+  };
+  assert_eq!(loaded_pattern_info, expected_pattern_info);
+}
+
+#[test]
+fn reads_fabric_info() {
+  let (kind, color) = read_fabric_info(&mut load_fixture("fabric_info")).unwrap();
+  assert_eq!(kind, "Aida");
+  assert_eq!(color, Rgb::new(0xF0, 0xEA, 0xDA));
+}
+
+#[test]
+fn reads_palette() {
+  let (palette, symbols, symbol_font) = read_palette(&mut load_fixture("palette")).unwrap();
+  let expected_palette = [
+    PaletteItem {
+      brand: String::from("DMC"),
+      number: String::from("310"),
+      name: String::from("Black"),
+      color: Rgb::new(0x2C, 0x32, 0x25),
+      blends: None,
+      bead: None,
+      strands: Some(StitchStrands {
+        full: Some(2),
+        petite: Some(2),
+        half: Some(2),
+        quarter: Some(2),
+        back: Some(1),
+        straight: Some(1),
+        french_knot: Some(2),
+        special: Some(2),
+      }),
+    },
+    PaletteItem {
+      brand: String::from("Anchor"),
+      number: String::from("1"),
+      name: String::from("Crème"),
+      color: Rgb::WHITE,
+      blends: None,
+      bead: None,
+      strands: None,
+    },
+  ];
+  assert_eq!(palette, expected_palette);
+  assert_eq!(symbols, [Some('#'), Some('×')]);
+  assert_eq!(symbol_font, "PCStitch Symbols");
+}
+
+#[test]
+fn fails_on_missing_palette_marker() {
+  let error = read_palette(&mut Cursor::new(vec![0; 32])).unwrap_err();
+  assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn reads_stitches() {
+  let (fullstitches, partstitches) = read_stitches(&mut load_fixture("stitches"), 4, 3, 2).unwrap();
+  let full = |x, y| FullStitch {
+    x: Coord::from_cells(x),
+    y: Coord::from_cells(y),
+    palindex: 0,
+    kind: FullStitchKind::Full,
+  };
+  let expected_fullstitches = [
+    full(0, 0),
+    full(0, 1),
+    full(0, 2),
+    full(2, 0),
+    full(2, 1),
+    full(2, 2),
+    full(3, 0),
+    FullStitch {
+      x: Coord::from_halves(3),
+      y: Coord::from_halves(3),
+      palindex: 0,
+      kind: FullStitchKind::Petite,
+    },
+  ];
+  let expected_partstitches = [
+    PartStitch {
+      x: Coord::from_cells(1),
+      y: Coord::from_cells(2),
+      palindex: 1,
+      direction: PartStitchDirection::Forward,
+      kind: PartStitchKind::Half,
+    },
+    PartStitch {
+      x: Coord::from_cells(3),
+      y: Coord::from_cells(1),
+      palindex: 1,
+      direction: PartStitchDirection::Backward,
+      kind: PartStitchKind::Half,
+    },
+    PartStitch {
+      x: Coord::from_cells(3),
+      y: Coord::from_cells(2),
+      palindex: 1,
+      direction: PartStitchDirection::Backward,
+      kind: PartStitchKind::Half,
+    },
+    PartStitch {
+      x: Coord::from_halves(3),
+      y: Coord::from_cells(0),
+      palindex: 1,
+      direction: PartStitchDirection::Forward,
+      kind: PartStitchKind::Quarter,
+    },
+  ];
+  assert_eq!(fullstitches, expected_fullstitches);
+  assert_eq!(partstitches, expected_partstitches);
+}
+
+#[test]
+fn fails_on_invalid_runs() {
+  // The run is longer than the pattern.
+  let buf = vec![0x05, 0x00, 0x01, 0x01];
+  assert!(read_stitches(&mut Cursor::new(buf), 2, 2, 2).is_err());
+
+  // The run is empty.
+  let buf = vec![0x00, 0x00, 0x01, 0x01];
+  assert!(read_stitches(&mut Cursor::new(buf), 2, 2, 2).is_err());
+
+  // The cell kind is unknown.
+  let buf = vec![0x04, 0x00, 0x01, 0x09];
+  assert!(read_stitches(&mut Cursor::new(buf), 2, 2, 2).is_err());
+
+  // The palette index is out of the palette.
+  let buf = vec![0x04, 0x00, 0x03, 0x01];
+  let error = read_stitches(&mut Cursor::new(buf), 2, 2, 2).unwrap_err();
+  assert_eq!(error.to_string(), "The palette index 3 is out of the palette");
+}
+
+#[test]
+fn reads_joints() {
+  let (linestitches, nodestitches) = read_joints(&mut load_fixture("joints"), 2).unwrap();
+  let expected_linestitches = [
+    LineStitch {
+      x: (Coord::from_cells(0), Coord::from_cells(2)),
+      y: (Coord::from_cells(0), Coord::from_cells(0)),
+      palindex: 0,
+      kind: LineStitchKind::Back,
+    },
+    LineStitch {
+      x: (Coord::from_halves(2), Coord::from_halves(6)),
+      y: (Coord::from_halves(2), Coord::from_halves(5)),
+      palindex: 1,
+      kind: LineStitchKind::Straight,
+    },
+  ];
+  let expected_nodestitches = [
+    NodeStitch {
+      x: Coord::from_cells(1),
+      y: Coord::from_cells(1),
+      rotated: false,
+      palindex: 0,
+      kind: NodeStitchKind::FrenchKnot,
+    },
+    NodeStitch {
+      x: Coord::from_halves(5),
+      y: Coord::from_halves(5),
+      rotated: false,
+      palindex: 1,
+      kind: NodeStitchKind::Bead,
+    },
+  ];
+  assert_eq!(linestitches, expected_linestitches);
+  assert_eq!(nodestitches, expected_nodestitches);
+}
+
+#[test]
+fn fails_on_unknown_joints() {
+  let buf = vec![0x01, 0x00, 0x00, 0x00, 0x07, 0x01];
+  assert!(read_joints(&mut Cursor::new(buf), 2).is_err());
+
+  let zero_palindex_buf = vec![0x01, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00];
+  assert!(read_joints(&mut Cursor::new(zero_palindex_buf), 2).is_err());
+
+  let out_of_palette_buf = vec![0x01, 0x00, 0x00, 0x00, 0x03, 0x03, 0x00, 0x00, 0x00, 0x00];
+  let error = read_joints(&mut Cursor::new(out_of_palette_buf), 2).unwrap_err();
+  assert_eq!(error.to_string(), "The palette index 3 is out of the palette");
+}
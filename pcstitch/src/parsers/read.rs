@@ -0,0 +1,37 @@
+use std::io;
+
+use xsp_core::ReadFormatExt as _;
+
+use crate::schemas::Rgb;
+
+#[cfg(test)]
+#[path = "read.test.rs"]
+mod tests;
+
+/// Provides additional methods for reading PAT data.
+pub trait ReadPatExt: io::Read + byteorder::ReadBytesExt {
+  /// Reads a C-style string with a specified length.
+  /// The string is in CP1252 encoding.
+  fn read_cstring(&mut self, length: usize) -> io::Result<String> {
+    self.read_encoded_cstring(length, encoding_rs::WINDOWS_1252)
+  }
+
+  /// Reads a string prefixed with its length.
+  /// The string is in CP1252 encoding.
+  fn read_string(&mut self) -> io::Result<String> {
+    let length: usize = self.read_u16::<byteorder::LittleEndian>()?.into();
+    let mut buf = vec![0; length];
+    self.read_exact(&mut buf)?;
+    Ok(encoding_rs::WINDOWS_1252.decode(&buf).0.to_string())
+  }
+
+  /// Reads an RGB color followed by an unused byte.
+  fn read_color(&mut self) -> io::Result<Rgb> {
+    let mut buf: [u8; 4] = [0; 4];
+    self.read_exact(&mut buf)?;
+    Ok(Rgb::new(buf[0], buf[1], buf[2]))
+  }
+}
+
+/// All types that implement `Read` get methods defined in `ReadPatExt`.
+impl<R: io::Read + ?Sized> ReadPatExt for R {}
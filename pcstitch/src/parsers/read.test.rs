@@ -0,0 +1,40 @@
+use std::io::Cursor;
+
+use super::ReadPatExt as _;
+use crate::schemas::Rgb;
+
+#[test]
+fn reads_cstring() {
+  let buf = vec![0x57, 0x68, 0x69, 0x74, 0x65, 0x00, 0x00, 0x00];
+  assert_eq!(Cursor::new(buf).read_cstring(7).unwrap(), String::from("White"));
+
+  let cp1252_buf = vec![0x43, 0x72, 0xE8, 0x6D, 0x65, 0x00];
+  assert_eq!(Cursor::new(cp1252_buf).read_cstring(5).unwrap(), String::from("Crème"));
+}
+
+#[test]
+fn returns_empty_string_on_non_null_terminated_cstring() {
+  let not_nul_terminated_buf = vec![0x43, 0x6F, 0x66, 0x66, 0x65, 0x65];
+  assert_eq!(
+    Cursor::new(not_nul_terminated_buf).read_cstring(5).unwrap(),
+    String::from("")
+  );
+}
+
+#[test]
+fn reads_string() {
+  let buf = vec![0x04, 0x00, 0x41, 0x69, 0x64, 0x61, 0xFF];
+  assert_eq!(Cursor::new(buf).read_string().unwrap(), String::from("Aida"));
+
+  let empty_buf = vec![0x00, 0x00];
+  assert_eq!(Cursor::new(empty_buf).read_string().unwrap(), String::new());
+
+  let truncated_buf = vec![0x04, 0x00, 0x41];
+  assert!(Cursor::new(truncated_buf).read_string().is_err());
+}
+
+#[test]
+fn reads_color() {
+  let color_buf = vec![0x2C, 0x32, 0x25, 0x00];
+  assert_eq!(Cursor::new(color_buf).read_color().unwrap(), Rgb::new(0x2C, 0x32, 0x25));
+}
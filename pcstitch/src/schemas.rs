@@ -0,0 +1,18 @@
+pub use pmaker::{
+  Coord, Fabric, FullStitch, FullStitchKind, LineStitch, LineStitchKind, NodeStitch, NodeStitchKind, PaletteItem,
+  PartStitch, PartStitchDirection, PartStitchKind, PatternInfo, Rgb, StitchStrands,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pattern {
+  pub info: PatternInfo,
+  pub fabric: Fabric,
+  pub palette: Vec<PaletteItem>,
+  /// The symbols of the palette items, in the order of the palette.
+  pub symbols: Vec<Option<char>>,
+  pub symbol_font: String,
+  pub fullstitches: Vec<FullStitch>,
+  pub partstitches: Vec<PartStitch>,
+  pub linestitches: Vec<LineStitch>,
+  pub nodestitches: Vec<NodeStitch>,
+}
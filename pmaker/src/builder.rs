@@ -0,0 +1,192 @@
+//! Construction of patterns in code.
+
+use std::fmt;
+
+use crate::schemas::color::Rgb;
+use crate::schemas::xsd::*;
+use crate::validation::{MAX_PALETTE_SIZE, ValidationErrors};
+
+#[cfg(test)]
+#[path = "builder.test.rs"]
+mod tests;
+
+/// The reason why an item can't be added to the pattern.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuilderError {
+  /// The palette already has `MAX_PALETTE_SIZE` items.
+  TooManyColors,
+  /// There are already 256 special stitch models, so the new one can't be referenced by a `modindex`.
+  TooManySpecialStitchModels,
+}
+
+impl fmt::Display for BuilderError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      BuilderError::TooManyColors => {
+        write!(f, "The palette already has {MAX_PALETTE_SIZE} colors")
+      }
+      BuilderError::TooManySpecialStitchModels => write!(f, "There are already 256 special stitch models"),
+    }
+  }
+}
+
+impl std::error::Error for BuilderError {}
+
+/// Builds a pattern starting from the settings of a new pattern in Pattern Maker.
+///
+/// The formats and symbols tables are kept in sync with the palette.
+/// The pattern is validated when it is built.
+#[derive(Debug, Clone)]
+pub struct PatternBuilder {
+  pattern: Pattern,
+}
+
+impl PatternBuilder {
+  /// Starts a pattern on a white 14-count Aida of the given size in stitches.
+  pub fn new(width: u16, height: u16) -> Self {
+    PatternBuilder {
+      pattern: Pattern {
+        info: PatternInfo::default(),
+        fabric: Fabric {
+          width,
+          height,
+          stitches_per_inch: (14, 14),
+          kind: String::from("Aida"),
+          name: String::from("White"),
+          color: Rgb::WHITE,
+        },
+        palette: Vec::new(),
+        formats: Vec::new(),
+        symbols: Vec::new(),
+        fullstitches: Vec::new(),
+        partstitches: Vec::new(),
+        linestitches: Vec::new(),
+        nodestitches: Vec::new(),
+        specialstitches: Vec::new(),
+        special_stitch_models: Vec::new(),
+        grid: Grid::default(),
+        pattern_settings: PatternSettings::default(),
+        stitch_settings: StitchSettings::default(),
+        symbol_settings: SymbolSettings::default(),
+        print_settings: PrintSettings::default(),
+      },
+    }
+  }
+
+  pub fn info(&mut self, info: PatternInfo) -> &mut Self {
+    self.pattern.info = info;
+    self
+  }
+
+  /// Replaces the fabric properties keeping the size of the pattern.
+  pub fn fabric(&mut self, fabric: Fabric) -> &mut Self {
+    self.pattern.fabric = Fabric {
+      width: self.pattern.fabric.width,
+      height: self.pattern.fabric.height,
+      ..fabric
+    };
+    self
+  }
+
+  pub fn grid(&mut self, grid: Grid) -> &mut Self {
+    self.pattern.grid = grid;
+    self
+  }
+
+  pub fn pattern_settings(&mut self, pattern_settings: PatternSettings) -> &mut Self {
+    self.pattern.pattern_settings = pattern_settings;
+    self
+  }
+
+  pub fn stitch_settings(&mut self, stitch_settings: StitchSettings) -> &mut Self {
+    self.pattern.stitch_settings = stitch_settings;
+    self
+  }
+
+  pub fn symbol_settings(&mut self, symbol_settings: SymbolSettings) -> &mut Self {
+    self.pattern.symbol_settings = symbol_settings;
+    self
+  }
+
+  pub fn print_settings(&mut self, print_settings: PrintSettings) -> &mut Self {
+    self.pattern.print_settings = print_settings;
+    self
+  }
+
+  /// Adds a palette item with the default formats and the next free full stitch symbol.
+  /// Returns its `palindex`.
+  pub fn add_palette_item(&mut self, palitem: PaletteItem) -> Result<u8, BuilderError> {
+    let symbols = Symbols {
+      full: self.next_free_symbol(),
+      ..Default::default()
+    };
+    self.add_palette_item_with(palitem, Formats::default(), symbols)
+  }
+
+  /// Adds a palette item with its formats and symbols.
+  /// Returns its `palindex`.
+  pub fn add_palette_item_with(
+    &mut self,
+    palitem: PaletteItem,
+    formats: Formats,
+    symbols: Symbols,
+  ) -> Result<u8, BuilderError> {
+    if self.pattern.palette.len() >= MAX_PALETTE_SIZE {
+      return Err(BuilderError::TooManyColors);
+    }
+    let palindex = self.pattern.palette.len() as u8;
+    self.pattern.palette.push(palitem);
+    self.pattern.formats.push(formats);
+    self.pattern.symbols.push(symbols);
+    Ok(palindex)
+  }
+
+  /// Returns the first full stitch symbol that isn't used by the palette yet,
+  /// skipping the space and the control characters.
+  pub(crate) fn next_free_symbol(&self) -> Option<u16> {
+    (33..=126)
+      .chain(161..=u16::MAX)
+      .find(|&code| self.pattern.symbols.iter().all(|symbols| symbols.full != Some(code)))
+  }
+
+  pub fn add_fullstitch(&mut self, stitch: FullStitch) -> &mut Self {
+    self.pattern.fullstitches.push(stitch);
+    self
+  }
+
+  pub fn add_partstitch(&mut self, stitch: PartStitch) -> &mut Self {
+    self.pattern.partstitches.push(stitch);
+    self
+  }
+
+  pub fn add_linestitch(&mut self, stitch: LineStitch) -> &mut Self {
+    self.pattern.linestitches.push(stitch);
+    self
+  }
+
+  pub fn add_nodestitch(&mut self, stitch: NodeStitch) -> &mut Self {
+    self.pattern.nodestitches.push(stitch);
+    self
+  }
+
+  pub fn add_specialstitch(&mut self, stitch: SpecialStitch) -> &mut Self {
+    self.pattern.specialstitches.push(stitch);
+    self
+  }
+
+  /// Adds a special stitch model.
+  /// Returns its `modindex`.
+  pub fn add_special_stitch_model(&mut self, model: SpecialStitchModel) -> Result<u8, BuilderError> {
+    let Ok(modindex) = u8::try_from(self.pattern.special_stitch_models.len()) else {
+      return Err(BuilderError::TooManySpecialStitchModels);
+    };
+    self.pattern.special_stitch_models.push(model);
+    Ok(modindex)
+  }
+
+  /// Validates and returns the pattern.
+  pub fn build(&self) -> Result<Pattern, ValidationErrors> {
+    self.pattern.validate()?;
+    Ok(self.pattern.clone())
+  }
+}
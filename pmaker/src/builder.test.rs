@@ -0,0 +1,125 @@
+use super::*;
+use crate::schemas::coords::Coord;
+use crate::validation::{StitchRef, ValidationError};
+
+#[test]
+fn builds_pattern() {
+  let mut builder = PatternBuilder::new(10, 5);
+  let black = builder
+    .add_palette_item(PaletteItem::new("DMC", "310", Rgb::BLACK))
+    .unwrap();
+  let white = builder
+    .add_palette_item_with(
+      PaletteItem::new("DMC", "B5200", Rgb::WHITE),
+      Formats::default(),
+      Symbols {
+        full: Some(33),
+        ..Default::default()
+      },
+    )
+    .unwrap();
+  builder
+    .add_fullstitch(FullStitch {
+      x: Coord::from_cells(9),
+      y: Coord::from_cells(4),
+      palindex: black,
+      kind: FullStitchKind::Full,
+    })
+    .add_linestitch(LineStitch {
+      x: (Coord::ZERO, Coord::from_cells(10)),
+      y: (Coord::ZERO, Coord::from_cells(5)),
+      palindex: white,
+      kind: LineStitchKind::Back,
+    });
+
+  let pattern = builder.build().unwrap();
+  assert_eq!((black, white), (0, 1));
+  assert_eq!((pattern.fabric.width, pattern.fabric.height), (10, 5));
+  assert_eq!(pattern.palette.len(), 2);
+  assert_eq!(pattern.formats, [Formats::default(), Formats::default()]);
+  assert_eq!(pattern.symbols[1].full, Some(33));
+  assert_eq!(pattern.fullstitches.len(), 1);
+  assert_eq!(pattern.linestitches.len(), 1);
+  assert_eq!(pattern.print_settings, PrintSettings::default());
+}
+
+#[test]
+fn validates_pattern() {
+  let mut builder = PatternBuilder::new(10, 5);
+  builder.add_fullstitch(FullStitch {
+    x: Coord::from_cells(10),
+    y: Coord::ZERO,
+    palindex: 0,
+    kind: FullStitchKind::Full,
+  });
+  assert_eq!(
+    builder.build().unwrap_err().0,
+    [
+      ValidationError::PalindexOutOfRange {
+        stitch: StitchRef::Full(0),
+        palindex: 0,
+      },
+      ValidationError::OutOfFabric {
+        stitch: StitchRef::Full(0),
+      },
+    ]
+  );
+}
+
+#[test]
+fn assigns_free_symbols() {
+  let mut builder = PatternBuilder::new(10, 5);
+  builder
+    .add_palette_item_with(
+      PaletteItem::new("DMC", "310", Rgb::BLACK),
+      Formats::default(),
+      Symbols {
+        full: Some(34),
+        ..Default::default()
+      },
+    )
+    .unwrap();
+  for number in 1..100 {
+    builder
+      .add_palette_item(PaletteItem::new("DMC", &number.to_string(), Rgb::BLACK))
+      .unwrap();
+  }
+
+  let symbols: Vec<_> = builder
+    .build()
+    .unwrap()
+    .symbols
+    .iter()
+    .map(|symbols| symbols.full)
+    .collect();
+  assert_eq!(symbols[..4], [Some(34), Some(33), Some(35), Some(36)]);
+  assert_eq!(symbols[93], Some(126));
+  assert_eq!(symbols[94], Some(161));
+}
+
+#[test]
+fn fails_on_too_many_colors() {
+  let mut builder = PatternBuilder::new(10, 5);
+  for number in 0..MAX_PALETTE_SIZE {
+    builder
+      .add_palette_item(PaletteItem::new("DMC", &number.to_string(), Rgb::BLACK))
+      .unwrap();
+  }
+  assert_eq!(
+    builder.add_palette_item(PaletteItem::new("DMC", "310", Rgb::BLACK)),
+    Err(BuilderError::TooManyColors)
+  );
+  assert_eq!(builder.build().unwrap().symbols[MAX_PALETTE_SIZE - 1].full, Some(306));
+}
+
+#[test]
+fn fails_on_too_many_special_stitch_models() {
+  let mut builder = PatternBuilder::new(10, 5);
+  for _ in 0..256 {
+    builder.add_special_stitch_model(SpecialStitchModel::default()).unwrap();
+  }
+  assert_eq!(
+    builder.add_special_stitch_model(SpecialStitchModel::default()),
+    Err(BuilderError::TooManySpecialStitchModels)
+  );
+}
@@ -0,0 +1,210 @@
+//! Thread color catalogs.
+//!
+//! Each catalog maps the thread numbers of a single brand to their names and colors.
+//! The brand names match the ones stored in the Pattern Maker files.
+//!
+//! Only the DMC and Mill Hill catalogs are bundled.
+//! The catalogs of the other brands, like Anchor, Madeira, Cosmo, PNK Kirova or Gamma,
+//! are loaded from a file in the same format with `ThreadCatalog::parse` and registered in `Catalogs`,
+//! which all the thread lookups go through.
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use anyhow::Result;
+
+use crate::schemas::color::Rgb;
+use crate::schemas::xsd::{Blend, PaletteItem};
+
+#[cfg(test)]
+#[path = "catalogs.test.rs"]
+mod tests;
+
+static THREAD_CATALOGS: LazyLock<Vec<ThreadCatalog>> = LazyLock::new(|| {
+  [
+    ("DMC", include_str!("../resources/catalogs/dmc.txt")),
+    (
+      "Mill Hill Glass Seed Bead",
+      include_str!("../resources/catalogs/mill_hill_glass_seed_beads.txt"),
+    ),
+    (
+      "Mill Hill Frosted Glass Seed Bead",
+      include_str!("../resources/catalogs/mill_hill_frosted_glass_seed_beads.txt"),
+    ),
+  ]
+  .into_iter()
+  .map(|(brand, content)| ThreadCatalog::parse(brand, content).unwrap())
+  .collect()
+});
+
+/// A thread color catalog of a single brand.
+#[derive(Debug, PartialEq)]
+pub struct ThreadCatalog {
+  brand: String,
+  threads: Vec<CatalogThread>,
+  index: HashMap<String, usize>,
+}
+
+/// A single thread of a catalog.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CatalogThread {
+  pub number: String,
+  pub name: String,
+  pub color: Rgb,
+}
+
+impl ThreadCatalog {
+  /// Parses a catalog from its textual representation.
+  ///
+  /// Every line of the catalog is in the `number : color : name` format.
+  /// Blank lines and lines starting with `#` are ignored.
+  pub fn parse(brand: &str, content: &str) -> Result<Self> {
+    let mut threads = Vec::new();
+    let mut index = HashMap::new();
+
+    for (i, line) in content.lines().enumerate() {
+      let line = line.trim();
+      if line.is_empty() || line.starts_with('#') {
+        continue;
+      }
+
+      let mut parts = line.splitn(3, ':').map(|part| part.trim());
+      let (Some(number), Some(color), Some(name)) = (parts.next(), parts.next(), parts.next()) else {
+        anyhow::bail!("Invalid entry of the {brand} catalog at line {}: {line:?}", i + 1);
+      };
+      let Ok(color) = Rgb::from_hex(color) else {
+        anyhow::bail!("Invalid color of the {brand} catalog at line {}: {color:?}", i + 1);
+      };
+
+      index.insert(normalize_thread_number(number), threads.len());
+      threads.push(CatalogThread {
+        number: number.to_owned(),
+        name: name.to_owned(),
+        color,
+      });
+    }
+
+    Ok(ThreadCatalog {
+      brand: brand.to_owned(),
+      threads,
+      index,
+    })
+  }
+
+  pub fn brand(&self) -> &str {
+    &self.brand
+  }
+
+  pub fn threads(&self) -> &[CatalogThread] {
+    &self.threads
+  }
+
+  /// Looks up a thread by its number.
+  /// Numbers are compared case-insensitively and without leading zeros, so `0022` matches `22`.
+  pub fn get(&self, number: &str) -> Option<&CatalogThread> {
+    self
+      .index
+      .get(&normalize_thread_number(number))
+      .map(|&index| &self.threads[index])
+  }
+}
+
+/// Normalizes a thread number for comparison.
+fn normalize_thread_number(number: &str) -> String {
+  let number = number.trim();
+  if !number.is_empty() && number.bytes().all(|byte| byte.is_ascii_digit()) {
+    let number = number.trim_start_matches('0');
+    return if number.is_empty() {
+      String::from("0")
+    } else {
+      number.to_owned()
+    };
+  }
+  number.to_lowercase()
+}
+
+/// The thread catalogs that the threads are looked up in.
+///
+/// It holds the bundled catalogs and the ones registered with `with`.
+/// A registered catalog takes precedence over a bundled catalog of the same brand.
+#[derive(Debug, Default)]
+pub struct Catalogs {
+  registered: Vec<ThreadCatalog>,
+}
+
+impl Catalogs {
+  /// Returns the registry of the bundled catalogs.
+  pub fn bundled() -> Self {
+    Self::default()
+  }
+
+  /// Registers a catalog, replacing the registered catalog of the same brand.
+  pub fn with(mut self, catalog: ThreadCatalog) -> Self {
+    self
+      .registered
+      .retain(|registered| !registered.brand.eq_ignore_ascii_case(&catalog.brand));
+    self.registered.push(catalog);
+    self
+  }
+
+  /// Returns all the catalogs, the registered ones first.
+  pub fn catalogs(&self) -> impl Iterator<Item = &ThreadCatalog> {
+    let bundled = THREAD_CATALOGS.iter().filter(|catalog| {
+      !self
+        .registered
+        .iter()
+        .any(|registered| registered.brand.eq_ignore_ascii_case(&catalog.brand))
+    });
+    self.registered.iter().chain(bundled)
+  }
+
+  /// Returns the catalog of the brand, if there is one.
+  pub fn catalog(&self, brand: &str) -> Option<&ThreadCatalog> {
+    self
+      .catalogs()
+      .find(|catalog| catalog.brand.eq_ignore_ascii_case(brand.trim()))
+  }
+
+  /// Looks up a thread in the catalog of its brand.
+  pub fn find_thread(&self, brand: &str, number: &str) -> Option<&CatalogThread> {
+    self.catalog(brand)?.get(number)
+  }
+}
+
+impl PaletteItem {
+  /// Returns the catalog entry of the palette item, if its brand has a catalog.
+  pub fn catalog_thread<'a>(&self, catalogs: &'a Catalogs) -> Option<&'a CatalogThread> {
+    catalogs.find_thread(&self.brand, &self.number)
+  }
+
+  /// Returns the catalog entries of the blend components.
+  /// Components that are missing in the catalogs are `None`.
+  pub fn blend_threads<'a>(&self, catalogs: &'a Catalogs) -> Option<Vec<Option<&'a CatalogThread>>> {
+    self
+      .blends
+      .as_ref()
+      .map(|blends| blends.iter().map(|blend| blend.catalog_thread(catalogs)).collect())
+  }
+
+  /// Fills in the missing name of the palette item from the catalogs.
+  /// Returns `true` if the name was filled in.
+  pub fn fill_name_from_catalog(&mut self, catalogs: &Catalogs) -> bool {
+    if !self.name.is_empty() {
+      return false;
+    }
+    match self.catalog_thread(catalogs) {
+      Some(thread) => {
+        self.name = thread.name.clone();
+        true
+      }
+      None => false,
+    }
+  }
+}
+
+impl Blend {
+  /// Returns the catalog entry of the blend component, which holds its real color.
+  pub fn catalog_thread<'a>(&self, catalogs: &'a Catalogs) -> Option<&'a CatalogThread> {
+    catalogs.find_thread(&self.brand, &self.number)
+  }
+}
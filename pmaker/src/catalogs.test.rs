@@ -0,0 +1,138 @@
+use super::*;
+
+#[test]
+fn parses_catalog() {
+  let catalog = ThreadCatalog::parse(
+    "DMC",
+    "# A comment.\n310   : 000000 : Black\n\nB5200 : ffffff : Snow White\n",
+  )
+  .unwrap();
+  assert_eq!(catalog.brand(), "DMC");
+  assert_eq!(
+    catalog.threads(),
+    [
+      CatalogThread {
+        number: String::from("310"),
+        name: String::from("Black"),
+        color: Rgb::new(0x00, 0x00, 0x00),
+      },
+      CatalogThread {
+        number: String::from("B5200"),
+        name: String::from("Snow White"),
+        color: Rgb::new(0xFF, 0xFF, 0xFF),
+      },
+    ]
+  );
+
+  assert!(ThreadCatalog::parse("DMC", "310 : 000000").is_err());
+  assert!(ThreadCatalog::parse("DMC", "310 : black : Black").is_err());
+}
+
+#[test]
+fn loads_bundled_catalogs() {
+  let catalogs = Catalogs::bundled();
+  for catalog in catalogs.catalogs() {
+    assert!(
+      !catalog.threads().is_empty(),
+      "The {} catalog is empty",
+      catalog.brand()
+    );
+  }
+  for brand in ["DMC", "Mill Hill Glass Seed Bead", "Mill Hill Frosted Glass Seed Bead"] {
+    assert!(catalogs.catalog(brand).is_some(), "The {brand} catalog is missing");
+  }
+}
+
+#[test]
+fn looks_up_threads() {
+  let catalogs = Catalogs::bundled();
+  let black = catalogs.find_thread("DMC", "310").unwrap();
+  assert_eq!(black.name, "Black");
+  assert_eq!(black.color, Rgb::BLACK);
+
+  assert_eq!(catalogs.find_thread("dmc", "b5200").unwrap().number, "B5200");
+  assert_eq!(catalogs.find_thread("DMC", "0022").unwrap().number, "22");
+  assert_eq!(
+    catalogs.find_thread("Mill Hill Glass Seed Bead", "968").unwrap().name,
+    "Red"
+  );
+
+  assert!(catalogs.find_thread("DMC", "9999").is_none());
+  assert!(catalogs.find_thread("Unknown Brand", "310").is_none());
+}
+
+#[test]
+fn resolves_palette_items() {
+  let catalogs = Catalogs::bundled();
+  let mut palette_item = PaletteItem {
+    brand: String::from("Blend"),
+    number: String::from("11"),
+    name: String::from(""),
+    color: Rgb::new(0x93, 0xD0, 0xD3),
+    blends: Some(vec![
+      Blend {
+        brand: String::from("DMC"),
+        number: String::from("964"),
+        strands: 1,
+      },
+      Blend {
+        brand: String::from("DMC"),
+        number: String::from("3766"),
+        strands: 1,
+      },
+    ]),
+    bead: None,
+    strands: None,
+  };
+  let colors: Vec<_> = palette_item
+    .blend_threads(&catalogs)
+    .unwrap()
+    .into_iter()
+    .map(|thread| thread.unwrap().color)
+    .collect();
+  assert_eq!(colors, [Rgb::new(0xA9, 0xE2, 0xD8), Rgb::new(0x99, 0xCF, 0xD9)]);
+  assert!(!palette_item.fill_name_from_catalog(&catalogs));
+
+  palette_item.brand = String::from("DMC");
+  palette_item.number = String::from("964");
+  palette_item.blends = None;
+  assert_eq!(palette_item.blend_threads(&catalogs), None);
+  assert!(palette_item.fill_name_from_catalog(&catalogs));
+  assert_eq!(palette_item.name, "Sea Green Light");
+  assert!(!palette_item.fill_name_from_catalog(&catalogs));
+}
+
+#[test]
+fn looks_up_registered_catalogs() {
+  let anchor = ThreadCatalog::parse("Anchor", "403 : 000000 : Black\n").unwrap();
+  let dmc = ThreadCatalog::parse("DMC", "310 : 010101 : Black\n").unwrap();
+  let catalogs = Catalogs::bundled().with(anchor).with(dmc);
+
+  assert_eq!(catalogs.find_thread("anchor", "0403").unwrap().color, Rgb::BLACK);
+  assert_eq!(
+    catalogs.find_thread("DMC", "310").unwrap().color,
+    Rgb::new(0x01, 0x01, 0x01)
+  );
+  assert!(catalogs.find_thread("DMC", "B5200").is_none());
+  assert!(catalogs.catalog("Mill Hill Glass Seed Bead").is_some());
+  assert_eq!(
+    catalogs.catalogs().filter(|catalog| catalog.brand() == "DMC").count(),
+    1
+  );
+
+  let mut palette_item = PaletteItem {
+    blends: Some(vec![Blend {
+      brand: String::from("Anchor"),
+      number: String::from("403"),
+      strands: 2,
+    }]),
+    ..PaletteItem::new("Anchor", "403", Rgb::BLACK)
+  };
+  assert_eq!(
+    palette_item.blend_threads(&catalogs),
+    Some(vec![catalogs.find_thread("Anchor", "403")])
+  );
+  assert!(palette_item.fill_name_from_catalog(&catalogs));
+  assert_eq!(palette_item.name, "Black");
+  assert!(palette_item.catalog_thread(&Catalogs::bundled()).is_none());
+}
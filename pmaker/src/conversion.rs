@@ -0,0 +1,236 @@
+//! Cross-brand conversion of palettes.
+//!
+//! Every thread is mapped to the perceptually nearest thread of the target catalog.
+//! The distance between colors is measured with the CIEDE2000 formula in the CIELAB color space.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+
+use crate::catalogs::{CatalogThread, Catalogs, ThreadCatalog};
+use crate::schemas::color::{Lab, Rgb};
+use crate::schemas::xsd::{Blend, PaletteItem};
+
+#[cfg(test)]
+#[path = "conversion.test.rs"]
+mod tests;
+
+/// A palette converted to another brand.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaletteConversion {
+  pub palette: Vec<PaletteItem>,
+  pub report: ConversionReport,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConversionReport {
+  pub matches: Vec<ThreadMatch>,
+  pub collisions: Vec<ThreadCollision>,
+  pub skipped: Vec<SkippedThread>,
+}
+
+/// A thread that was mapped to a thread of the target catalog.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThreadMatch {
+  pub palindex: usize,
+  /// The index of the blend component, if the thread is a part of a blend.
+  pub blend_index: Option<usize>,
+  pub source_brand: String,
+  pub source_number: String,
+  pub target_number: String,
+  /// The CIEDE2000 color difference between the source and the target threads.
+  pub delta_e: f32,
+}
+
+/// Several palette items that were converted to the same thread or blend.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThreadCollision {
+  pub brand: String,
+  pub number: String,
+  pub palindexes: Vec<usize>,
+}
+
+/// A thread that was left as is.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkippedThread {
+  pub palindex: usize,
+  pub blend_index: Option<usize>,
+  pub reason: SkipReason,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SkipReason {
+  /// Beads are not converted to threads.
+  Bead,
+  /// The color of a blend component is missing in the catalogs.
+  UnknownColor,
+}
+
+/// Converts the palette to the brand of the target catalog.
+///
+/// The source threads are looked up in the `catalogs`, so the catalogs of the other brands must be registered there.
+/// Beads and the blend components of unknown colors are left as is and reported as skipped.
+pub fn convert_palette(
+  palette: &[PaletteItem],
+  target: &ThreadCatalog,
+  catalogs: &Catalogs,
+) -> Result<PaletteConversion> {
+  if target.threads().is_empty() {
+    anyhow::bail!("The {} catalog is empty", target.brand());
+  }
+
+  let target_colors: Vec<Lab> = target.threads().iter().map(|thread| thread.color.to_lab()).collect();
+  let find_nearest = |color: Rgb| -> (&CatalogThread, f32) {
+    let color = color.to_lab();
+    target
+      .threads()
+      .iter()
+      .zip(target_colors.iter())
+      .map(|(thread, target_color)| (thread, color.delta_e(target_color)))
+      .min_by(|(_, a), (_, b)| a.total_cmp(b))
+      .unwrap()
+  };
+
+  let mut report = ConversionReport::default();
+  let mut converted_palette = Vec::with_capacity(palette.len());
+
+  for (palindex, palitem) in palette.iter().enumerate() {
+    if palitem.bead.is_some() {
+      report.skipped.push(SkippedThread {
+        palindex,
+        blend_index: None,
+        reason: SkipReason::Bead,
+      });
+      converted_palette.push(palitem.clone());
+      continue;
+    }
+
+    if let Some(blends) = &palitem.blends {
+      let mut converted_blends = Vec::with_capacity(blends.len());
+      let mut blend_colors = Vec::with_capacity(blends.len());
+
+      for (blend_index, blend) in blends.iter().enumerate() {
+        let Some(source) = blend.catalog_thread(catalogs) else {
+          report.skipped.push(SkippedThread {
+            palindex,
+            blend_index: Some(blend_index),
+            reason: SkipReason::UnknownColor,
+          });
+          converted_blends.push(blend.clone());
+          continue;
+        };
+
+        let (thread, delta_e) = find_same_thread(target, &blend.brand, &blend.number)
+          .map(|thread| (thread, 0.0))
+          .unwrap_or_else(|| find_nearest(source.color));
+        report.matches.push(ThreadMatch {
+          palindex,
+          blend_index: Some(blend_index),
+          source_brand: blend.brand.clone(),
+          source_number: blend.number.clone(),
+          target_number: thread.number.clone(),
+          delta_e,
+        });
+        converted_blends.push(Blend {
+          brand: target.brand().to_owned(),
+          number: thread.number.clone(),
+          strands: blend.strands,
+        });
+        blend_colors.push((thread.color, blend.strands.max(1) as u32));
+      }
+
+      converted_palette.push(PaletteItem {
+        color: if blend_colors.len() == blends.len() {
+          Rgb::mix(blend_colors).unwrap_or(palitem.color)
+        } else {
+          palitem.color
+        },
+        blends: Some(converted_blends),
+        ..palitem.clone()
+      });
+      continue;
+    }
+
+    if let Some(thread) = find_same_thread(target, &palitem.brand, &palitem.number) {
+      // The thread already belongs to the target brand, so it is kept as is.
+      report.matches.push(ThreadMatch {
+        palindex,
+        blend_index: None,
+        source_brand: palitem.brand.clone(),
+        source_number: palitem.number.clone(),
+        target_number: thread.number.clone(),
+        delta_e: 0.0,
+      });
+      converted_palette.push(palitem.clone());
+      continue;
+    }
+
+    // Prefer the catalog color of the source thread over the color stored in the pattern.
+    let source_color = palitem
+      .catalog_thread(catalogs)
+      .map_or(palitem.color, |thread| thread.color);
+    let (thread, delta_e) = find_nearest(source_color);
+    report.matches.push(ThreadMatch {
+      palindex,
+      blend_index: None,
+      source_brand: palitem.brand.clone(),
+      source_number: palitem.number.clone(),
+      target_number: thread.number.clone(),
+      delta_e,
+    });
+    converted_palette.push(PaletteItem {
+      brand: target.brand().to_owned(),
+      number: thread.number.clone(),
+      name: thread.name.clone(),
+      color: thread.color,
+      ..palitem.clone()
+    });
+  }
+
+  report.collisions = find_collisions(&converted_palette);
+
+  Ok(PaletteConversion {
+    palette: converted_palette,
+    report,
+  })
+}
+
+/// Returns the thread itself if it already belongs to the target catalog.
+fn find_same_thread<'a>(target: &'a ThreadCatalog, brand: &str, number: &str) -> Option<&'a CatalogThread> {
+  if brand.eq_ignore_ascii_case(target.brand()) {
+    target.get(number)
+  } else {
+    None
+  }
+}
+
+/// Finds the palette items that are the same thread or blend.
+fn find_collisions(palette: &[PaletteItem]) -> Vec<ThreadCollision> {
+  let mut groups: HashMap<(String, String), Vec<usize>> = HashMap::new();
+  for (palindex, palitem) in palette.iter().enumerate() {
+    let number = match &palitem.blends {
+      Some(blends) => blends
+        .iter()
+        .map(|blend| format!("{} {}x{}", blend.brand, blend.number, blend.strands))
+        .collect::<Vec<_>>()
+        .join(" + "),
+      None => palitem.number.clone(),
+    };
+    groups
+      .entry((palitem.brand.clone(), number))
+      .or_default()
+      .push(palindex);
+  }
+
+  let mut collisions: Vec<ThreadCollision> = groups
+    .into_iter()
+    .filter(|(_, palindexes)| palindexes.len() > 1)
+    .map(|((brand, number), palindexes)| ThreadCollision {
+      brand,
+      number,
+      palindexes,
+    })
+    .collect();
+  collisions.sort_by_key(|collision| collision.palindexes[0]);
+  collisions
+}
@@ -0,0 +1,165 @@
+use super::*;
+use crate::schemas::xsd::Bead;
+
+/// A catalog of another brand loaded by the user.
+fn target_catalog() -> ThreadCatalog {
+  ThreadCatalog::parse("Anchor", "1 : FFFFFF : White\n403 : 000000 : Black\n").unwrap()
+}
+
+#[test]
+fn converts_palette_to_another_brand() {
+  let palette = [
+    PaletteItem::new("DMC", "310", Rgb::new(0x2C, 0x32, 0x25)),
+    PaletteItem::new("Anchor", "403", Rgb::new(0x00, 0x00, 0x00)),
+    PaletteItem::new("Custom Thread", "1", Rgb::new(0xFF, 0x00, 0x00)),
+    PaletteItem {
+      bead: Some(Bead {
+        length: 2.5,
+        diameter: 1.5,
+      }),
+      ..PaletteItem::new("Mill Hill Glass Seed Bead", "00968", Rgb::new(0xC7, 0x47, 0x61))
+    },
+  ];
+  let conversion = convert_palette(&palette, &target_catalog(), &Catalogs::bundled()).unwrap();
+
+  assert_eq!(conversion.palette[0].brand, "Anchor");
+  assert_eq!(conversion.palette[0].number, "403");
+  assert_eq!(conversion.palette[0].color, Rgb::BLACK);
+  assert_eq!(conversion.palette[1], palette[1]);
+  assert_eq!(conversion.palette[2].brand, "Anchor");
+  assert_eq!(conversion.palette[3], palette[3]);
+
+  let report = conversion.report;
+  assert_eq!(report.matches.len(), 3);
+  assert_eq!(report.matches[0].delta_e, 0.0);
+  assert_eq!(report.matches[1].delta_e, 0.0);
+  assert!(report.matches[2].delta_e > 0.0);
+  assert_eq!(
+    report.collisions,
+    [ThreadCollision {
+      brand: String::from("Anchor"),
+      number: String::from("403"),
+      palindexes: vec![0, 1],
+    }]
+  );
+  assert_eq!(
+    report.skipped,
+    [SkippedThread {
+      palindex: 3,
+      blend_index: None,
+      reason: SkipReason::Bead,
+    }]
+  );
+}
+
+#[test]
+fn converts_blend_components() {
+  let palette = [PaletteItem {
+    blends: Some(vec![
+      Blend {
+        brand: String::from("DMC"),
+        number: String::from("310"),
+        strands: 1,
+      },
+      Blend {
+        brand: String::from("DMC"),
+        number: String::from("B5200"),
+        strands: 1,
+      },
+      Blend {
+        brand: String::from("Custom Thread"),
+        number: String::from("1"),
+        strands: 1,
+      },
+    ]),
+    ..PaletteItem::new("Blend", "1", Rgb::new(0x80, 0x80, 0x80))
+  }];
+  let conversion = convert_palette(&palette, &target_catalog(), &Catalogs::bundled()).unwrap();
+
+  let blends = conversion.palette[0].blends.as_ref().unwrap();
+  assert_eq!(
+    blends
+      .iter()
+      .map(|blend| (blend.brand.as_str(), blend.number.as_str()))
+      .collect::<Vec<_>>(),
+    [("Anchor", "403"), ("Anchor", "1"), ("Custom Thread", "1")]
+  );
+  assert_eq!(conversion.palette[0].color, Rgb::new(0x80, 0x80, 0x80));
+  assert_eq!(conversion.report.matches.len(), 2);
+  assert_eq!(
+    conversion.report.skipped,
+    [SkippedThread {
+      palindex: 0,
+      blend_index: Some(2),
+      reason: SkipReason::UnknownColor,
+    }]
+  );
+}
+
+#[test]
+fn resolves_source_threads_in_registered_catalogs() {
+  let madeira = ThreadCatalog::parse("Madeira", "2400 : FFFFFF : White\n").unwrap();
+  let catalogs = Catalogs::bundled().with(madeira);
+  // The stored color is off, so only the catalog color matches the white thread.
+  let palette = [PaletteItem::new("Madeira", "2400", Rgb::new(0x20, 0x20, 0x20))];
+
+  let conversion = convert_palette(&palette, &target_catalog(), &catalogs).unwrap();
+  assert_eq!(conversion.palette[0].number, "1");
+  assert_eq!(conversion.report.matches[0].delta_e, 0.0);
+
+  let conversion = convert_palette(&palette, &target_catalog(), &Catalogs::bundled()).unwrap();
+  assert_eq!(conversion.palette[0].number, "403");
+}
+
+#[cfg(feature = "xsd")]
+#[test]
+fn converts_xsd_patterns() {
+  let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata/patterns/piggies.xsd");
+  let mut pattern = crate::parse_xsd_pattern(path).unwrap();
+  pattern.palette.push(PaletteItem {
+    blends: Some(vec![
+      Blend {
+        brand: String::from("DMC"),
+        number: String::from("310"),
+        strands: 1,
+      },
+      Blend {
+        brand: String::from("Madeira"),
+        number: String::from("2400"),
+        strands: 1,
+      },
+    ]),
+    ..PaletteItem::new("Blend", "1", Rgb::new(0x80, 0x80, 0x80))
+  });
+  let madeira = ThreadCatalog::parse("Madeira", "2400 : FFFFFF : White\n").unwrap();
+  let catalogs = Catalogs::bundled().with(madeira);
+
+  let conversion = convert_palette(&pattern.palette, &target_catalog(), &catalogs).unwrap();
+  assert_eq!(conversion.palette.len(), pattern.palette.len());
+  assert!(conversion.palette[..7].iter().all(|palitem| palitem.brand == "Anchor"));
+  assert_eq!(conversion.palette[2].number, "403");
+  assert_eq!(conversion.palette[7], pattern.palette[7]);
+  let blends = conversion.palette[8].blends.as_ref().unwrap();
+  assert_eq!(
+    blends
+      .iter()
+      .map(|blend| (blend.brand.as_str(), blend.number.as_str()))
+      .collect::<Vec<_>>(),
+    [("Anchor", "403"), ("Anchor", "1")]
+  );
+  assert_eq!(conversion.report.matches.len(), 9);
+  assert_eq!(
+    conversion.report.skipped,
+    [SkippedThread {
+      palindex: 7,
+      blend_index: None,
+      reason: SkipReason::Bead,
+    }]
+  );
+}
+
+#[test]
+fn fails_on_empty_catalog() {
+  let catalog = ThreadCatalog::parse("Empty", "").unwrap();
+  assert!(convert_palette(&[], &catalog, &Catalogs::bundled()).is_err());
+}
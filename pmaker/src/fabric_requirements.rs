@@ -0,0 +1,174 @@
+//! The calculation of the fabric that a pattern needs.
+//!
+//! The design size is the stitched area of the pattern, and the lengths are in inches.
+
+use crate::schemas::xsd::{Fabric, Pattern};
+use crate::statistics::DesignSize;
+use crate::units::CENTIMETERS_PER_INCH;
+
+#[cfg(test)]
+#[path = "fabric_requirements.test.rs"]
+mod tests;
+
+/// A fabric count that the design can be stitched on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FabricCount {
+  pub kind: String,
+  /// The number of holes or threads per inch of each axis.
+  pub count: (u8, u8),
+  /// The number of threads that a stitch is worked over.
+  pub over: u8,
+}
+
+impl FabricCount {
+  /// An Aida fabric, worked over one block.
+  pub fn aida(count: u8) -> Self {
+    FabricCount {
+      kind: String::from("Aida"),
+      count: (count, count),
+      over: 1,
+    }
+  }
+
+  /// An evenweave fabric, worked over two threads.
+  pub fn evenweave(count: u8) -> Self {
+    FabricCount {
+      kind: String::from("Evenweave"),
+      count: (count, count),
+      over: 2,
+    }
+  }
+
+  /// Returns the number of stitches per inch of each axis.
+  pub fn stitches_per_inch(&self) -> (f32, f32) {
+    let over = f32::from(self.over.max(1));
+    (
+      f32::from(self.count.0.max(1)) / over,
+      f32::from(self.count.1.max(1)) / over,
+    )
+  }
+}
+
+impl From<&Fabric> for FabricCount {
+  fn from(fabric: &Fabric) -> Self {
+    FabricCount {
+      kind: fabric.kind.clone(),
+      count: fabric.stitches_per_inch,
+      over: 1,
+    }
+  }
+}
+
+/// Returns the commonly used fabric counts: Aida 11, 14, 16 and 18, and evenweave 28 and 32 over two.
+pub fn standard_fabric_counts() -> Vec<FabricCount> {
+  vec![
+    FabricCount::aida(11),
+    FabricCount::aida(14),
+    FabricCount::aida(16),
+    FabricCount::aida(18),
+    FabricCount::evenweave(28),
+    FabricCount::evenweave(32),
+  ]
+}
+
+/// A precut piece of fabric.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FabricPiece {
+  pub name: String,
+  /// The width and the height in inches.
+  pub size: (f32, f32),
+}
+
+impl FabricPiece {
+  pub fn new(name: &str, width: f32, height: f32) -> Self {
+    FabricPiece {
+      name: name.to_string(),
+      size: (width, height),
+    }
+  }
+
+  /// Returns whether a cut of the given size fits the piece in any orientation.
+  pub fn fits(&self, cut_size: (f32, f32)) -> bool {
+    let (width, height) = self.size;
+    (cut_size.0 <= width && cut_size.1 <= height) || (cut_size.0 <= height && cut_size.1 <= width)
+  }
+
+  fn area(&self) -> f32 {
+    self.size.0 * self.size.1
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FabricRequirementOptions {
+  /// The fabric that is left around the design on each side for framing, in inches.
+  pub margin: f32,
+  /// The precut pieces to check the cut size against.
+  pub pieces: Vec<FabricPiece>,
+}
+
+impl Default for FabricRequirementOptions {
+  fn default() -> Self {
+    FabricRequirementOptions {
+      margin: 3.0,
+      pieces: vec![
+        FabricPiece::new("12 x 18 in", 12.0, 18.0),
+        FabricPiece::new("15 x 18 in", 15.0, 18.0),
+        FabricPiece::new("Fat quarter", 18.0, 21.0),
+        FabricPiece::new("Half yard", 18.0, 43.0),
+      ],
+    }
+  }
+}
+
+/// The fabric that a pattern needs when it is stitched on a fabric count.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FabricRequirement {
+  pub fabric: FabricCount,
+  pub design_size: DesignSize,
+  /// The size of the fabric to cut including the margins, in inches.
+  pub cut_size: (f32, f32),
+  pub cut_size_in_centimeters: (f32, f32),
+  /// The pieces that the cut fits in, from the smallest one.
+  pub fitting_pieces: Vec<FabricPiece>,
+}
+
+/// Computes the fabric requirements of the pattern for each fabric count.
+/// If no fabric counts are given, the fabric of the pattern is used.
+pub fn fabric_requirements(
+  pattern: &Pattern,
+  fabrics: &[FabricCount],
+  options: &FabricRequirementOptions,
+) -> Vec<FabricRequirement> {
+  let stitches = pattern.statistics().design_size.stitches;
+  let pattern_fabric = [FabricCount::from(&pattern.fabric)];
+  let fabrics = if fabrics.is_empty() {
+    &pattern_fabric[..]
+  } else {
+    fabrics
+  };
+
+  fabrics
+    .iter()
+    .map(|fabric| {
+      let design_size = DesignSize::new(stitches, fabric.stitches_per_inch());
+      let margin = options.margin.max(0.0);
+      let cut_size = (design_size.inches.0 + 2.0 * margin, design_size.inches.1 + 2.0 * margin);
+
+      let mut fitting_pieces: Vec<_> = options
+        .pieces
+        .iter()
+        .filter(|piece| piece.fits(cut_size))
+        .cloned()
+        .collect();
+      fitting_pieces.sort_by(|a, b| a.area().total_cmp(&b.area()));
+
+      FabricRequirement {
+        fabric: fabric.clone(),
+        design_size,
+        cut_size,
+        cut_size_in_centimeters: (cut_size.0 * CENTIMETERS_PER_INCH, cut_size.1 * CENTIMETERS_PER_INCH),
+        fitting_pieces,
+      }
+    })
+    .collect()
+}
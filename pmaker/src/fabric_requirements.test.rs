@@ -0,0 +1,86 @@
+use super::*;
+use crate::builder::PatternBuilder;
+use crate::schemas::color::Rgb;
+use crate::schemas::coords::Coord;
+use crate::schemas::xsd::*;
+
+/// Returns a pattern with the stitched area of 140x70 cells.
+fn sample_pattern() -> Pattern {
+  let mut builder = PatternBuilder::new(200, 100);
+  let palindex = builder
+    .add_palette_item(PaletteItem::new("DMC", "310", Rgb::BLACK))
+    .unwrap();
+  for (x, y) in [(10, 20), (149, 89)] {
+    builder.add_fullstitch(FullStitch {
+      x: Coord::from_cells(x),
+      y: Coord::from_cells(y),
+      palindex,
+      kind: FullStitchKind::Full,
+    });
+  }
+  builder.build().unwrap()
+}
+
+fn assert_close(actual: (f32, f32), expected: (f32, f32)) {
+  assert!(
+    (actual.0 - expected.0).abs() < 1e-4 && (actual.1 - expected.1).abs() < 1e-4,
+    "{actual:?} != {expected:?}"
+  );
+}
+
+#[test]
+fn computes_requirements_of_standard_fabrics() {
+  let requirements = fabric_requirements(
+    &sample_pattern(),
+    &standard_fabric_counts(),
+    &FabricRequirementOptions::default(),
+  );
+  assert_eq!(requirements.len(), 6);
+
+  let aida_14 = &requirements[1];
+  assert_eq!(aida_14.fabric, FabricCount::aida(14));
+  assert_eq!(aida_14.design_size.stitches, (140, 70));
+  assert_close(aida_14.design_size.inches, (10.0, 5.0));
+  assert_close(aida_14.cut_size, (16.0, 11.0));
+  assert_close(aida_14.cut_size_in_centimeters, (16.0 * 2.54, 11.0 * 2.54));
+  // The cut fits the smallest piece turned sideways.
+  let names: Vec<_> = aida_14.fitting_pieces.iter().map(|piece| piece.name.as_str()).collect();
+  assert_eq!(names, ["12 x 18 in", "15 x 18 in", "Fat quarter", "Half yard"]);
+
+  // The 11-count fabric needs a larger piece.
+  let aida_11 = &requirements[0];
+  assert_close(aida_11.cut_size, (140.0 / 11.0 + 6.0, 70.0 / 11.0 + 6.0));
+  assert_eq!(aida_11.fitting_pieces[0].name, "Fat quarter");
+
+  // The 28-count evenweave over two is the same as the 14-count Aida.
+  let evenweave_28 = &requirements[4];
+  assert_eq!(evenweave_28.fabric.kind, "Evenweave");
+  assert_close(evenweave_28.cut_size, aida_14.cut_size);
+}
+
+#[test]
+fn defaults_to_pattern_fabric() {
+  let mut pattern = sample_pattern();
+  pattern.fabric.kind = String::from("Linen");
+  pattern.fabric.stitches_per_inch = (20, 10);
+  let options = FabricRequirementOptions {
+    margin: 1.0,
+    pieces: vec![FabricPiece::new("Small", 10.0, 10.0)],
+  };
+  let requirements = fabric_requirements(&pattern, &[], &options);
+  assert_eq!(requirements.len(), 1);
+  assert_eq!(
+    requirements[0].fabric,
+    FabricCount {
+      kind: String::from("Linen"),
+      count: (20, 10),
+      over: 1,
+    }
+  );
+  assert_close(requirements[0].design_size.inches, (7.0, 7.0));
+  assert_close(requirements[0].cut_size, (9.0, 9.0));
+  assert_eq!(requirements[0].fitting_pieces.len(), 1);
+
+  let requirements = fabric_requirements(&pattern, &[FabricCount::aida(11)], &options);
+  assert!(requirements[0].fitting_pieces.is_empty());
+}
@@ -0,0 +1,414 @@
+//! Conversion of raster images into patterns.
+//!
+//! The image is scaled to the size of the pattern, so every pixel becomes a full stitch.
+//! Its colors are quantized first, then every color of the reduced palette is replaced with the perceptually nearest thread of the catalog.
+//! Transparent pixels are left unstitched.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use image::RgbaImage;
+use image::imageops::FilterType;
+
+use crate::builder::PatternBuilder;
+use crate::catalogs::{CatalogThread, ThreadCatalog};
+use crate::schemas::color::{Lab, Rgb};
+use crate::schemas::coords::Coord;
+use crate::schemas::xsd::*;
+use crate::validation::MAX_PALETTE_SIZE;
+
+#[cfg(test)]
+#[path = "image_conversion.test.rs"]
+mod tests;
+
+/// Pixels with a lower alpha are considered transparent.
+const ALPHA_THRESHOLD: u8 = 128;
+
+/// The maximum number of the k-means iterations.
+const KMEANS_ITERATIONS: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quantization {
+  /// Clusters the colors with the k-means algorithm in the CIELAB color space.
+  KMeans,
+  /// Splits the RGB color cube at the median of its widest channel until there are enough boxes.
+  MedianCut,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dithering {
+  None,
+  /// Diffuses the quantization error to the neighbouring stitches.
+  FloydSteinberg,
+}
+
+#[derive(Debug, Clone)]
+pub struct ImageConversionOptions<'a> {
+  /// The catalog whose threads make up the palette.
+  pub catalog: &'a ThreadCatalog,
+  /// The width of the pattern in stitches.
+  pub width: u16,
+  /// The height of the pattern in stitches.
+  /// If it is not set, it is computed to keep the aspect ratio of the image.
+  pub height: Option<u16>,
+  /// The maximum number of colors in the palette.
+  pub max_colors: usize,
+  pub quantization: Quantization,
+  pub dithering: Dithering,
+}
+
+impl<'a> ImageConversionOptions<'a> {
+  /// Creates the options to convert an image into a pattern of the given width, keeping the aspect ratio of the image.
+  pub fn new(catalog: &'a ThreadCatalog, width: u16) -> Self {
+    ImageConversionOptions {
+      catalog,
+      width,
+      height: None,
+      max_colors: 24,
+      quantization: Quantization::KMeans,
+      dithering: Dithering::None,
+    }
+  }
+}
+
+/// Decodes a PNG or JPEG image and converts it into a pattern.
+pub fn convert_image(buf: &[u8], options: &ImageConversionOptions) -> Result<Pattern> {
+  let image = image::load_from_memory(buf)?;
+  convert_rgba_image(&image.into_rgba8(), options)
+}
+
+/// Converts an image into a pattern of full stitches.
+///
+/// The palette contains only the threads that are actually used, and every palette item gets its own symbol.
+pub fn convert_rgba_image(image: &RgbaImage, options: &ImageConversionOptions) -> Result<Pattern> {
+  if image.width() == 0 || image.height() == 0 {
+    anyhow::bail!("The image is empty");
+  }
+  if options.width == 0 || options.height == Some(0) {
+    anyhow::bail!("The pattern size must be positive");
+  }
+  if options.max_colors == 0 || options.max_colors > MAX_PALETTE_SIZE {
+    anyhow::bail!("The maximum number of colors must be between 1 and {MAX_PALETTE_SIZE}");
+  }
+  if options.catalog.threads().is_empty() {
+    anyhow::bail!("The {} catalog is empty", options.catalog.brand());
+  }
+
+  let width = options.width;
+  let height = options.height.unwrap_or_else(|| {
+    let height = f64::from(image.height()) * f64::from(width) / f64::from(image.width());
+    (height.round() as u16).max(1)
+  });
+
+  let scaled = image::imageops::resize(image, width.into(), height.into(), FilterType::Triangle);
+  let cells: Vec<Option<Rgb>> = scaled
+    .pixels()
+    .map(|pixel| {
+      let [r, g, b, a] = pixel.0;
+      (a >= ALPHA_THRESHOLD).then_some(Rgb::new(r, g, b))
+    })
+    .collect();
+
+  let histogram = {
+    let mut histogram = HashMap::new();
+    for color in cells.iter().flatten() {
+      *histogram.entry(*color).or_insert(0u32) += 1;
+    }
+    // Sort the colors to make the quantization independent of the hash map order.
+    let mut histogram: Vec<(Rgb, u32)> = histogram.into_iter().collect();
+    histogram.sort();
+    histogram
+  };
+
+  let centers = match options.quantization {
+    Quantization::KMeans => kmeans(&histogram, options.max_colors),
+    Quantization::MedianCut => median_cut(&histogram, options.max_colors),
+  };
+  let threads = snap_to_catalog(&centers, options.catalog);
+
+  let mut mapper = ThreadMapper::new(&threads);
+  let palindexes = match options.dithering {
+    Dithering::None => cells
+      .iter()
+      .map(|cell| cell.map(|color| mapper.nearest(color)))
+      .collect(),
+    Dithering::FloydSteinberg => dither(&cells, width.into(), &mut mapper),
+  };
+
+  build_pattern(width, height, &palindexes, &threads, options.catalog.brand())
+}
+
+/// Groups the colors into at most `max_colors` clusters and returns their centers.
+fn kmeans(histogram: &[(Rgb, u32)], max_colors: usize) -> Vec<Lab> {
+  let colors: Vec<(Lab, f32)> = histogram
+    .iter()
+    .map(|(color, count)| (color.to_lab(), *count as f32))
+    .collect();
+  let Some(&(most_frequent, _)) = colors.iter().max_by(|(_, a), (_, b)| a.total_cmp(b)) else {
+    return Vec::new();
+  };
+
+  // Seed the clusters deterministically, picking the color that is the farthest from the chosen centers, weighted by its frequency.
+  let mut centers = vec![most_frequent];
+  while centers.len() < max_colors.min(colors.len()) {
+    let (next, weight) = colors
+      .iter()
+      .map(|(color, count)| (*color, count * nearest_center(&centers, color).1))
+      .max_by(|(_, a), (_, b)| a.total_cmp(b))
+      .unwrap();
+    if weight == 0.0 {
+      break;
+    }
+    centers.push(next);
+  }
+
+  let mut assignments = vec![usize::MAX; colors.len()];
+  for _ in 0..KMEANS_ITERATIONS {
+    let mut changed = false;
+    for (assignment, (color, _)) in assignments.iter_mut().zip(colors.iter()) {
+      let center = nearest_center(&centers, color).0;
+      changed |= *assignment != center;
+      *assignment = center;
+    }
+    if !changed {
+      break;
+    }
+
+    let mut sums = vec![(0.0, 0.0, 0.0, 0.0); centers.len()];
+    for (&assignment, (color, count)) in assignments.iter().zip(colors.iter()) {
+      let sum = &mut sums[assignment];
+      sum.0 += color.l * count;
+      sum.1 += color.a * count;
+      sum.2 += color.b * count;
+      sum.3 += count;
+    }
+    for (center, (l, a, b, weight)) in centers.iter_mut().zip(sums) {
+      if weight > 0.0 {
+        *center = Lab {
+          l: l / weight,
+          a: a / weight,
+          b: b / weight,
+        };
+      }
+    }
+  }
+
+  centers
+}
+
+/// Returns the index of the nearest center and the squared Euclidean distance to it.
+fn nearest_center(centers: &[Lab], color: &Lab) -> (usize, f32) {
+  centers
+    .iter()
+    .map(|center| (center.l - color.l).powi(2) + (center.a - color.a).powi(2) + (center.b - color.b).powi(2))
+    .enumerate()
+    .min_by(|(_, a), (_, b)| a.total_cmp(b))
+    .unwrap()
+}
+
+/// Splits the colors into at most `max_colors` boxes and returns their average colors.
+fn median_cut(histogram: &[(Rgb, u32)], max_colors: usize) -> Vec<Lab> {
+  fn channel(color: &Rgb, channel: usize) -> u8 {
+    [color.r, color.g, color.b][channel]
+  }
+
+  /// Returns the widest channel of the box and its range.
+  fn widest_channel(colors: &[(Rgb, u32)]) -> (usize, u8) {
+    (0..3)
+      .map(|index| {
+        let min = colors.iter().map(|(color, _)| channel(color, index)).min().unwrap();
+        let max = colors.iter().map(|(color, _)| channel(color, index)).max().unwrap();
+        (index, max - min)
+      })
+      .max_by_key(|&(_, range)| range)
+      .unwrap()
+  }
+
+  if histogram.is_empty() {
+    return Vec::new();
+  }
+
+  let mut boxes = vec![histogram.to_vec()];
+  while boxes.len() < max_colors {
+    let Some((index, (widest, _))) = boxes
+      .iter()
+      .map(|colors| widest_channel(colors))
+      .enumerate()
+      .filter(|(_, (_, range))| *range > 0)
+      .max_by_key(|(_, (_, range))| *range)
+    else {
+      break;
+    };
+
+    let mut colors = boxes.swap_remove(index);
+    colors.sort_by_key(|(color, _)| channel(color, widest));
+
+    // Split at the weighted median, keeping both halves non-empty.
+    let total: u32 = colors.iter().map(|(_, count)| count).sum();
+    let mut accumulated = 0;
+    let mut median = colors.len() - 1;
+    for (index, (_, count)) in colors.iter().enumerate() {
+      accumulated += count;
+      if accumulated * 2 >= total {
+        median = index + 1;
+        break;
+      }
+    }
+    let upper = colors.split_off(median.clamp(1, colors.len() - 1));
+    boxes.push(colors);
+    boxes.push(upper);
+  }
+
+  boxes
+    .into_iter()
+    .filter_map(Rgb::mix)
+    .map(|color| color.to_lab())
+    .collect()
+}
+
+/// Replaces every color with the nearest thread of the catalog, dropping the duplicates.
+fn snap_to_catalog<'a>(centers: &[Lab], catalog: &'a ThreadCatalog) -> Vec<&'a CatalogThread> {
+  let thread_colors: Vec<Lab> = catalog.threads().iter().map(|thread| thread.color.to_lab()).collect();
+  let mut threads: Vec<&CatalogThread> = Vec::with_capacity(centers.len());
+  for center in centers {
+    let (thread, _) = catalog
+      .threads()
+      .iter()
+      .zip(thread_colors.iter())
+      .map(|(thread, color)| (thread, center.delta_e(color)))
+      .min_by(|(_, a), (_, b)| a.total_cmp(b))
+      .unwrap();
+    if !threads.iter().any(|known| known.number == thread.number) {
+      threads.push(thread);
+    }
+  }
+  threads
+}
+
+/// Finds the nearest threads of the palette, caching the results.
+struct ThreadMapper<'a> {
+  threads: Vec<(&'a CatalogThread, Lab)>,
+  cache: HashMap<Rgb, usize>,
+}
+
+impl<'a> ThreadMapper<'a> {
+  fn new(threads: &[&'a CatalogThread]) -> Self {
+    ThreadMapper {
+      threads: threads.iter().map(|thread| (*thread, thread.color.to_lab())).collect(),
+      cache: HashMap::new(),
+    }
+  }
+
+  fn nearest(&mut self, color: Rgb) -> usize {
+    *self.cache.entry(color).or_insert_with(|| {
+      let color = color.to_lab();
+      self
+        .threads
+        .iter()
+        .map(|(_, thread_color)| color.delta_e(thread_color))
+        .enumerate()
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .unwrap()
+        .0
+    })
+  }
+
+  fn color(&self, index: usize) -> Rgb {
+    self.threads[index].0.color
+  }
+}
+
+/// Maps the cells to the threads, diffusing the error with the Floyd-Steinberg algorithm.
+/// The error is not diffused into the transparent cells.
+fn dither(cells: &[Option<Rgb>], width: usize, mapper: &mut ThreadMapper) -> Vec<Option<usize>> {
+  let mut buffer: Vec<Option<[f32; 3]>> = cells
+    .iter()
+    .map(|cell| cell.map(|color| [color.r.into(), color.g.into(), color.b.into()]))
+    .collect();
+  let mut palindexes = vec![None; cells.len()];
+
+  for index in 0..buffer.len() {
+    let Some(value) = buffer[index] else {
+      continue;
+    };
+    let color = Rgb::new(
+      value[0].round().clamp(0.0, 255.0) as u8,
+      value[1].round().clamp(0.0, 255.0) as u8,
+      value[2].round().clamp(0.0, 255.0) as u8,
+    );
+    let palindex = mapper.nearest(color);
+    palindexes[index] = Some(palindex);
+
+    let thread_color = mapper.color(palindex);
+    let error = [
+      value[0] - f32::from(thread_color.r),
+      value[1] - f32::from(thread_color.g),
+      value[2] - f32::from(thread_color.b),
+    ];
+
+    let (x, y) = ((index % width) as isize, (index / width) as isize);
+    for (dx, dy, weight) in [(1, 0, 7.0), (-1, 1, 3.0), (0, 1, 5.0), (1, 1, 1.0)] {
+      let (nx, ny) = (x + dx, y + dy);
+      if nx < 0 || nx >= width as isize {
+        continue;
+      }
+      if let Some(Some(neighbour)) = buffer.get_mut(ny as usize * width + nx as usize) {
+        for channel in 0..3 {
+          neighbour[channel] += error[channel] * weight / 16.0;
+        }
+      }
+    }
+  }
+
+  palindexes
+}
+
+fn build_pattern(
+  width: u16,
+  height: u16,
+  palindexes: &[Option<usize>],
+  threads: &[&CatalogThread],
+  brand: &str,
+) -> Result<Pattern> {
+  let mut builder = PatternBuilder::new(width, height);
+  let default_stitch_font = PatternSettings::default().default_stitch_font;
+
+  // Keep only the used threads, in the order of the palette.
+  let mut used = vec![false; threads.len()];
+  for palindex in palindexes.iter().flatten() {
+    used[*palindex] = true;
+  }
+  let mut remapped = vec![0; threads.len()];
+  let used_threads = threads.iter().enumerate().filter(|(index, _)| used[*index]);
+  for (index, thread) in used_threads {
+    let palitem = PaletteItem {
+      brand: brand.to_owned(),
+      number: thread.number.clone(),
+      name: thread.name.clone(),
+      color: thread.color,
+      blends: None,
+      bead: None,
+      strands: None,
+    };
+    let mut formats = Formats::default();
+    formats.font.font_name = Some(default_stitch_font.clone());
+    let symbols = Symbols {
+      full: builder.next_free_symbol(),
+      ..Default::default()
+    };
+    remapped[index] = builder.add_palette_item_with(palitem, formats, symbols)?;
+  }
+
+  for (index, palindex) in palindexes.iter().enumerate() {
+    if let Some(palindex) = palindex {
+      builder.add_fullstitch(FullStitch {
+        x: Coord::from_cells((index % usize::from(width)) as i32),
+        y: Coord::from_cells((index / usize::from(width)) as i32),
+        palindex: remapped[*palindex],
+        kind: FullStitchKind::Full,
+      });
+    }
+  }
+
+  Ok(builder.build()?)
+}
@@ -0,0 +1,187 @@
+use std::io::Cursor;
+use std::sync::LazyLock;
+
+use image::{ImageFormat, Rgba};
+
+use super::*;
+use crate::catalogs::Catalogs;
+
+fn dmc() -> &'static ThreadCatalog {
+  static CATALOGS: LazyLock<Catalogs> = LazyLock::new(Catalogs::bundled);
+  CATALOGS.catalog("DMC").unwrap()
+}
+
+/// A horizontal gradient from black to white.
+fn gradient(width: u32, height: u32) -> RgbaImage {
+  RgbaImage::from_fn(width, height, |x, _| {
+    let value = (x * 255 / (width - 1)) as u8;
+    Rgba([value, value, value, 255])
+  })
+}
+
+fn encode(image: &RgbaImage, format: ImageFormat) -> Vec<u8> {
+  let mut buf = Cursor::new(Vec::new());
+  match format {
+    ImageFormat::Jpeg => image::DynamicImage::ImageRgba8(image.clone())
+      .into_rgb8()
+      .write_to(&mut buf, format)
+      .unwrap(),
+    _ => image.write_to(&mut buf, format).unwrap(),
+  }
+  buf.into_inner()
+}
+
+#[test]
+fn converts_images_to_catalog_threads() {
+  let checkerboard = RgbaImage::from_fn(8, 8, |x, y| {
+    if (x + y) % 2 == 0 {
+      Rgba([0, 0, 0, 255])
+    } else {
+      Rgba([255, 255, 255, 255])
+    }
+  });
+  let mut options = ImageConversionOptions::new(dmc(), 8);
+
+  for quantization in [Quantization::KMeans, Quantization::MedianCut] {
+    options.quantization = quantization;
+    let pattern = convert_rgba_image(&checkerboard, &options).unwrap();
+
+    assert_eq!((pattern.fabric.width, pattern.fabric.height), (8, 8));
+    assert_eq!(pattern.fullstitches.len(), 64);
+    let mut numbers: Vec<_> = pattern.palette.iter().map(|palitem| palitem.number.as_str()).collect();
+    numbers.sort();
+    assert_eq!(numbers, ["310", "B5200"], "{quantization:?}");
+    assert!(pattern.palette.iter().all(|palitem| palitem.brand == "DMC"));
+
+    let first = &pattern.fullstitches[0];
+    assert_eq!(pattern.palette[first.palindex as usize].number, "310");
+  }
+}
+
+#[test]
+fn fills_formats_and_symbols() {
+  let mut options = ImageConversionOptions::new(dmc(), 16);
+  options.max_colors = 6;
+  let pattern = convert_rgba_image(&gradient(32, 8), &options).unwrap();
+
+  assert_eq!(pattern.formats.len(), pattern.palette.len());
+  assert_eq!(pattern.symbols.len(), pattern.palette.len());
+  assert!(
+    pattern
+      .formats
+      .iter()
+      .all(|formats| formats.font.font_name.as_deref() == Some("CrossStitch3"))
+  );
+
+  let mut codes: Vec<_> = pattern.symbols.iter().map(|symbols| symbols.full.unwrap()).collect();
+  codes.dedup();
+  assert_eq!(codes.len(), pattern.palette.len());
+  assert_eq!(codes[0], u16::from(b'!'));
+}
+
+#[test]
+fn respects_max_colors() {
+  let image = RgbaImage::from_fn(48, 48, |x, y| Rgba([(x * 5) as u8, (y * 5) as u8, 128, 255]));
+  for quantization in [Quantization::KMeans, Quantization::MedianCut] {
+    for max_colors in [1, 3, 10] {
+      let mut options = ImageConversionOptions::new(dmc(), 24);
+      options.quantization = quantization;
+      options.max_colors = max_colors;
+      let pattern = convert_rgba_image(&image, &options).unwrap();
+      assert!(
+        !pattern.palette.is_empty() && pattern.palette.len() <= max_colors,
+        "{quantization:?} produced {} colors instead of {max_colors}",
+        pattern.palette.len()
+      );
+    }
+  }
+}
+
+#[test]
+fn keeps_aspect_ratio() {
+  let options = ImageConversionOptions::new(dmc(), 10);
+  let pattern = convert_rgba_image(&gradient(40, 20), &options).unwrap();
+  assert_eq!((pattern.fabric.width, pattern.fabric.height), (10, 5));
+
+  let options = ImageConversionOptions {
+    height: Some(7),
+    ..ImageConversionOptions::new(dmc(), 10)
+  };
+  let pattern = convert_rgba_image(&gradient(40, 20), &options).unwrap();
+  assert_eq!((pattern.fabric.width, pattern.fabric.height), (10, 7));
+}
+
+#[test]
+fn skips_transparent_pixels() {
+  let image = RgbaImage::from_fn(4, 4, |x, _| {
+    if x < 2 {
+      Rgba([200, 30, 30, 255])
+    } else {
+      Rgba([0, 0, 0, 0])
+    }
+  });
+  let pattern = convert_rgba_image(&image, &ImageConversionOptions::new(dmc(), 4)).unwrap();
+  assert_eq!(pattern.palette.len(), 1);
+  assert_eq!(pattern.fullstitches.len(), 8);
+  assert!(pattern.fullstitches.iter().all(|stitch| stitch.x.cell() < 2));
+}
+
+#[test]
+fn dithers_gradients() {
+  /// Counts the color changes along the rows.
+  fn count_transitions(pattern: &Pattern) -> usize {
+    pattern
+      .fullstitches
+      .windows(2)
+      .filter(|pair| pair[0].y == pair[1].y && pair[0].palindex != pair[1].palindex)
+      .count()
+  }
+
+  let image = gradient(64, 8);
+  let mut options = ImageConversionOptions::new(dmc(), 32);
+  options.max_colors = 2;
+  let plain = convert_rgba_image(&image, &options).unwrap();
+
+  options.dithering = Dithering::FloydSteinberg;
+  let dithered = convert_rgba_image(&image, &options).unwrap();
+
+  assert_eq!(plain.palette, dithered.palette);
+  assert_eq!(dithered.fullstitches.len(), 32 * 4);
+  // Without dithering, every row is split into two solid halves.
+  assert_eq!(count_transitions(&plain), 4);
+  assert!(count_transitions(&dithered) > 4 * 4);
+}
+
+#[test]
+fn decodes_png_and_jpeg() {
+  let image = gradient(20, 10);
+  for format in [ImageFormat::Png, ImageFormat::Jpeg] {
+    let buf = encode(&image, format);
+    let pattern = convert_image(&buf, &ImageConversionOptions::new(dmc(), 10)).unwrap();
+    assert_eq!((pattern.fabric.width, pattern.fabric.height), (10, 5), "{format:?}");
+    assert_eq!(pattern.fullstitches.len(), 50, "{format:?}");
+  }
+
+  assert!(convert_image(b"not an image", &ImageConversionOptions::new(dmc(), 10)).is_err());
+}
+
+#[test]
+fn fails_on_invalid_options() {
+  let image = gradient(4, 4);
+
+  let options = ImageConversionOptions::new(dmc(), 0);
+  assert!(convert_rgba_image(&image, &options).is_err());
+
+  let mut options = ImageConversionOptions::new(dmc(), 4);
+  options.max_colors = 0;
+  assert!(convert_rgba_image(&image, &options).is_err());
+  options.max_colors = MAX_PALETTE_SIZE + 1;
+  let error = convert_rgba_image(&image, &options).unwrap_err();
+  assert_eq!(
+    error.to_string(),
+    "The maximum number of colors must be between 1 and 240"
+  );
+
+  let empty = ThreadCatalog::parse("Empty", "").unwrap();
+  assert!(convert_rgba_image(&image, &ImageConversionOptions::new(&empty, 4)).is_err());
+}
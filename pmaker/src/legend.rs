@@ -0,0 +1,313 @@
+//! The color key of a pattern, exported as CSV, Markdown or a standalone HTML page.
+
+use std::fmt::Write as _;
+
+use crate::schemas::color::Rgb;
+use crate::schemas::xsd::*;
+use crate::statistics::StitchCounts;
+
+#[cfg(test)]
+#[path = "legend.test.rs"]
+mod tests;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LegendFormat {
+  Csv,
+  Markdown,
+  Html,
+}
+
+/// A row of the legend that describes a palette item.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LegendEntry {
+  pub palindex: usize,
+  /// The symbol of the full stitches.
+  pub symbol: Option<char>,
+  pub brand: String,
+  pub number: String,
+  pub name: String,
+  pub color: Rgb,
+  /// The strands of each stitch kind with the defaults of the pattern filled in.
+  pub strands: StitchStrands<u8>,
+  pub blends: Vec<Blend>,
+  pub bead: Option<Bead>,
+  pub stitches: StitchCounts,
+}
+
+/// The names of the stitch kinds in the order of the legend columns.
+const STITCH_KINDS: [&str; 9] = [
+  "Full",
+  "Petite",
+  "Half",
+  "Quarter",
+  "Back",
+  "Straight",
+  "French knot",
+  "Bead",
+  "Special",
+];
+
+/// Collects the legend entries of all the palette items.
+pub fn legend_entries(pattern: &Pattern) -> Vec<LegendEntry> {
+  pattern
+    .palette
+    .iter()
+    .zip(pattern.statistics().stitches)
+    .enumerate()
+    .map(|(palindex, (palitem, stitches))| LegendEntry {
+      palindex,
+      symbol: pattern
+        .symbols
+        .get(palindex)
+        .and_then(|symbols| symbols.full)
+        .and_then(|code| char::from_u32(code.into())),
+      brand: palitem.brand.clone(),
+      number: palitem.number.clone(),
+      name: palitem.name.clone(),
+      color: palitem.color,
+      strands: pattern.strands(palindex),
+      blends: palitem.blends.clone().unwrap_or_default(),
+      bead: palitem.bead.clone(),
+      stitches,
+    })
+    .collect()
+}
+
+/// Exports the legend of the pattern in the given format.
+pub fn export_legend(pattern: &Pattern, format: LegendFormat) -> String {
+  let entries = legend_entries(pattern);
+  match format {
+    LegendFormat::Csv => legend_csv(&entries),
+    LegendFormat::Markdown => legend_markdown(&entries),
+    LegendFormat::Html => legend_html(&pattern.info.title, &entries),
+  }
+}
+
+/// Writes a row per palette item with a column for the strands and the stitches of each kind.
+fn legend_csv(entries: &[LegendEntry]) -> String {
+  let mut header = vec![
+    String::from("Symbol"),
+    String::from("Brand"),
+    String::from("Number"),
+    String::from("Name"),
+    String::from("Color"),
+  ];
+  header.extend(
+    STITCH_KINDS
+      .iter()
+      .filter(|kind| **kind != "Bead")
+      .map(|kind| format!("{kind} strands")),
+  );
+  header.extend([String::from("Blends"), String::from("Bead size")]);
+  header.extend(STITCH_KINDS.iter().map(|kind| format!("{kind} stitches")));
+
+  let mut csv = String::new();
+  write_csv_row(&mut csv, &header);
+  for entry in entries {
+    let mut row = vec![
+      entry.symbol.map(String::from).unwrap_or_default(),
+      entry.brand.clone(),
+      entry.number.clone(),
+      entry.name.clone(),
+      format!("#{}", entry.color),
+    ];
+    row.extend(
+      strands_list(&entry.strands)
+        .iter()
+        .map(|(_, strands)| strands.to_string()),
+    );
+    row.extend([blends(&entry.blends), bead_size(entry.bead.as_ref())]);
+    row.extend(
+      stitch_counts_list(&entry.stitches)
+        .iter()
+        .map(|(_, count)| count.to_string()),
+    );
+    write_csv_row(&mut csv, &row);
+  }
+  csv
+}
+
+fn write_csv_row(csv: &mut String, cells: &[String]) {
+  for (index, cell) in cells.iter().enumerate() {
+    if index > 0 {
+      csv.push(',');
+    }
+    if cell.contains([',', '"', '\n', '\r']) {
+      write!(csv, "\"{}\"", cell.replace('"', "\"\"")).unwrap();
+    } else {
+      csv.push_str(cell);
+    }
+  }
+  csv.push_str("\r\n");
+}
+
+/// The columns of the Markdown and HTML tables.
+const TABLE_COLUMNS: [&str; 9] = [
+  "Symbol",
+  "Brand",
+  "Number",
+  "Name",
+  "Color",
+  "Strands",
+  "Blends",
+  "Bead size",
+  "Stitches",
+];
+
+/// Returns the cells of the Markdown and HTML tables.
+/// The strands are listed only for the stitch kinds that the palette item is used in.
+fn table_cells(entry: &LegendEntry) -> [String; 9] {
+  let counts = stitch_counts_list(&entry.stitches);
+  let strands = strands_list(&entry.strands)
+    .into_iter()
+    .filter(|(kind, _)| counts.iter().any(|(counted, count)| counted == kind && *count > 0))
+    .map(|(kind, strands)| format!("{kind}: {strands}"))
+    .collect::<Vec<_>>()
+    .join(", ");
+  let stitches = counts
+    .into_iter()
+    .filter(|(_, count)| *count > 0)
+    .map(|(kind, count)| format!("{kind}: {count}"))
+    .collect::<Vec<_>>()
+    .join(", ");
+  [
+    entry.symbol.map(String::from).unwrap_or_default(),
+    entry.brand.clone(),
+    entry.number.clone(),
+    entry.name.clone(),
+    format!("#{}", entry.color),
+    strands,
+    blends(&entry.blends),
+    bead_size(entry.bead.as_ref()),
+    stitches,
+  ]
+}
+
+fn legend_markdown(entries: &[LegendEntry]) -> String {
+  let mut markdown = String::new();
+  writeln!(markdown, "| {} |", TABLE_COLUMNS.join(" | ")).unwrap();
+  writeln!(markdown, "|{}", " --- |".repeat(TABLE_COLUMNS.len())).unwrap();
+  for entry in entries {
+    let cells = table_cells(entry).map(|cell| escape_markdown(&cell));
+    writeln!(markdown, "| {} |", cells.join(" | ")).unwrap();
+  }
+  markdown
+}
+
+fn escape_markdown(text: &str) -> String {
+  let mut escaped = String::with_capacity(text.len());
+  for ch in text.chars() {
+    match ch {
+      '\\' | '|' | '*' | '_' | '`' | '[' | ']' | '<' | '>' => {
+        escaped.push('\\');
+        escaped.push(ch);
+      }
+      '\n' | '\r' => escaped.push(' '),
+      _ => escaped.push(ch),
+    }
+  }
+  escaped
+}
+
+fn legend_html(title: &str, entries: &[LegendEntry]) -> String {
+  let title = if title.is_empty() {
+    String::from("Legend")
+  } else {
+    format!("{} Legend", escape_html(title))
+  };
+
+  let mut html = String::new();
+  html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+  writeln!(html, "<title>{title}</title>").unwrap();
+  html.push_str(concat!(
+    "<style>\n",
+    "body { font-family: sans-serif; }\n",
+    "table { border-collapse: collapse; }\n",
+    "th, td { border: 1px solid #999; padding: 4px 8px; text-align: left; }\n",
+    ".swatch { display: inline-block; width: 1.5em; height: 1em; border: 1px solid #000; vertical-align: middle; }\n",
+    "</style>\n",
+  ));
+  html.push_str("</head>\n<body>\n");
+  writeln!(html, "<h1>{title}</h1>").unwrap();
+  html.push_str("<table>\n<thead>\n<tr>");
+  for column in TABLE_COLUMNS {
+    write!(html, "<th>{column}</th>").unwrap();
+  }
+  html.push_str("</tr>\n</thead>\n<tbody>\n");
+  for entry in entries {
+    html.push_str("<tr>");
+    for (index, cell) in table_cells(entry).iter().enumerate() {
+      if index == 4 {
+        write!(
+          html,
+          "<td><span class=\"swatch\" style=\"background-color: #{}\"></span> {}</td>",
+          entry.color, cell
+        )
+        .unwrap();
+      } else {
+        write!(html, "<td>{}</td>", escape_html(cell)).unwrap();
+      }
+    }
+    html.push_str("</tr>\n");
+  }
+  html.push_str("</tbody>\n</table>\n</body>\n</html>\n");
+  html
+}
+
+fn escape_html(text: &str) -> String {
+  let mut escaped = String::with_capacity(text.len());
+  for ch in text.chars() {
+    match ch {
+      '&' => escaped.push_str("&amp;"),
+      '<' => escaped.push_str("&lt;"),
+      '>' => escaped.push_str("&gt;"),
+      '"' => escaped.push_str("&quot;"),
+      '\'' => escaped.push_str("&#39;"),
+      _ => escaped.push(ch),
+    }
+  }
+  escaped
+}
+
+fn strands_list(strands: &StitchStrands<u8>) -> [(&'static str, u8); 8] {
+  [
+    (STITCH_KINDS[0], strands.full),
+    (STITCH_KINDS[1], strands.petite),
+    (STITCH_KINDS[2], strands.half),
+    (STITCH_KINDS[3], strands.quarter),
+    (STITCH_KINDS[4], strands.back),
+    (STITCH_KINDS[5], strands.straight),
+    (STITCH_KINDS[6], strands.french_knot),
+    (STITCH_KINDS[8], strands.special),
+  ]
+}
+
+fn stitch_counts_list(counts: &StitchCounts) -> [(&'static str, usize); 9] {
+  [
+    (STITCH_KINDS[0], counts.full),
+    (STITCH_KINDS[1], counts.petite),
+    (STITCH_KINDS[2], counts.half),
+    (STITCH_KINDS[3], counts.quarter),
+    (STITCH_KINDS[4], counts.back),
+    (STITCH_KINDS[5], counts.straight),
+    (STITCH_KINDS[6], counts.french_knot),
+    (STITCH_KINDS[7], counts.bead),
+    (STITCH_KINDS[8], counts.special),
+  ]
+}
+
+/// Formats the blend components, e.g. `DMC 310 (1) + DMC 321 (1)`.
+fn blends(blends: &[Blend]) -> String {
+  blends
+    .iter()
+    .map(|blend| format!("{} {} ({})", blend.brand, blend.number, blend.strands))
+    .collect::<Vec<_>>()
+    .join(" + ")
+}
+
+/// Formats the bead size in millimeters, e.g. `2.5 x 1.5 mm`.
+fn bead_size(bead: Option<&Bead>) -> String {
+  bead
+    .map(|bead| format!("{} x {} mm", bead.length, bead.diameter))
+    .unwrap_or_default()
+}
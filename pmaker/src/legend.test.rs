@@ -0,0 +1,168 @@
+use super::*;
+use crate::builder::PatternBuilder;
+use crate::schemas::coords::Coord;
+
+fn sample_pattern() -> Pattern {
+  let mut builder = PatternBuilder::new(4, 4);
+  builder.info(PatternInfo {
+    title: String::from("Roses & <Tulips>"),
+    ..Default::default()
+  });
+  let red = builder
+    .add_palette_item_with(
+      PaletteItem {
+        name: String::from("Red, bright"),
+        strands: Some(StitchStrands {
+          back: Some(2),
+          ..Default::default()
+        }),
+        ..PaletteItem::new("DMC", "321", Rgb::new(0xC7, 0x2B, 0x3B))
+      },
+      Formats::default(),
+      Symbols {
+        full: Some(u16::from(b'|')),
+        ..Default::default()
+      },
+    )
+    .unwrap();
+  let blend = builder
+    .add_palette_item_with(
+      PaletteItem {
+        blends: Some(vec![
+          Blend {
+            brand: String::from("DMC"),
+            number: String::from("310"),
+            strands: 1,
+          },
+          Blend {
+            brand: String::from("DMC"),
+            number: String::from("666"),
+            strands: 1,
+          },
+        ]),
+        ..PaletteItem::new("Blends", "310+666", Rgb::new(0x60, 0x10, 0x20))
+      },
+      Formats::default(),
+      Symbols::default(),
+    )
+    .unwrap();
+  let bead = builder
+    .add_palette_item(PaletteItem {
+      name: String::from("Gold"),
+      bead: Some(Bead {
+        length: 2.5,
+        diameter: 1.5,
+      }),
+      ..PaletteItem::new("Mill Hill", "00557", Rgb::new(0xD4, 0xAF, 0x37))
+    })
+    .unwrap();
+
+  for x in 0..3 {
+    builder.add_fullstitch(FullStitch {
+      x: Coord::from_cells(x),
+      y: Coord::ZERO,
+      palindex: red,
+      kind: FullStitchKind::Full,
+    });
+  }
+  builder
+    .add_partstitch(PartStitch {
+      x: Coord::ZERO,
+      y: Coord::from_cells(1),
+      palindex: blend,
+      direction: PartStitchDirection::Forward,
+      kind: PartStitchKind::Half,
+    })
+    .add_linestitch(LineStitch {
+      x: (Coord::ZERO, Coord::from_cells(4)),
+      y: (Coord::ZERO, Coord::ZERO),
+      palindex: red,
+      kind: LineStitchKind::Back,
+    })
+    .add_nodestitch(NodeStitch {
+      x: Coord::from_cells(2),
+      y: Coord::from_cells(2),
+      rotated: false,
+      palindex: bead,
+      kind: NodeStitchKind::Bead,
+    });
+  builder.build().unwrap()
+}
+
+#[test]
+fn collects_legend_entries() {
+  let pattern = sample_pattern();
+  let entries = legend_entries(&pattern);
+  assert_eq!(entries.len(), 3);
+
+  let red = &entries[0];
+  assert_eq!(red.symbol, Some('|'));
+  assert_eq!(red.strands.back, 2);
+  assert_eq!(red.strands.full, pattern.stitch_settings.default_strands.full);
+  assert_eq!(
+    red.stitches,
+    StitchCounts {
+      full: 3,
+      back: 1,
+      ..Default::default()
+    }
+  );
+  assert_eq!(red.stitches.total(), 4);
+
+  assert_eq!(entries[1].symbol, None);
+  assert_eq!(entries[1].blends.len(), 2);
+  assert_eq!(entries[1].stitches.half, 1);
+  assert_eq!(entries[2].symbol, Some('!'));
+  assert_eq!(entries[2].stitches.bead, 1);
+}
+
+#[test]
+fn exports_csv() {
+  let csv = export_legend(&sample_pattern(), LegendFormat::Csv);
+  let lines: Vec<_> = csv.split("\r\n").collect();
+  assert_eq!(lines.len(), 5);
+  assert!(lines[0].starts_with("Symbol,Brand,Number,Name,Color,Full strands,"));
+  assert!(lines[0].ends_with(",Bead stitches,Special stitches"));
+  assert_eq!(
+    lines[1],
+    "|,DMC,321,\"Red, bright\",#C72B3B,2,2,2,2,2,1,2,2,,,3,0,0,0,1,0,0,0,0"
+  );
+  assert!(lines[2].contains(",DMC 310 (1) + DMC 666 (1),,"));
+  assert!(lines[3].contains(",2.5 x 1.5 mm,"));
+  assert_eq!(lines[4], "");
+}
+
+#[test]
+fn exports_markdown() {
+  let markdown = export_legend(&sample_pattern(), LegendFormat::Markdown);
+  let lines: Vec<_> = markdown.lines().collect();
+  assert_eq!(lines.len(), 5);
+  assert_eq!(
+    lines[0],
+    "| Symbol | Brand | Number | Name | Color | Strands | Blends | Bead size | Stitches |"
+  );
+  assert_eq!(lines[1], "| --- | --- | --- | --- | --- | --- | --- | --- | --- |");
+  assert_eq!(
+    lines[2],
+    r"| \| | DMC | 321 | Red, bright | #C72B3B | Full: 2, Back: 2 |  |  | Full: 3, Back: 1 |"
+  );
+  assert_eq!(
+    lines[3],
+    "|  | Blends | 310+666 |  | #601020 | Half: 2 | DMC 310 (1) + DMC 666 (1) |  | Half: 1 |"
+  );
+  assert_eq!(
+    lines[4],
+    "| ! | Mill Hill | 00557 | Gold | #D4AF37 |  |  | 2.5 x 1.5 mm | Bead: 1 |"
+  );
+}
+
+#[test]
+fn exports_html() {
+  let html = export_legend(&sample_pattern(), LegendFormat::Html);
+  assert!(html.starts_with("<!DOCTYPE html>"));
+  assert!(html.contains("<title>Roses &amp; &lt;Tulips&gt; Legend</title>"));
+  assert_eq!(html.matches("<tr>").count(), 4);
+  assert!(html.contains("<td>|</td><td>DMC</td><td>321</td><td>Red, bright</td>"));
+  assert!(html.contains(r##"<span class="swatch" style="background-color: #C72B3B"></span> #C72B3B"##));
+  assert!(html.trim_end().ends_with("</html>"));
+}
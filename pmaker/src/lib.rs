@@ -1,5 +1,40 @@
+mod builder;
+#[cfg(feature = "catalogs")]
+mod catalogs;
+#[cfg(feature = "catalogs")]
+mod conversion;
+mod fabric_requirements;
+#[cfg(feature = "image")]
+mod image_conversion;
+mod legend;
+#[cfg(feature = "xsd")]
 mod parsers;
+mod print_template;
+#[cfg(feature = "rendering")]
+mod rendering;
 mod schemas;
+mod statistics;
+mod thread_usage;
+mod units;
+mod validation;
 
+pub use builder::*;
+#[cfg(feature = "catalogs")]
+pub use catalogs::*;
+#[cfg(feature = "catalogs")]
+pub use conversion::*;
+pub use fabric_requirements::*;
+#[cfg(feature = "image")]
+pub use image_conversion::*;
+pub use legend::*;
+#[cfg(feature = "xsd")]
 pub use parsers::xsd::*;
+pub use print_template::*;
+#[cfg(feature = "rendering")]
+pub use rendering::*;
+pub use schemas::color::*;
+pub use schemas::coords::*;
 pub use schemas::xsd::*;
+pub use statistics::*;
+pub use thread_usage::*;
+pub use validation::*;
@@ -1,5 +1,7 @@
 use std::io;
 
+use crate::schemas::color::Rgb;
+
 #[cfg(test)]
 #[path = "read.test.rs"]
 mod tests;
@@ -29,11 +31,11 @@ pub trait ReadXsdExt: io::Read + byteorder::ReadBytesExt {
     }
   }
 
-  /// Reads a hex color as `String`.
-  fn read_hex_color(&mut self) -> io::Result<String> {
+  /// Reads an RGB color.
+  fn read_color(&mut self) -> io::Result<Rgb> {
     let mut buf: [u8; 3] = [0; 3];
     self.read_exact(&mut buf)?;
-    Ok(hex::encode_upper(buf))
+    Ok(Rgb::from(buf))
   }
 }
 
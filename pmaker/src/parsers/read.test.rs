@@ -1,6 +1,7 @@
 use std::io::Cursor;
 
 use super::ReadXsdExt as _;
+use crate::schemas::color::Rgb;
 
 #[test]
 fn reads_cstring() {
@@ -24,16 +25,13 @@ fn returns_empty_string_on_non_null_terminated_cstring() {
 }
 
 #[test]
-fn reads_hex_color() {
+fn reads_color() {
   let black_color_buf = vec![0x00, 0x00, 0x00];
   assert_eq!(
-    Cursor::new(black_color_buf).read_hex_color().unwrap(),
-    String::from("000000")
+    Cursor::new(black_color_buf).read_color().unwrap(),
+    Rgb::new(0x00, 0x00, 0x00)
   );
 
-  let white_color_buf = vec![0xFF, 0xFF, 0xFF];
-  assert_eq!(
-    Cursor::new(white_color_buf).read_hex_color().unwrap(),
-    String::from("FFFFFF")
-  );
+  let color_buf = vec![0x2C, 0x32, 0x25];
+  assert_eq!(Cursor::new(color_buf).read_color().unwrap(), Rgb::new(0x2C, 0x32, 0x25));
 }
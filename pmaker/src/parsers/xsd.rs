@@ -10,6 +10,7 @@ use anyhow::Result;
 use byteorder::{LittleEndian, ReadBytesExt};
 
 use super::ReadXsdExt as _;
+use crate::schemas::coords::{Coord, CurveCoord};
 use crate::schemas::xsd::*;
 
 #[cfg(test)]
@@ -57,6 +58,15 @@ const SPECIAL_STITCH_NAME_LENGTH: usize = 255;
 
 pub fn parse_xsd_pattern<P: AsRef<std::path::Path>>(file_path: P) -> Result<Pattern> {
   let buf = std::fs::read(file_path.as_ref())?;
+  parse_xsd_pattern_from_bytes(&buf)
+}
+
+/// Checks whether the data starts with the signature of Pattern Maker v4.
+pub fn has_xsd_signature(buf: &[u8]) -> bool {
+  read_signature(&mut &buf[..]).is_ok_and(|signature| signature == VALID_SIGNATURE)
+}
+
+pub fn parse_xsd_pattern_from_bytes(buf: &[u8]) -> Result<Pattern> {
   let mut cursor = std::io::Cursor::new(buf);
 
   let signature = read_signature(&mut cursor)?;
@@ -94,7 +104,7 @@ pub fn parse_xsd_pattern<P: AsRef<std::path::Path>>(file_path: P) -> Result<Patt
   let grid = read_grid(&mut cursor)?;
 
   let fabric_color_name = cursor.read_cstring(FABRIC_COLOR_NAME_LENGTH)?;
-  let fabric_color = cursor.read_hex_color()?;
+  let fabric_color = cursor.read_color()?;
   cursor.seek_relative(65)?;
   let pattern_info = read_pattern_info(&mut cursor)?;
   cursor.seek_relative(6)?;
@@ -222,7 +232,7 @@ fn read_palette_item<R: Read + Seek>(reader: &mut R) -> io::Result<PaletteItem>
   let brand = PM_THREAD_BRANDS.get(&brand_id).unwrap().to_owned();
   let number = reader.read_cstring(COLOR_NUMBER_LENGTH)?;
   let name = reader.read_cstring(COLOR_NAME_LENGTH)?;
-  let color = reader.read_hex_color()?;
+  let color = reader.read_color()?;
   reader.seek_relative(1)?;
   let blends = read_blends(reader)?;
   let is_bead = reader.read_u32::<LittleEndian>()? == 1;
@@ -319,9 +329,9 @@ fn read_symbol_formats<R: Read + Seek>(reader: &mut R, palette_size: usize) -> i
   let mut formats = Vec::with_capacity(palette_size);
   for _ in 0..palette_size {
     let use_alt_bg_color = reader.read_u16::<LittleEndian>()? == 1;
-    let bg_color = reader.read_hex_color()?;
+    let bg_color = reader.read_color()?;
     reader.seek_relative(1)?;
-    let fg_color = reader.read_hex_color()?;
+    let fg_color = reader.read_color()?;
     reader.seek_relative(1)?;
     formats.push(SymbolFormat {
       use_alt_bg_color,
@@ -337,7 +347,7 @@ fn read_line_formats<R: Read + Seek>(reader: &mut R, palette_size: usize) -> io:
   let mut formats = Vec::with_capacity(palette_size);
   for _ in 0..palette_size {
     let use_alt_color = reader.read_u16::<LittleEndian>()? == 1;
-    let color = reader.read_hex_color()?;
+    let color = reader.read_color()?;
     reader.seek_relative(1)?;
     let style = reader.read_u16::<LittleEndian>()?;
     let thickness = reader.read_u16::<LittleEndian>()? as f32 / 10.0;
@@ -356,7 +366,7 @@ fn read_node_formats<R: Read + Seek>(reader: &mut R, palette_size: usize) -> io:
   let mut formats = Vec::with_capacity(palette_size);
   for _ in 0..palette_size {
     let use_dot_style = reader.read_u16::<LittleEndian>()? == 1;
-    let color = reader.read_hex_color()?;
+    let color = reader.read_color()?;
     reader.seek_relative(1)?;
     let use_alt_color = reader.read_u16::<LittleEndian>()? == 1;
     let thickness = reader.read_u16::<LittleEndian>()? as f32 / 10.0;
@@ -484,7 +494,7 @@ fn read_grid<R: Read + Seek>(reader: &mut R) -> io::Result<Grid> {
   fn read_grid_line_style<R: Read + Seek>(reader: &mut R) -> io::Result<GridLineStyle> {
     let thickness = (reader.read_u16::<LittleEndian>()? * 72) as f32 / 1000.0; // Convert to points.
     reader.seek_relative(2)?;
-    let color = reader.read_hex_color()?;
+    let color = reader.read_color()?;
     reader.seek_relative(3)?;
     Ok(GridLineStyle { color, thickness })
   }
@@ -543,7 +553,7 @@ fn read_stitch_settings<R: Read + Seek>(reader: &mut R) -> io::Result<StitchSett
   let stitch_outline = StitchOutline {
     color_percentage: reader.read_u16::<LittleEndian>()? as u8,
     color: if use_specified_color {
-      let color = reader.read_hex_color()?;
+      let color = reader.read_color()?;
       reader.seek_relative(1)?;
       Some(color)
     } else {
@@ -728,8 +738,8 @@ fn map_stitches_data_into_stitches(
       continue;
     }
 
-    let x = (i % coord_factor) as f32;
-    let y = (i / coord_factor) as f32;
+    let x = Coord::from_cells((i % coord_factor) as i32);
+    let y = Coord::from_cells((i / coord_factor) as i32);
 
     if stitch_buffer[3] == 0 {
       fullstitches.push(FullStitch {
@@ -798,12 +808,14 @@ fn map_stitches_data_into_stitches(
 /// Adjusts the coordinates of the small stitch.
 /// The XSD format contains coordinates without additional offsets relative to the cell.
 /// But this is important for us.
-fn adjust_small_stitch_coors(x: f32, y: f32, kind: XsdSmallStitchKind) -> io::Result<(f32, f32)> {
+fn adjust_small_stitch_coors(x: Coord, y: Coord, kind: XsdSmallStitchKind) -> io::Result<(Coord, Coord)> {
   match kind {
     XsdSmallStitchKind::QuarterTopLeft | XsdSmallStitchKind::PetiteTopLeft => Ok((x, y)),
-    XsdSmallStitchKind::QuarterTopRight | XsdSmallStitchKind::PetiteTopRight => Ok((x + 0.5, y)),
-    XsdSmallStitchKind::QuarterBottomLeft | XsdSmallStitchKind::PetiteBottomLeft => Ok((x, y + 0.5)),
-    XsdSmallStitchKind::QuarterBottomRight | XsdSmallStitchKind::PetiteBottomRight => Ok((x + 0.5, y + 0.5)),
+    XsdSmallStitchKind::QuarterTopRight | XsdSmallStitchKind::PetiteTopRight => Ok((x + Coord::HALF, y)),
+    XsdSmallStitchKind::QuarterBottomLeft | XsdSmallStitchKind::PetiteBottomLeft => Ok((x, y + Coord::HALF)),
+    XsdSmallStitchKind::QuarterBottomRight | XsdSmallStitchKind::PetiteBottomRight => {
+      Ok((x + Coord::HALF, y + Coord::HALF))
+    }
     _ => Ok((x, y)),
   }
 }
@@ -833,18 +845,15 @@ fn read_special_stitch_models<R: Read + Seek>(reader: &mut R) -> io::Result<Vec<
       name: reader.read_cstring(SPECIAL_STITCH_NAME_LENGTH)?,
       ..Default::default()
     };
-    let mut shift = (0.0, 0.0);
+    let mut shift = (Coord::ZERO, Coord::ZERO);
     reader.seek_relative(2)?;
 
     for i in 0..3 {
       if i == 0 {
         reader.seek_relative(2)?;
-        shift = (
-          reader.read_u16::<LittleEndian>()? as f32 / 2.0,
-          reader.read_u16::<LittleEndian>()? as f32 / 2.0,
-        );
-        special_stitch_model.width = reader.read_u16::<LittleEndian>()? as f32 / 2.0;
-        special_stitch_model.height = reader.read_u16::<LittleEndian>()? as f32 / 2.0;
+        shift = (read_coord(reader)?, read_coord(reader)?);
+        special_stitch_model.width = read_coord(reader)?;
+        special_stitch_model.height = read_coord(reader)?;
       } else {
         reader.seek_relative(10)?;
       }
@@ -869,6 +878,7 @@ fn read_special_stitch_models<R: Read + Seek>(reader: &mut R) -> io::Result<Vec<
     }
 
     // Adjust the coordinates of the curvedstitches.
+    let shift = (CurveCoord::from(shift.0), CurveCoord::from(shift.1));
     for curve in special_stitch_model.curvedstitches.iter_mut() {
       curve.points = curve.points.iter().map(|(x, y)| (*x - shift.0, *y - shift.1)).collect();
     }
@@ -903,6 +913,11 @@ impl From<u16> for XsdJointKind {
   }
 }
 
+/// Reads a joint coordinate, which is stored in halves of a cell.
+fn read_coord<R: Read>(reader: &mut R) -> io::Result<Coord> {
+  Ok(Coord::from_halves(reader.read_u16::<LittleEndian>()?.into()))
+}
+
 type Joints = (Vec<LineStitch>, Vec<NodeStitch>, Vec<SpecialStitch>, Vec<CurvedStitch>);
 
 /// Reads the french knots, beads, back, straight and special stitches and curved stitches used in the pattern.
@@ -919,8 +934,8 @@ fn read_joints<R: Read + Seek>(reader: &mut R, joints_count: u16) -> io::Result<
     match joint_kind {
       XsdJointKind::FrenchKnot => {
         reader.seek_relative(2)?;
-        let x = reader.read_u16::<LittleEndian>()? as f32 / 2.0;
-        let y = reader.read_u16::<LittleEndian>()? as f32 / 2.0;
+        let x = read_coord(reader)?;
+        let y = read_coord(reader)?;
         reader.seek_relative(4)?;
         let palindex = reader.read_u8()?;
         reader.seek_relative(1)?;
@@ -935,10 +950,10 @@ fn read_joints<R: Read + Seek>(reader: &mut R, joints_count: u16) -> io::Result<
 
       XsdJointKind::Back | XsdJointKind::Straight => {
         reader.seek_relative(2)?;
-        let x1 = reader.read_u16::<LittleEndian>()? as f32 / 2.0;
-        let y1 = reader.read_u16::<LittleEndian>()? as f32 / 2.0;
-        let x2 = reader.read_u16::<LittleEndian>()? as f32 / 2.0;
-        let y2 = reader.read_u16::<LittleEndian>()? as f32 / 2.0;
+        let x1 = read_coord(reader)?;
+        let y1 = read_coord(reader)?;
+        let x2 = read_coord(reader)?;
+        let y2 = read_coord(reader)?;
         let palindex = reader.read_u8()?;
         reader.seek_relative(1)?;
         let kind = if joint_kind == XsdJointKind::Back {
@@ -961,10 +976,9 @@ fn read_joints<R: Read + Seek>(reader: &mut R, joints_count: u16) -> io::Result<
           points: Vec::with_capacity(points_count),
         };
         for _ in 0..points_count {
-          // 15.0 is the resolution of the curve points.
-          // 2.0 is the factor that is used to convert the XSD coordinates to the pattern coordinates.
-          let x = reader.read_u16::<LittleEndian>()? as f32 / 15.0 / 2.0;
-          let y = reader.read_u16::<LittleEndian>()? as f32 / 15.0 / 2.0;
+          // The curve points are stored in 1/15 of the half-cell, that is, in 1/30 of the cell.
+          let x = CurveCoord::from_thirtieths(reader.read_u16::<LittleEndian>()?.into());
+          let y = CurveCoord::from_thirtieths(reader.read_u16::<LittleEndian>()?.into());
           curve.points.push((x, y));
         }
         curvedstitches.push(curve);
@@ -974,8 +988,8 @@ fn read_joints<R: Read + Seek>(reader: &mut R, joints_count: u16) -> io::Result<
         reader.seek_relative(2)?;
         let palindex = reader.read_u8()?;
         reader.seek_relative(4)?;
-        let x = reader.read_u16::<LittleEndian>()? as f32 / 2.0;
-        let y = reader.read_u16::<LittleEndian>()? as f32 / 2.0;
+        let x = read_coord(reader)?;
+        let y = read_coord(reader)?;
         let (rotation, flip) = {
           let mut flip = (false, false);
           let mut rotation = 0;
@@ -1020,8 +1034,8 @@ fn read_joints<R: Read + Seek>(reader: &mut R, joints_count: u16) -> io::Result<
 
       XsdJointKind::Bead => {
         reader.seek_relative(2)?;
-        let x = reader.read_u16::<LittleEndian>()? as f32 / 2.0;
-        let y = reader.read_u16::<LittleEndian>()? as f32 / 2.0;
+        let x = read_coord(reader)?;
+        let y = read_coord(reader)?;
         let palindex = reader.read_u8()?;
         reader.seek_relative(1)?;
         let rotated = matches!(reader.read_u16::<LittleEndian>()?, 90 | 270);
@@ -2,6 +2,7 @@ use std::fs::File;
 use std::io::Cursor;
 
 use super::*;
+use crate::schemas::color::Rgb;
 
 fn load_fixture(name: &str) -> File {
   let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
@@ -23,15 +24,22 @@ fn reads_signature() {
   );
 }
 
+#[test]
+fn checks_signature() {
+  assert!(has_xsd_signature(&[0x10, 0x05, 0x00]));
+  assert!(!has_xsd_signature(&[0x00, 0x00]));
+  assert!(!has_xsd_signature(&[0x10]));
+}
+
 #[test]
 fn reads_palette() {
   let loaded_palette = read_palette(&mut load_fixture("palette")).unwrap();
-  let expected_palette = vec![
+  let expected_palette = [
     PaletteItem {
       brand: String::from("DMC"),
       number: String::from("310"),
       name: String::from("Black"),
-      color: String::from("2C3225"),
+      color: Rgb::new(0x2C, 0x32, 0x25),
       blends: None,
       bead: None,
       strands: Some(Default::default()),
@@ -40,7 +48,7 @@ fn reads_palette() {
       brand: String::from("PNK Kirova"),
       number: String::from("9224"),
       name: String::from("ПНК Кирова"),
-      color: String::from("B40032"),
+      color: Rgb::new(0xB4, 0x00, 0x32),
       blends: None,
       bead: None,
       strands: Some(Default::default()),
@@ -49,7 +57,7 @@ fn reads_palette() {
       brand: String::from("Mill Hill Frosted Glass Seed Bead"),
       number: String::from("62038"),
       name: String::from("Frosted Aquamarine"),
-      color: String::from("A6D3D9"),
+      color: Rgb::new(0xA6, 0xD3, 0xD9),
       blends: None,
       bead: Some(Bead {
         length: 2.5,
@@ -61,7 +69,7 @@ fn reads_palette() {
       brand: String::from("Blend"),
       number: String::from("11"),
       name: String::from(""),
-      color: String::from("93D0D3"),
+      color: Rgb::new(0x93, 0xD0, 0xD3),
       blends: Some(vec![
         Blend {
           brand: String::from("DMC"),
@@ -95,40 +103,40 @@ fn reads_palette() {
 #[test]
 fn reads_formats() {
   let loaded_formats = read_formats(&mut load_fixture("formats"), 2).unwrap();
-  let expected_formats = vec![
+  let expected_formats = [
     Formats {
       symbol: SymbolFormat {
         use_alt_bg_color: false,
-        bg_color: String::from("FFFFFF"),
-        fg_color: String::from("000000"),
+        bg_color: Rgb::new(0xFF, 0xFF, 0xFF),
+        fg_color: Rgb::new(0x00, 0x00, 0x00),
       },
       back_stitch: LineStitchFormat {
         use_alt_color: false,
-        color: String::from("000000"),
+        color: Rgb::new(0x00, 0x00, 0x00),
         style: 5,
         thickness: 1.0,
       },
       straight_stitch: LineStitchFormat {
         use_alt_color: false,
-        color: String::from("000000"),
+        color: Rgb::new(0x00, 0x00, 0x00),
         style: 5,
         thickness: 1.0,
       },
       french_knot: NodeStitchFormat {
         use_dot_style: true,
         use_alt_color: false,
-        color: String::from("000000"),
+        color: Rgb::new(0x00, 0x00, 0x00),
         thickness: 4.0,
       },
       bead: NodeStitchFormat {
         use_dot_style: true,
         use_alt_color: false,
-        color: String::from("000000"),
+        color: Rgb::new(0x00, 0x00, 0x00),
         thickness: 4.0,
       },
       special_stitch: LineStitchFormat {
         use_alt_color: false,
-        color: String::from("000000"),
+        color: Rgb::new(0x00, 0x00, 0x00),
         style: 5,
         thickness: 1.0,
       },
@@ -143,36 +151,36 @@ fn reads_formats() {
     Formats {
       symbol: SymbolFormat {
         use_alt_bg_color: false,
-        bg_color: String::from("FFFFFF"),
-        fg_color: String::from("000000"),
+        bg_color: Rgb::new(0xFF, 0xFF, 0xFF),
+        fg_color: Rgb::new(0x00, 0x00, 0x00),
       },
       back_stitch: LineStitchFormat {
         use_alt_color: false,
-        color: String::from("FFFFFF"),
+        color: Rgb::new(0xFF, 0xFF, 0xFF),
         style: 8,
         thickness: 1.5,
       },
       straight_stitch: LineStitchFormat {
         use_alt_color: false,
-        color: String::from("FFFFFF"),
+        color: Rgb::new(0xFF, 0xFF, 0xFF),
         style: 6,
         thickness: 0.8,
       },
       french_knot: NodeStitchFormat {
         use_dot_style: false,
         use_alt_color: false,
-        color: String::from("FFFFFF"),
+        color: Rgb::new(0xFF, 0xFF, 0xFF),
         thickness: 4.0,
       },
       bead: NodeStitchFormat {
         use_dot_style: false,
         use_alt_color: false,
-        color: String::from("FFFFFF"),
+        color: Rgb::new(0xFF, 0xFF, 0xFF),
         thickness: 4.0,
       },
       special_stitch: LineStitchFormat {
         use_alt_color: false,
-        color: String::from("FFFFFF"),
+        color: Rgb::new(0xFF, 0xFF, 0xFF),
         style: 5,
         thickness: 1.5,
       },
@@ -193,7 +201,7 @@ fn reads_formats() {
 #[test]
 fn reads_symbols() {
   let loaded_symbols = read_symbols(&mut load_fixture("symbols"), 2).unwrap();
-  let expected_symbols = vec![
+  let expected_symbols = [
     Symbols {
       full: Some(33),
       petite: Some(34),
@@ -268,19 +276,19 @@ fn reads_grid_settings() {
     Grid {
       major_lines_interval: 10,
       minor_screen_lines: GridLineStyle {
-        color: String::from("C8C8C8"),
+        color: Rgb::new(0xC8, 0xC8, 0xC8),
         thickness: 0.072,
       },
       major_screen_lines: GridLineStyle {
-        color: String::from("646464"),
+        color: Rgb::new(0x64, 0x64, 0x64),
         thickness: 0.072,
       },
       minor_printer_lines: GridLineStyle {
-        color: String::from("000000"),
+        color: Rgb::new(0x00, 0x00, 0x00),
         thickness: 0.144,
       },
       major_printer_lines: GridLineStyle {
-        color: String::from("000000"),
+        color: Rgb::new(0x00, 0x00, 0x00),
         thickness: 0.504,
       },
     }
@@ -363,50 +371,50 @@ fn reads_stitches() {
     read_stitches(&mut load_fixture("stitches"), 10, 10 * 10, 8).unwrap();
   let expected_fullstitches = [
     FullStitch {
-      x: 0.0,
-      y: 0.0,
+      x: Coord::from_cells(0),
+      y: Coord::from_cells(0),
       palindex: 1,
       kind: FullStitchKind::Full,
     },
     FullStitch {
-      x: 9.0,
-      y: 0.0,
+      x: Coord::from_cells(9),
+      y: Coord::from_cells(0),
       palindex: 2,
       kind: FullStitchKind::Full,
     },
     FullStitch {
-      x: 1.0,
-      y: 1.0,
+      x: Coord::from_cells(1),
+      y: Coord::from_cells(1),
       palindex: 3,
       kind: FullStitchKind::Petite,
     },
     FullStitch {
-      x: 2.5,
-      y: 1.0,
+      x: Coord::from_halves(5),
+      y: Coord::from_cells(1),
       palindex: 3,
       kind: FullStitchKind::Petite,
     },
     FullStitch {
-      x: 1.0,
-      y: 2.5,
+      x: Coord::from_cells(1),
+      y: Coord::from_halves(5),
       palindex: 3,
       kind: FullStitchKind::Petite,
     },
     FullStitch {
-      x: 2.5,
-      y: 2.5,
+      x: Coord::from_halves(5),
+      y: Coord::from_halves(5),
       palindex: 3,
       kind: FullStitchKind::Petite,
     },
     FullStitch {
-      x: 0.0,
-      y: 9.0,
+      x: Coord::from_cells(0),
+      y: Coord::from_cells(9),
       palindex: 6,
       kind: FullStitchKind::Full,
     },
     FullStitch {
-      x: 9.0,
-      y: 9.0,
+      x: Coord::from_cells(9),
+      y: Coord::from_cells(9),
       palindex: 0,
       kind: FullStitchKind::Full,
     },
@@ -417,57 +425,57 @@ fn reads_stitches() {
 
   let expected_partstitches = [
     PartStitch {
-      x: 1.5,
-      y: 1.5,
+      x: Coord::from_halves(3),
+      y: Coord::from_halves(3),
       palindex: 4,
       direction: PartStitchDirection::Backward,
       kind: PartStitchKind::Quarter,
     },
     PartStitch {
-      x: 2.0,
-      y: 1.5,
+      x: Coord::from_cells(2),
+      y: Coord::from_halves(3),
       palindex: 4,
       direction: PartStitchDirection::Forward,
       kind: PartStitchKind::Quarter,
     },
     PartStitch {
-      x: 1.5,
-      y: 2.0,
+      x: Coord::from_halves(3),
+      y: Coord::from_cells(2),
       palindex: 4,
       direction: PartStitchDirection::Forward,
       kind: PartStitchKind::Quarter,
     },
     PartStitch {
-      x: 2.0,
-      y: 2.0,
+      x: Coord::from_cells(2),
+      y: Coord::from_cells(2),
       palindex: 4,
       direction: PartStitchDirection::Backward,
       kind: PartStitchKind::Quarter,
     },
     PartStitch {
-      x: 3.0,
-      y: 3.0,
+      x: Coord::from_cells(3),
+      y: Coord::from_cells(3),
       palindex: 5,
       direction: PartStitchDirection::Backward,
       kind: PartStitchKind::Half,
     },
     PartStitch {
-      x: 4.0,
-      y: 3.0,
+      x: Coord::from_cells(4),
+      y: Coord::from_cells(3),
       palindex: 5,
       direction: PartStitchDirection::Forward,
       kind: PartStitchKind::Half,
     },
     PartStitch {
-      x: 3.0,
-      y: 4.0,
+      x: Coord::from_cells(3),
+      y: Coord::from_cells(4),
       palindex: 5,
       direction: PartStitchDirection::Forward,
       kind: PartStitchKind::Half,
     },
     PartStitch {
-      x: 4.0,
-      y: 4.0,
+      x: Coord::from_cells(4),
+      y: Coord::from_cells(4),
       palindex: 5,
       direction: PartStitchDirection::Backward,
       kind: PartStitchKind::Half,
@@ -481,72 +489,72 @@ fn reads_stitches() {
 #[test]
 fn reads_special_stitch_models() {
   let loaded_special_stitch_models = read_special_stitch_models(&mut load_fixture("special_stitch_models")).unwrap();
-  let expected_speciql_stitch_models = vec![
+  let expected_speciql_stitch_models = [
     SpecialStitchModel {
       unique_name: String::from("Lasy Daisy Over 2x1"),
       name: String::from(""),
-      width: 2.0,
-      height: 2.0,
+      width: Coord::from_cells(2),
+      height: Coord::from_cells(2),
       nodestitches: vec![],
       linestitches: vec![],
       curvedstitches: vec![CurvedStitch {
         points: vec![
-          (1.5666666, 2.0666666),
-          (0.6, 0.8333333),
-          (0.6333333, 0.23333333),
-          (0.79999995, 0.06666667),
-          (1.1333333, 0.2),
-          (1.3666667, 0.56666666),
-          (1.5666666, 2.0666666),
+          (CurveCoord::from_thirtieths(47), CurveCoord::from_thirtieths(62)),
+          (CurveCoord::from_thirtieths(18), CurveCoord::from_thirtieths(25)),
+          (CurveCoord::from_thirtieths(19), CurveCoord::from_thirtieths(7)),
+          (CurveCoord::from_thirtieths(24), CurveCoord::from_thirtieths(2)),
+          (CurveCoord::from_thirtieths(34), CurveCoord::from_thirtieths(6)),
+          (CurveCoord::from_thirtieths(41), CurveCoord::from_thirtieths(17)),
+          (CurveCoord::from_thirtieths(47), CurveCoord::from_thirtieths(62)),
         ],
       }],
     },
     SpecialStitchModel {
       unique_name: String::from("Rhodes Heart - over 6"),
       name: String::from("Rhodes Heart"),
-      width: 3.0,
-      height: 2.5,
+      width: Coord::from_cells(3),
+      height: Coord::from_halves(5),
       nodestitches: vec![],
       linestitches: vec![
         LineStitch {
-          x: (1.0, 2.0),
-          y: (2.0, 0.0),
+          x: (Coord::from_cells(1), Coord::from_cells(2)),
+          y: (Coord::from_cells(2), Coord::from_cells(0)),
           palindex: 0,
           kind: LineStitchKind::Straight,
         },
         LineStitch {
-          x: (0.5, 2.5),
-          y: (1.5, 0.0),
+          x: (Coord::from_halves(1), Coord::from_halves(5)),
+          y: (Coord::from_halves(3), Coord::from_cells(0)),
           palindex: 0,
           kind: LineStitchKind::Straight,
         },
         LineStitch {
-          x: (0.0, 3.0),
-          y: (1.0, 0.5),
+          x: (Coord::from_cells(0), Coord::from_cells(3)),
+          y: (Coord::from_cells(1), Coord::from_halves(1)),
           palindex: 0,
           kind: LineStitchKind::Straight,
         },
         LineStitch {
-          x: (0.0, 3.0),
-          y: (0.5, 1.0),
+          x: (Coord::from_cells(0), Coord::from_cells(3)),
+          y: (Coord::from_halves(1), Coord::from_cells(1)),
           palindex: 0,
           kind: LineStitchKind::Straight,
         },
         LineStitch {
-          x: (0.5, 2.5),
-          y: (0.0, 1.5),
+          x: (Coord::from_halves(1), Coord::from_halves(5)),
+          y: (Coord::from_cells(0), Coord::from_halves(3)),
           palindex: 0,
           kind: LineStitchKind::Straight,
         },
         LineStitch {
-          x: (1.0, 2.0),
-          y: (0.0, 2.0),
+          x: (Coord::from_cells(1), Coord::from_cells(2)),
+          y: (Coord::from_cells(0), Coord::from_cells(2)),
           palindex: 0,
           kind: LineStitchKind::Straight,
         },
         LineStitch {
-          x: (1.5, 1.5),
-          y: (0.5, 2.5),
+          x: (Coord::from_halves(3), Coord::from_halves(3)),
+          y: (Coord::from_halves(1), Coord::from_halves(5)),
           palindex: 0,
           kind: LineStitchKind::Straight,
         },
@@ -569,22 +577,22 @@ fn reads_joints() {
 
   let expected_nodestitches = [
     NodeStitch {
-      x: 3.0,
-      y: 3.0,
+      x: Coord::from_cells(3),
+      y: Coord::from_cells(3),
       rotated: false,
       palindex: 0,
       kind: NodeStitchKind::FrenchKnot,
     },
     NodeStitch {
-      x: 3.0,
-      y: 4.5,
+      x: Coord::from_cells(3),
+      y: Coord::from_halves(9),
       rotated: false,
       palindex: 2,
       kind: NodeStitchKind::Bead,
     },
     NodeStitch {
-      x: 3.0,
-      y: 5.5,
+      x: Coord::from_cells(3),
+      y: Coord::from_halves(11),
       rotated: true,
       palindex: 2,
       kind: NodeStitchKind::Bead,
@@ -596,26 +604,26 @@ fn reads_joints() {
 
   let expected_linestitches = [
     LineStitch {
-      x: (1.0, 2.0),
-      y: (1.0, 2.0),
+      x: (Coord::from_cells(1), Coord::from_cells(2)),
+      y: (Coord::from_cells(1), Coord::from_cells(2)),
       palindex: 1,
       kind: LineStitchKind::Back,
     },
     LineStitch {
-      x: (3.0, 4.0),
-      y: (2.0, 1.0),
+      x: (Coord::from_cells(3), Coord::from_cells(4)),
+      y: (Coord::from_cells(2), Coord::from_cells(1)),
       palindex: 1,
       kind: LineStitchKind::Back,
     },
     LineStitch {
-      x: (4.0, 5.0),
-      y: (1.0, 1.0),
+      x: (Coord::from_cells(4), Coord::from_cells(5)),
+      y: (Coord::from_cells(1), Coord::from_cells(1)),
       palindex: 1,
       kind: LineStitchKind::Back,
     },
     LineStitch {
-      x: (1.0, 5.0),
-      y: (2.0, 2.0),
+      x: (Coord::from_cells(1), Coord::from_cells(5)),
+      y: (Coord::from_cells(2), Coord::from_cells(2)),
       palindex: 1,
       kind: LineStitchKind::Straight,
     },
@@ -626,72 +634,72 @@ fn reads_joints() {
 
   let expected_special_stitches = [
     SpecialStitch {
-      x: 5.5,
-      y: 1.0,
+      x: Coord::from_halves(11),
+      y: Coord::from_cells(1),
       rotation: 0,
       flip: (false, false),
       palindex: 0,
       modindex: 0,
     },
     SpecialStitch {
-      x: 9.0,
-      y: 1.0,
+      x: Coord::from_cells(9),
+      y: Coord::from_cells(1),
       rotation: 0,
       flip: (true, false),
       palindex: 0,
       modindex: 0,
     },
     SpecialStitch {
-      x: 8.5,
-      y: 3.0,
+      x: Coord::from_halves(17),
+      y: Coord::from_cells(3),
       rotation: 0,
       flip: (false, true),
       palindex: 0,
       modindex: 0,
     },
     SpecialStitch {
-      x: 12.0,
-      y: 3.0,
+      x: Coord::from_cells(12),
+      y: Coord::from_cells(3),
       rotation: 0,
       flip: (true, true),
       palindex: 0,
       modindex: 0,
     },
     SpecialStitch {
-      x: 9.0,
-      y: 4.5,
+      x: Coord::from_cells(9),
+      y: Coord::from_halves(9),
       rotation: 90,
       flip: (false, false),
       palindex: 0,
       modindex: 0,
     },
     SpecialStitch {
-      x: 9.0,
-      y: 5.5,
+      x: Coord::from_cells(9),
+      y: Coord::from_halves(11),
       rotation: 270,
       flip: (false, false),
       palindex: 0,
       modindex: 0,
     },
     SpecialStitch {
-      x: 9.0,
-      y: 6.5,
+      x: Coord::from_cells(9),
+      y: Coord::from_halves(13),
       rotation: 90,
       flip: (false, true),
       palindex: 0,
       modindex: 0,
     },
     SpecialStitch {
-      x: 9.0,
-      y: 8.0,
+      x: Coord::from_cells(9),
+      y: Coord::from_cells(8),
       rotation: 90,
       flip: (true, false),
       palindex: 0,
       modindex: 0,
     },
     SpecialStitch {
-      x: 11.0,
-      y: 5.0,
+      x: Coord::from_cells(11),
+      y: Coord::from_cells(5),
       rotation: 0,
       flip: (false, false),
       palindex: 1,
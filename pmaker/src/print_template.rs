@@ -0,0 +1,138 @@
+//! The header and footer templates of the print settings.
+//!
+//! Pattern Maker stores them as raw strings up to 119 bytes long, e.g. `&l&t &r&n`.
+//! The `&` codes switch the alignment of the following text or insert a field.
+//! Only the codes used by the Pattern Maker files in the test data are supported:
+//!
+//! | Code | Meaning                                |
+//! | ---- | -------------------------------------- |
+//! | `&l` | Align the following text to the left.  |
+//! | `&r` | Align the following text to the right. |
+//! | `&t` | The pattern title.                     |
+//! | `&n` | The page number.                       |
+//!
+//! The text before the first alignment code is centered, and the other `&` characters are printed as is.
+
+use std::fmt;
+
+use crate::schemas::xsd::Pattern;
+
+#[cfg(test)]
+#[path = "print_template.test.rs"]
+mod tests;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemplateField {
+  Title,
+  PageNumber,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemplateAlignment {
+  Left,
+  Right,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplatePart {
+  Text(String),
+  Field(TemplateField),
+  Align(TemplateAlignment),
+}
+
+/// A parsed header or footer template.
+///
+/// The parts are kept in the order of the template, so it is formatted back exactly as it was parsed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PrintTemplate {
+  pub parts: Vec<TemplatePart>,
+}
+
+/// The page that a template is rendered for.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PageContext {
+  /// The page number, starting from 1.
+  pub page_number: usize,
+}
+
+/// A template rendered for a page.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RenderedTemplate {
+  pub left: String,
+  pub center: String,
+  pub right: String,
+}
+
+impl RenderedTemplate {
+  pub fn is_empty(&self) -> bool {
+    self.left.is_empty() && self.center.is_empty() && self.right.is_empty()
+  }
+}
+
+impl PrintTemplate {
+  pub fn parse(template: &str) -> Self {
+    let mut parts = Vec::new();
+    let mut chars = template.chars().peekable();
+    while let Some(ch) = chars.next() {
+      let part = match (ch, chars.peek()) {
+        ('&', Some('l')) => TemplatePart::Align(TemplateAlignment::Left),
+        ('&', Some('r')) => TemplatePart::Align(TemplateAlignment::Right),
+        ('&', Some('t')) => TemplatePart::Field(TemplateField::Title),
+        ('&', Some('n')) => TemplatePart::Field(TemplateField::PageNumber),
+        _ => {
+          push_text(&mut parts, ch);
+          continue;
+        }
+      };
+      chars.next();
+      parts.push(part);
+    }
+    PrintTemplate { parts }
+  }
+
+  /// Returns whether the template contains the field.
+  pub fn contains(&self, field: TemplateField) -> bool {
+    self.parts.contains(&TemplatePart::Field(field))
+  }
+
+  pub fn render(&self, pattern: &Pattern, page: &PageContext) -> RenderedTemplate {
+    let mut rendered = RenderedTemplate::default();
+    let mut section = &mut rendered.center;
+    for part in &self.parts {
+      match part {
+        TemplatePart::Text(text) => section.push_str(text),
+        TemplatePart::Field(TemplateField::Title) => section.push_str(&pattern.info.title),
+        TemplatePart::Field(TemplateField::PageNumber) => section.push_str(&page.page_number.to_string()),
+        TemplatePart::Align(TemplateAlignment::Left) => section = &mut rendered.left,
+        TemplatePart::Align(TemplateAlignment::Right) => section = &mut rendered.right,
+      }
+    }
+    for section in [&mut rendered.left, &mut rendered.center, &mut rendered.right] {
+      *section = section.trim().to_string();
+    }
+    rendered
+  }
+}
+
+impl fmt::Display for PrintTemplate {
+  /// Formats the template back into the Pattern Maker string.
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for part in &self.parts {
+      f.write_str(match part {
+        TemplatePart::Text(text) => text,
+        TemplatePart::Field(TemplateField::Title) => "&t",
+        TemplatePart::Field(TemplateField::PageNumber) => "&n",
+        TemplatePart::Align(TemplateAlignment::Left) => "&l",
+        TemplatePart::Align(TemplateAlignment::Right) => "&r",
+      })?;
+    }
+    Ok(())
+  }
+}
+
+fn push_text(parts: &mut Vec<TemplatePart>, ch: char) {
+  match parts.last_mut() {
+    Some(TemplatePart::Text(text)) => text.push(ch),
+    _ => parts.push(TemplatePart::Text(ch.to_string())),
+  }
+}
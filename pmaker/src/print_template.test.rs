@@ -0,0 +1,82 @@
+use super::*;
+use crate::builder::PatternBuilder;
+use crate::schemas::xsd::PatternInfo;
+
+fn pattern() -> Pattern {
+  let mut builder = PatternBuilder::new(10, 10);
+  builder.info(PatternInfo {
+    title: String::from("Roses"),
+    author: String::from("Nazar Antoniuk"),
+    company: String::from("Embroidery Studio"),
+    copyright: String::from("© 2025"),
+    description: String::new(),
+  });
+  builder.build().unwrap()
+}
+
+fn page(page_number: usize) -> PageContext {
+  PageContext { page_number }
+}
+
+#[test]
+fn parses_default_header() {
+  let template = PrintTemplate::parse("&l&t &r&n");
+  assert_eq!(
+    template.parts,
+    [
+      TemplatePart::Align(TemplateAlignment::Left),
+      TemplatePart::Field(TemplateField::Title),
+      TemplatePart::Text(String::from(" ")),
+      TemplatePart::Align(TemplateAlignment::Right),
+      TemplatePart::Field(TemplateField::PageNumber),
+    ]
+  );
+  assert!(template.contains(TemplateField::PageNumber));
+
+  assert_eq!(
+    template.render(&pattern(), &page(3)),
+    RenderedTemplate {
+      left: String::from("Roses"),
+      center: String::new(),
+      right: String::from("3"),
+    }
+  );
+}
+
+#[test]
+fn renders_sections() {
+  let template = PrintTemplate::parse("Pattern &t&lLeft&rPage &n&lMore");
+  let rendered = template.render(&pattern(), &page(7));
+  assert_eq!(rendered.left, "LeftMore");
+  assert_eq!(rendered.center, "Pattern Roses");
+  assert_eq!(rendered.right, "Page 7");
+
+  assert!(PrintTemplate::parse("").render(&pattern(), &page(1)).is_empty());
+}
+
+#[test]
+fn keeps_unknown_codes_as_text() {
+  let template = PrintTemplate::parse("&lRoses && Tulips &d&c&T&");
+  assert_eq!(
+    template.parts,
+    [
+      TemplatePart::Align(TemplateAlignment::Left),
+      TemplatePart::Text(String::from("Roses && Tulips &d&c&T&")),
+    ]
+  );
+  assert_eq!(template.render(&pattern(), &page(1)).left, "Roses && Tulips &d&c&T&");
+}
+
+#[test]
+fn formats_templates() {
+  for template in [
+    "&l&t &r&n",
+    "&t",
+    "Page &n&&&",
+    "&lRoses && Tulips&cPage &n of &p&r&d &f",
+    "",
+    "&",
+  ] {
+    assert_eq!(PrintTemplate::parse(template).to_string(), template);
+  }
+}
@@ -0,0 +1,438 @@
+//! The chart of a pattern as a list of shapes that every output format draws in the same way.
+//!
+//! The positions are in cells, while the thicknesses and radii are in the units of the output, usually points.
+
+use crate::schemas::color::Rgb;
+use crate::schemas::xsd::*;
+
+#[cfg(test)]
+#[path = "chart.test.rs"]
+mod tests;
+
+/// Which grid line styles of the pattern are used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GridLines {
+  Screen,
+  Printer,
+}
+
+/// How the half and quarter stitches are shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FractionalStitchShape {
+  /// A stitch is a triangle whose long side is the stitch itself.
+  Triangles,
+  /// A quarter stitch is a quarter of the cell and a half stitch is two quarters along its diagonal.
+  HalfCells,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChartOptions {
+  /// The size of a cell in the units of the output.
+  pub cell_size: f32,
+  pub grid_lines: GridLines,
+  pub fractional_stitch_shape: FractionalStitchShape,
+}
+
+impl Default for ChartOptions {
+  fn default() -> Self {
+    ChartOptions {
+      cell_size: 10.0,
+      grid_lines: GridLines::Printer,
+      fractional_stitch_shape: FractionalStitchShape::Triangles,
+    }
+  }
+}
+
+pub(crate) type Point = (f32, f32);
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Shape {
+  Rect {
+    origin: Point,
+    size: Point,
+    fill: Rgb,
+  },
+  Polygon {
+    points: Vec<Point>,
+    fill: Rgb,
+  },
+  Line {
+    from: Point,
+    to: Point,
+    color: Rgb,
+    thickness: f32,
+  },
+  Polyline {
+    points: Vec<Point>,
+    color: Rgb,
+    thickness: f32,
+  },
+  Circle {
+    center: Point,
+    radius: f32,
+    fill: Rgb,
+  },
+  Symbol {
+    center: Point,
+    /// The font size relative to the cell size.
+    size: f32,
+    symbol: char,
+    font: String,
+    bold: bool,
+    italic: bool,
+    color: Rgb,
+  },
+}
+
+/// The shapes of the chart in the drawing order.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ChartScene {
+  pub width: u16,
+  pub height: u16,
+  pub shapes: Vec<Shape>,
+}
+
+impl ChartScene {
+  pub fn new(pattern: &Pattern, options: &ChartOptions) -> Self {
+    let mut builder = SceneBuilder {
+      pattern,
+      options,
+      shapes: Vec::new(),
+    };
+
+    builder.shapes.push(Shape::Rect {
+      origin: (0.0, 0.0),
+      size: (pattern.fabric.width.into(), pattern.fabric.height.into()),
+      fill: pattern.fabric.color,
+    });
+
+    let mut symbols = Vec::new();
+    for stitch in pattern.fullstitches.iter() {
+      builder.add_fullstitch(stitch, &mut symbols);
+    }
+    for stitch in pattern.partstitches.iter() {
+      builder.add_partstitch(stitch, &mut symbols);
+    }
+
+    builder.add_grid();
+
+    let draw_symbols_over_backstitches = pattern.symbol_settings.draw_symbols_over_backstitches;
+    if !draw_symbols_over_backstitches {
+      builder.shapes.append(&mut symbols);
+    }
+    for stitch in pattern.linestitches.iter() {
+      builder.add_linestitch(stitch);
+    }
+    for stitch in pattern.specialstitches.iter() {
+      builder.add_specialstitch(stitch);
+    }
+    if draw_symbols_over_backstitches {
+      builder.shapes.append(&mut symbols);
+    }
+    for stitch in pattern.nodestitches.iter() {
+      builder.add_nodestitch(stitch);
+    }
+
+    ChartScene {
+      width: pattern.fabric.width,
+      height: pattern.fabric.height,
+      shapes: builder.shapes,
+    }
+  }
+}
+
+struct SceneBuilder<'a> {
+  pattern: &'a Pattern,
+  options: &'a ChartOptions,
+  shapes: Vec<Shape>,
+}
+
+impl SceneBuilder<'_> {
+  fn formats(&self, palindex: u8) -> Formats {
+    self.pattern.formats.get(palindex as usize).cloned().unwrap_or_default()
+  }
+
+  fn palette_color(&self, palindex: u8) -> Rgb {
+    self
+      .pattern
+      .palette
+      .get(palindex as usize)
+      .map(|palitem| palitem.color)
+      .unwrap_or(Rgb::BLACK)
+  }
+
+  /// Returns the background of the stitch symbol, if it has one.
+  fn symbol_background(&self, palindex: u8) -> Option<Rgb> {
+    let formats = self.formats(palindex);
+    if formats.symbol.use_alt_bg_color {
+      Some(formats.symbol.bg_color)
+    } else if self.pattern.symbol_settings.show_stitch_color {
+      Some(self.palette_color(palindex))
+    } else {
+      None
+    }
+  }
+
+  /// Creates a symbol shape for the palette item, if it has the symbol.
+  fn symbol(&self, palindex: u8, code: Option<u16>, center: Point, small: bool) -> Option<Shape> {
+    let symbols = self.pattern.symbols.get(palindex as usize)?;
+    let symbol = char::from_u32(code.or(symbols.full)?.into())?;
+    let formats = self.formats(palindex);
+    let size = if small {
+      formats.font.small_stitch_size
+    } else {
+      formats.font.stitch_size
+    };
+    Some(Shape::Symbol {
+      center,
+      size: f32::from(size) / 100.0,
+      symbol,
+      font: formats
+        .font
+        .font_name
+        .clone()
+        .unwrap_or_else(|| self.pattern.pattern_settings.default_stitch_font.clone()),
+      bold: formats.font.bold,
+      italic: formats.font.italic,
+      color: formats.symbol.fg_color,
+    })
+  }
+
+  fn add_fullstitch(&mut self, stitch: &FullStitch, symbols: &mut Vec<Shape>) {
+    let (x, y) = (stitch.x.to_f32(), stitch.y.to_f32());
+    let (size, code, small) = match stitch.kind {
+      FullStitchKind::Full => (1.0, None, false),
+      FullStitchKind::Petite => (
+        0.5,
+        self
+          .pattern
+          .symbols
+          .get(stitch.palindex as usize)
+          .and_then(|s| s.petite),
+        true,
+      ),
+    };
+
+    if let Some(fill) = self.symbol_background(stitch.palindex) {
+      self.shapes.push(Shape::Rect {
+        origin: (x, y),
+        size: (size, size),
+        fill,
+      });
+    }
+    let center = (x + size / 2.0, y + size / 2.0);
+    symbols.extend(self.symbol(stitch.palindex, code, center, small));
+  }
+
+  fn add_partstitch(&mut self, stitch: &PartStitch, symbols: &mut Vec<Shape>) {
+    let (x, y) = (stitch.x.to_f32(), stitch.y.to_f32());
+    let size = match stitch.kind {
+      PartStitchKind::Half => 1.0,
+      PartStitchKind::Quarter => 0.5,
+    };
+
+    // The ends of the stitch line.
+    let (start, end) = match stitch.direction {
+      PartStitchDirection::Forward => ((x, y + size), (x + size, y)),
+      PartStitchDirection::Backward => ((x, y), (x + size, y + size)),
+    };
+
+    let fill = self
+      .symbol_background(stitch.palindex)
+      .unwrap_or_else(|| self.palette_color(stitch.palindex));
+    match self.options.fractional_stitch_shape {
+      FractionalStitchShape::Triangles => {
+        // The triangle lies below the stitch line.
+        let corner = match stitch.direction {
+          PartStitchDirection::Forward => (x + size, y + size),
+          PartStitchDirection::Backward => (x, y + size),
+        };
+        self.shapes.push(Shape::Polygon {
+          points: vec![start, end, corner],
+          fill,
+        });
+      }
+      FractionalStitchShape::HalfCells => match stitch.kind {
+        PartStitchKind::Half => {
+          // The quarters of the cell that the stitch line crosses.
+          for (qx, qy) in [start, end] {
+            self.shapes.push(Shape::Rect {
+              origin: (qx.min(x + 0.5), qy.min(y + 0.5)),
+              size: (0.5, 0.5),
+              fill,
+            });
+          }
+        }
+        PartStitchKind::Quarter => self.shapes.push(Shape::Rect {
+          origin: (x, y),
+          size: (0.5, 0.5),
+          fill,
+        }),
+      },
+    }
+
+    let symbols_table = self.pattern.symbols.get(stitch.palindex as usize);
+    let (code, small) = match stitch.kind {
+      PartStitchKind::Half => (
+        symbols_table.and_then(|s| s.half),
+        !self.pattern.symbol_settings.use_large_half_stitch_symbol,
+      ),
+      PartStitchKind::Quarter => (symbols_table.and_then(|s| s.quarter), true),
+    };
+    let center = (x + size / 2.0, y + size / 2.0);
+    symbols.extend(self.symbol(stitch.palindex, code, center, small));
+  }
+
+  fn add_grid(&mut self) {
+    let grid = &self.pattern.grid;
+    let (minor, major) = match self.options.grid_lines {
+      GridLines::Screen => (&grid.minor_screen_lines, &grid.major_screen_lines),
+      GridLines::Printer => (&grid.minor_printer_lines, &grid.major_printer_lines),
+    };
+    let (width, height) = (self.pattern.fabric.width, self.pattern.fabric.height);
+    let interval = grid.major_lines_interval.max(1);
+    let is_major = |line: u16, last: u16| line.is_multiple_of(interval) || line == last;
+
+    // The major lines are drawn over the minor ones.
+    for style_is_major in [false, true] {
+      let style = if style_is_major { major } else { minor };
+      for column in (0..=width).filter(|&column| is_major(column, width) == style_is_major) {
+        self.shapes.push(Shape::Line {
+          from: (column.into(), 0.0),
+          to: (column.into(), height.into()),
+          color: style.color,
+          thickness: style.thickness,
+        });
+      }
+      for row in (0..=height).filter(|&row| is_major(row, height) == style_is_major) {
+        self.shapes.push(Shape::Line {
+          from: (0.0, row.into()),
+          to: (width.into(), row.into()),
+          color: style.color,
+          thickness: style.thickness,
+        });
+      }
+    }
+  }
+
+  fn line_format_color(&self, format: &LineStitchFormat, palindex: u8) -> Rgb {
+    if format.use_alt_color {
+      format.color
+    } else {
+      self.palette_color(palindex)
+    }
+  }
+
+  fn add_linestitch(&mut self, stitch: &LineStitch) {
+    let formats = self.formats(stitch.palindex);
+    let format = match stitch.kind {
+      LineStitchKind::Back => &formats.back_stitch,
+      LineStitchKind::Straight => &formats.straight_stitch,
+    };
+    self.shapes.push(Shape::Line {
+      from: (stitch.x.0.to_f32(), stitch.y.0.to_f32()),
+      to: (stitch.x.1.to_f32(), stitch.y.1.to_f32()),
+      color: self.line_format_color(format, stitch.palindex),
+      thickness: format.thickness,
+    });
+  }
+
+  /// Draws the line and curved stitches of the special stitch model, rotated and flipped as the special stitch is.
+  fn add_specialstitch(&mut self, stitch: &SpecialStitch) {
+    let Some(model) = self.pattern.special_stitch_models.get(stitch.modindex as usize) else {
+      return;
+    };
+    let format = self.formats(stitch.palindex).special_stitch;
+    let color = self.line_format_color(&format, stitch.palindex);
+    let transform = SpecialStitchTransform::new(stitch, model);
+
+    for linestitch in model.linestitches.iter() {
+      self.shapes.push(Shape::Line {
+        from: transform.apply(linestitch.x.0.to_f32(), linestitch.y.0.to_f32()),
+        to: transform.apply(linestitch.x.1.to_f32(), linestitch.y.1.to_f32()),
+        color,
+        thickness: format.thickness,
+      });
+    }
+    for curve in model.curvedstitches.iter() {
+      self.shapes.push(Shape::Polyline {
+        points: curve
+          .points
+          .iter()
+          .map(|(x, y)| transform.apply(x.to_f32(), y.to_f32()))
+          .collect(),
+        color,
+        thickness: format.thickness,
+      });
+    }
+  }
+
+  fn add_nodestitch(&mut self, stitch: &NodeStitch) {
+    let formats = self.formats(stitch.palindex);
+    let (format, code) = match stitch.kind {
+      NodeStitchKind::FrenchKnot => (
+        &formats.french_knot,
+        self
+          .pattern
+          .symbols
+          .get(stitch.palindex as usize)
+          .and_then(|s| s.french_knot),
+      ),
+      NodeStitchKind::Bead => (
+        &formats.bead,
+        self.pattern.symbols.get(stitch.palindex as usize).and_then(|s| s.bead),
+      ),
+    };
+    let center = (stitch.x.to_f32(), stitch.y.to_f32());
+
+    if !format.use_dot_style
+      && let Some(symbol) = code.and_then(|code| self.symbol(stitch.palindex, Some(code), center, true))
+    {
+      self.shapes.push(symbol);
+      return;
+    }
+
+    self.shapes.push(Shape::Circle {
+      center,
+      radius: format.thickness / 2.0,
+      fill: if format.use_alt_color {
+        format.color
+      } else {
+        self.palette_color(stitch.palindex)
+      },
+    });
+  }
+}
+
+/// Places the points of a special stitch model on the pattern.
+pub(crate) struct SpecialStitchTransform {
+  origin: Point,
+  size: Point,
+  rotation: u16,
+  flip: (bool, bool),
+}
+
+impl SpecialStitchTransform {
+  pub fn new(stitch: &SpecialStitch, model: &SpecialStitchModel) -> Self {
+    SpecialStitchTransform {
+      origin: (stitch.x.to_f32(), stitch.y.to_f32()),
+      size: (model.width.to_f32(), model.height.to_f32()),
+      rotation: stitch.rotation % 360,
+      flip: stitch.flip,
+    }
+  }
+
+  pub fn apply(&self, x: f32, y: f32) -> Point {
+    let (width, height) = self.size;
+    let x = if self.flip.0 { width - x } else { x };
+    let y = if self.flip.1 { height - y } else { y };
+    // Rotate clockwise keeping the model in the same bounding box corner.
+    let (x, y) = match self.rotation {
+      90 => (height - y, x),
+      180 => (width - x, height - y),
+      270 => (y, width - x),
+      _ => (x, y),
+    };
+    (self.origin.0 + x, self.origin.1 + y)
+  }
+}
@@ -0,0 +1,299 @@
+use super::*;
+use crate::builder::PatternBuilder;
+use crate::schemas::coords::Coord;
+
+fn symbols(full: char) -> Symbols {
+  Symbols {
+    full: Some(full as u16),
+    ..Default::default()
+  }
+}
+
+fn polygons(scene: &ChartScene) -> Vec<&Vec<Point>> {
+  let mut polygons = Vec::new();
+  for shape in scene.shapes.iter() {
+    if let Shape::Polygon { points, .. } = shape {
+      polygons.push(points);
+    }
+  }
+  polygons
+}
+
+fn rects(scene: &ChartScene) -> Vec<(Point, Point)> {
+  let mut rects = Vec::new();
+  for shape in scene.shapes.iter() {
+    if let Shape::Rect { origin, size, .. } = shape {
+      rects.push((*origin, *size));
+    }
+  }
+  rects
+}
+
+#[test]
+fn draws_grid() {
+  let pattern = PatternBuilder::new(12, 3).build().unwrap();
+  let scene = ChartScene::new(&pattern, &ChartOptions::default());
+
+  let grid = &pattern.grid;
+  let lines: Vec<_> = scene
+    .shapes
+    .iter()
+    .filter_map(|shape| match shape {
+      Shape::Line {
+        from,
+        to,
+        color,
+        thickness,
+      } => Some((*from, *to, *color, *thickness)),
+      _ => None,
+    })
+    .collect();
+  assert_eq!(lines.len(), 13 + 4);
+
+  let major: Vec<_> = lines
+    .iter()
+    .filter(|line| line.3 == grid.major_printer_lines.thickness)
+    .map(|line| (line.0, line.1))
+    .collect();
+  // The major lines are drawn at every interval and on the borders.
+  assert_eq!(
+    major,
+    [
+      ((0.0, 0.0), (0.0, 3.0)),
+      ((10.0, 0.0), (10.0, 3.0)),
+      ((12.0, 0.0), (12.0, 3.0)),
+      ((0.0, 0.0), (12.0, 0.0)),
+      ((0.0, 3.0), (12.0, 3.0)),
+    ]
+  );
+
+  let options = ChartOptions {
+    grid_lines: GridLines::Screen,
+    ..Default::default()
+  };
+  let scene = ChartScene::new(&pattern, &options);
+  assert!(scene.shapes.iter().any(|shape| matches!(
+    shape,
+    Shape::Line { color, .. } if *color == grid.minor_screen_lines.color
+  )));
+}
+
+#[test]
+fn draws_fractional_stitches() {
+  let mut builder = PatternBuilder::new(2, 1);
+  let palindex = builder
+    .add_palette_item_with(
+      PaletteItem::new("DMC", "310", Rgb::BLACK),
+      Formats::default(),
+      symbols('X'),
+    )
+    .unwrap();
+  builder
+    .add_partstitch(PartStitch {
+      x: Coord::ZERO,
+      y: Coord::ZERO,
+      palindex,
+      direction: PartStitchDirection::Forward,
+      kind: PartStitchKind::Half,
+    })
+    .add_partstitch(PartStitch {
+      x: Coord::from_halves(3),
+      y: Coord::HALF,
+      palindex,
+      direction: PartStitchDirection::Backward,
+      kind: PartStitchKind::Quarter,
+    });
+  let pattern = builder.build().unwrap();
+
+  let scene = ChartScene::new(&pattern, &ChartOptions::default());
+  assert_eq!(
+    polygons(&scene),
+    [
+      &vec![(0.0, 1.0), (1.0, 0.0), (1.0, 1.0)],
+      &vec![(1.5, 0.5), (2.0, 1.0), (1.5, 1.0)],
+    ]
+  );
+
+  let options = ChartOptions {
+    fractional_stitch_shape: FractionalStitchShape::HalfCells,
+    ..Default::default()
+  };
+  let scene = ChartScene::new(&pattern, &options);
+  assert!(polygons(&scene).is_empty());
+  assert_eq!(
+    rects(&scene)[1..],
+    [
+      ((0.0, 0.5), (0.5, 0.5)),
+      ((0.5, 0.0), (0.5, 0.5)),
+      ((1.5, 0.5), (0.5, 0.5)),
+    ]
+  );
+}
+
+#[test]
+fn sizes_symbols() {
+  let mut builder = PatternBuilder::new(2, 2);
+  let palindex = builder
+    .add_palette_item_with(
+      PaletteItem::new("DMC", "310", Rgb::BLACK),
+      Formats::default(),
+      Symbols {
+        petite: Some('p' as u16),
+        ..symbols('X')
+      },
+    )
+    .unwrap();
+  builder
+    .add_fullstitch(FullStitch {
+      x: Coord::ZERO,
+      y: Coord::ZERO,
+      palindex,
+      kind: FullStitchKind::Full,
+    })
+    .add_fullstitch(FullStitch {
+      x: Coord::from_halves(3),
+      y: Coord::from_halves(3),
+      palindex,
+      kind: FullStitchKind::Petite,
+    });
+  let pattern = builder.build().unwrap();
+  let scene = ChartScene::new(&pattern, &ChartOptions::default());
+
+  let symbols: Vec<_> = scene
+    .shapes
+    .iter()
+    .filter_map(|shape| match shape {
+      Shape::Symbol {
+        center,
+        size,
+        symbol,
+        font,
+        ..
+      } => Some((*center, *size, *symbol, font.as_str())),
+      _ => None,
+    })
+    .collect();
+  assert_eq!(
+    symbols,
+    [
+      ((0.5, 0.5), 1.0, 'X', "CrossStitch3"),
+      ((1.75, 1.75), 0.6, 'p', "CrossStitch3"),
+    ]
+  );
+  // The symbols have no background by default.
+  assert_eq!(rects(&scene).len(), 1);
+}
+
+#[test]
+fn orders_layers() {
+  let mut builder = PatternBuilder::new(1, 1);
+  let palindex = builder
+    .add_palette_item_with(
+      PaletteItem::new("DMC", "310", Rgb::BLACK),
+      Formats::default(),
+      symbols('X'),
+    )
+    .unwrap();
+  builder
+    .add_fullstitch(FullStitch {
+      x: Coord::ZERO,
+      y: Coord::ZERO,
+      palindex,
+      kind: FullStitchKind::Full,
+    })
+    .add_linestitch(LineStitch {
+      x: (Coord::ZERO, Coord::from_cells(1)),
+      y: (Coord::ZERO, Coord::from_cells(1)),
+      palindex,
+      kind: LineStitchKind::Back,
+    });
+  let mut pattern = builder.build().unwrap();
+
+  let symbol_position = |pattern: &Pattern| {
+    let scene = ChartScene::new(pattern, &ChartOptions::default());
+    let symbol = scene
+      .shapes
+      .iter()
+      .position(|shape| matches!(shape, Shape::Symbol { .. }))
+      .unwrap();
+    (symbol, scene.shapes.len())
+  };
+
+  let (symbol, count) = symbol_position(&pattern);
+  assert_eq!(symbol, count - 2);
+
+  pattern.symbol_settings.draw_symbols_over_backstitches = true;
+  let (symbol, count) = symbol_position(&pattern);
+  assert_eq!(symbol, count - 1);
+}
+
+#[test]
+fn draws_node_stitches() {
+  let mut formats = Formats::default();
+  formats.bead.use_dot_style = false;
+  formats.french_knot.use_alt_color = true;
+  formats.french_knot.color = Rgb::WHITE;
+
+  let mut builder = PatternBuilder::new(1, 1);
+  let palindex = builder
+    .add_palette_item_with(
+      PaletteItem::new("DMC", "310", Rgb::BLACK),
+      formats,
+      Symbols {
+        bead: Some('o' as u16),
+        ..Default::default()
+      },
+    )
+    .unwrap();
+  for kind in [NodeStitchKind::FrenchKnot, NodeStitchKind::Bead] {
+    builder.add_nodestitch(NodeStitch {
+      x: Coord::HALF,
+      y: Coord::HALF,
+      rotated: false,
+      palindex,
+      kind,
+    });
+  }
+  let pattern = builder.build().unwrap();
+  let scene = ChartScene::new(&pattern, &ChartOptions::default());
+
+  let nodes = &scene.shapes[scene.shapes.len() - 2..];
+  assert_eq!(
+    nodes[0],
+    Shape::Circle {
+      center: (0.5, 0.5),
+      radius: 2.0,
+      fill: Rgb::WHITE,
+    }
+  );
+  assert!(matches!(nodes[1], Shape::Symbol { symbol: 'o', .. }));
+}
+
+#[test]
+fn transforms_special_stitches() {
+  let stitch = SpecialStitch {
+    x: Coord::from_cells(2),
+    y: Coord::from_cells(3),
+    rotation: 90,
+    flip: (false, false),
+    palindex: 0,
+    modindex: 0,
+  };
+  let model = SpecialStitchModel {
+    width: Coord::from_cells(2),
+    height: Coord::from_cells(1),
+    ..Default::default()
+  };
+
+  let transform = SpecialStitchTransform::new(&stitch, &model);
+  assert_eq!(transform.apply(0.0, 0.0), (3.0, 3.0));
+  assert_eq!(transform.apply(2.0, 1.0), (2.0, 5.0));
+
+  let flipped = SpecialStitch {
+    rotation: 0,
+    flip: (true, false),
+    ..stitch
+  };
+  let transform = SpecialStitchTransform::new(&flipped, &model);
+  assert_eq!(transform.apply(0.0, 0.0), (4.0, 3.0));
+}
@@ -0,0 +1,233 @@
+//! The layout of a chart split into printed pages.
+//!
+//! It is pure geometry shared by all the printable outputs.
+//! The lengths are in points, 72 per inch, measured from the top left corner of the page.
+
+use std::ops::Range;
+
+use anyhow::{Result, bail};
+
+use super::chart::Point;
+use crate::schemas::xsd::{Pattern, PrintSettings};
+
+#[cfg(test)]
+#[path = "layout.test.rs"]
+mod tests;
+
+pub const POINTS_PER_INCH: f32 = 72.0;
+
+/// The size of a paper sheet in inches.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PaperSize {
+  pub width: f32,
+  pub height: f32,
+}
+
+impl PaperSize {
+  pub const LETTER: PaperSize = PaperSize {
+    width: 8.5,
+    height: 11.0,
+  };
+  pub const LEGAL: PaperSize = PaperSize {
+    width: 8.5,
+    height: 14.0,
+  };
+  pub const A4: PaperSize = PaperSize {
+    width: 8.27,
+    height: 11.69,
+  };
+  pub const A3: PaperSize = PaperSize {
+    width: 11.69,
+    height: 16.54,
+  };
+
+  /// Returns the paper size with swapped sides.
+  pub fn landscape(self) -> Self {
+    PaperSize {
+      width: self.height,
+      height: self.width,
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PageLayoutOptions {
+  pub paper_size: PaperSize,
+  /// The size of a cell in points at the printer spacing of 1.
+  /// The actual cell size is multiplied by `SymbolSettings.printer_spacing` for each axis.
+  pub cell_size: f32,
+  /// The number of rows and columns that are repeated from the previous page.
+  pub overlap: u16,
+}
+
+impl Default for PageLayoutOptions {
+  fn default() -> Self {
+    PageLayoutOptions {
+      paper_size: PaperSize::LETTER,
+      cell_size: 7.2,
+      overlap: 2,
+    }
+  }
+}
+
+/// The area of a page inside the margins that is left after the header and footer lines.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PrintArea {
+  pub left: f32,
+  pub top: f32,
+  pub width: f32,
+  pub height: f32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PageLayout {
+  pub page_size: Point,
+  pub cell_size: Point,
+  pub print_area: PrintArea,
+  /// The number of pages across and down the chart.
+  pub page_grid: (usize, usize),
+  /// The chart pages from left to right and from top to bottom.
+  pub pages: Vec<ChartPage>,
+}
+
+/// A part of the chart printed on a page.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChartPage {
+  /// The page number, starting from 1.
+  pub number: usize,
+  pub columns: Range<u16>,
+  pub rows: Range<u16>,
+  /// The offset of the chart part from the top left corner of the print area, non-zero when the chart is centered.
+  pub offset: Point,
+  pub adjacent_pages: AdjacentPages,
+}
+
+/// The numbers of the pages that continue the chart on each side.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AdjacentPages {
+  pub top: Option<usize>,
+  pub bottom: Option<usize>,
+  pub left: Option<usize>,
+  pub right: Option<usize>,
+}
+
+impl PageLayout {
+  /// Returns the position of the top left corner of the chart part on the page.
+  pub fn origin(&self, page: &ChartPage) -> Point {
+    (
+      self.print_area.left + page.offset.0,
+      self.print_area.top + page.offset.1,
+    )
+  }
+
+  /// Returns the width and the height of the chart part on the page.
+  pub fn chart_size(&self, page: &ChartPage) -> Point {
+    (
+      f32::from(page.columns.end - page.columns.start) * self.cell_size.0,
+      f32::from(page.rows.end - page.rows.start) * self.cell_size.1,
+    )
+  }
+}
+
+/// Computes how the chart of the pattern tiles into pages.
+pub fn layout_pages(pattern: &Pattern, options: &PageLayoutOptions) -> Result<PageLayout> {
+  let page_size = (
+    options.paper_size.width * POINTS_PER_INCH,
+    options.paper_size.height * POINTS_PER_INCH,
+  );
+  let (x_spacing, y_spacing) = pattern.symbol_settings.printer_spacing;
+  let cell_size = (
+    options.cell_size * f32::from(x_spacing.max(1)),
+    options.cell_size * f32::from(y_spacing.max(1)),
+  );
+  if cell_size.0 <= 0.0 || cell_size.1 <= 0.0 {
+    bail!("The cell size must be positive");
+  }
+  let area = print_area(&pattern.print_settings, page_size)?;
+
+  let columns_per_page = (area.width / cell_size.0).floor().min(f32::from(u16::MAX)) as u16;
+  let rows_per_page = (area.height / cell_size.1).floor().min(f32::from(u16::MAX)) as u16;
+  if columns_per_page <= options.overlap || rows_per_page <= options.overlap {
+    bail!("The page fits {columns_per_page}x{rows_per_page} cells, which is not more than the overlap");
+  }
+
+  let column_ranges = split(pattern.fabric.width, columns_per_page, options.overlap);
+  let row_ranges = split(pattern.fabric.height, rows_per_page, options.overlap);
+  let page_grid = (column_ranges.len(), row_ranges.len());
+  let page_number = |column: usize, row: usize| row * page_grid.0 + column + 1;
+  let center = pattern.print_settings.center_chart_on_pages;
+
+  let mut pages = Vec::new();
+  for (row, rows) in row_ranges.iter().enumerate() {
+    for (column, columns) in column_ranges.iter().enumerate() {
+      let width = f32::from(columns.end - columns.start) * cell_size.0;
+      let height = f32::from(rows.end - rows.start) * cell_size.1;
+      let offset = if center {
+        ((area.width - width) / 2.0, (area.height - height) / 2.0)
+      } else {
+        (0.0, 0.0)
+      };
+      pages.push(ChartPage {
+        number: page_number(column, row),
+        columns: columns.clone(),
+        rows: rows.clone(),
+        offset,
+        adjacent_pages: AdjacentPages {
+          top: (row > 0).then(|| page_number(column, row - 1)),
+          bottom: (row + 1 < page_grid.1).then(|| page_number(column, row + 1)),
+          left: (column > 0).then(|| page_number(column - 1, row)),
+          right: (column + 1 < page_grid.0).then(|| page_number(column + 1, row)),
+        },
+      });
+    }
+  }
+
+  Ok(PageLayout {
+    page_size,
+    cell_size,
+    print_area: area,
+    page_grid,
+    pages,
+  })
+}
+
+/// Computes the area inside the margins that is left after the header and footer lines.
+pub fn print_area(print_settings: &PrintSettings, page_size: Point) -> Result<PrintArea> {
+  let margins = &print_settings.margins;
+  let line_height = line_height(print_settings);
+  let top = (margins.top * POINTS_PER_INCH).max(margins.header * POINTS_PER_INCH + line_height);
+  let bottom = (margins.bottom * POINTS_PER_INCH).max(margins.footer * POINTS_PER_INCH + line_height);
+  let left = margins.left * POINTS_PER_INCH;
+  let right = margins.right * POINTS_PER_INCH;
+
+  let area = PrintArea {
+    left,
+    top,
+    width: page_size.0 - left - right,
+    height: page_size.1 - top - bottom,
+  };
+  if area.width <= 0.0 || area.height <= 0.0 {
+    bail!("The margins leave no space on the page");
+  }
+  Ok(area)
+}
+
+/// Returns the height of a text line in the print font.
+pub fn line_height(print_settings: &PrintSettings) -> f32 {
+  f32::from(print_settings.font.size.max(1)) * 1.5
+}
+
+/// Splits the cells into ranges of the given length that repeat the last `overlap` cells of the previous range.
+fn split(total: u16, per_page: u16, overlap: u16) -> Vec<Range<u16>> {
+  let mut ranges = Vec::new();
+  let mut start: u16 = 0;
+  loop {
+    let end = total.min(start.saturating_add(per_page));
+    ranges.push(start..end);
+    if end == total {
+      break;
+    }
+    start = end - overlap;
+  }
+  ranges
+}
@@ -0,0 +1,146 @@
+use super::*;
+use crate::builder::PatternBuilder;
+
+fn pattern(width: u16, height: u16) -> Pattern {
+  PatternBuilder::new(width, height).build().unwrap()
+}
+
+#[test]
+fn splits_ranges_with_overlap() {
+  assert_eq!(split(25, 10, 2), [0..10, 8..18, 16..25]);
+  assert_eq!(split(18, 10, 2), [0..10, 8..18]);
+  assert_eq!(split(5, 10, 2), vec![Range { start: 0, end: 5 }]);
+  assert_eq!(split(20, 10, 0), [0..10, 10..20]);
+}
+
+#[test]
+fn computes_print_area() {
+  let print_settings = PrintSettings::default();
+  // The header and footer lines of the 10pt font push the chart from the half-inch margins.
+  assert_eq!(
+    print_area(&print_settings, (612.0, 792.0)).unwrap(),
+    PrintArea {
+      left: 36.0,
+      top: 51.0,
+      width: 540.0,
+      height: 690.0,
+    }
+  );
+
+  let mut print_settings = PrintSettings::default();
+  print_settings.margins.top = 1.0;
+  print_settings.margins.left = 0.25;
+  assert_eq!(
+    print_area(&print_settings, (612.0, 792.0)).unwrap(),
+    PrintArea {
+      left: 18.0,
+      top: 72.0,
+      width: 558.0,
+      height: 669.0,
+    }
+  );
+
+  let error = print_area(&print_settings, (50.0, 792.0)).unwrap_err();
+  assert_eq!(error.to_string(), "The margins leave no space on the page");
+}
+
+#[test]
+fn tiles_chart_into_pages() {
+  let layout = layout_pages(&pattern(100, 120), &PageLayoutOptions::default()).unwrap();
+  assert_eq!(layout.page_size, (612.0, 792.0));
+  assert_eq!(layout.cell_size, (7.2, 7.2));
+  // The page fits 75 columns and 95 rows.
+  assert_eq!(layout.page_grid, (2, 2));
+  let ranges: Vec<_> = layout
+    .pages
+    .iter()
+    .map(|page| (page.number, page.columns.clone(), page.rows.clone()))
+    .collect();
+  assert_eq!(
+    ranges,
+    [
+      (1, 0..75, 0..95),
+      (2, 73..100, 0..95),
+      (3, 0..75, 93..120),
+      (4, 73..100, 93..120),
+    ]
+  );
+  assert!(layout.pages.iter().all(|page| page.offset == (0.0, 0.0)));
+  assert_eq!(layout.origin(&layout.pages[3]), (36.0, 51.0));
+  assert_eq!(layout.chart_size(&layout.pages[3]), (27.0 * 7.2, 27.0 * 7.2));
+}
+
+#[test]
+fn finds_adjacent_pages() {
+  let layout = layout_pages(&pattern(200, 120), &PageLayoutOptions::default()).unwrap();
+  assert_eq!(layout.page_grid, (3, 2));
+  assert_eq!(
+    layout.pages[0].adjacent_pages,
+    AdjacentPages {
+      top: None,
+      bottom: Some(4),
+      left: None,
+      right: Some(2),
+    }
+  );
+  assert_eq!(
+    layout.pages[4].adjacent_pages,
+    AdjacentPages {
+      top: Some(2),
+      bottom: None,
+      left: Some(4),
+      right: Some(6),
+    }
+  );
+}
+
+#[test]
+fn centers_chart_on_pages() {
+  let mut pattern = pattern(100, 50);
+  pattern.print_settings.center_chart_on_pages = true;
+  let layout = layout_pages(&pattern, &PageLayoutOptions::default()).unwrap();
+  assert_eq!(layout.pages[0].offset, (0.0, (690.0 - 50.0 * 7.2) / 2.0));
+  assert_eq!(
+    layout.pages[1].offset,
+    ((540.0 - 27.0 * 7.2) / 2.0, (690.0 - 50.0 * 7.2) / 2.0)
+  );
+}
+
+#[test]
+fn applies_printer_spacing_and_paper_size() {
+  let mut pattern = pattern(100, 50);
+  pattern.symbol_settings.printer_spacing = (2, 1);
+  let layout = layout_pages(&pattern, &PageLayoutOptions::default()).unwrap();
+  assert_eq!(layout.cell_size, (14.4, 7.2));
+  assert_eq!(layout.pages.len(), 3);
+  assert_eq!(layout.pages[0].columns, 0..37);
+
+  let options = PageLayoutOptions {
+    paper_size: PaperSize::A4.landscape(),
+    cell_size: 10.0,
+    overlap: 0,
+  };
+  let layout = layout_pages(&pattern, &options).unwrap();
+  assert_eq!(layout.cell_size, (20.0, 10.0));
+  assert_eq!(layout.pages[0].columns, 0..38);
+  assert_eq!(layout.pages[1].columns, 38..76);
+}
+
+#[test]
+fn fails_on_overlap_larger_than_page() {
+  let options = PageLayoutOptions {
+    overlap: 100,
+    ..Default::default()
+  };
+  let error = layout_pages(&pattern(10, 10), &options).unwrap_err();
+  assert_eq!(
+    error.to_string(),
+    "The page fits 75x95 cells, which is not more than the overlap"
+  );
+
+  let options = PageLayoutOptions {
+    cell_size: 0.0,
+    ..Default::default()
+  };
+  assert!(layout_pages(&pattern(10, 10), &options).is_err());
+}
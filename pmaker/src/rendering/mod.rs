@@ -0,0 +1,17 @@
+mod chart;
+mod layout;
+#[cfg(feature = "pdf")]
+mod pdf;
+#[cfg(feature = "preview")]
+mod preview;
+mod svg;
+#[cfg(feature = "pdf")]
+mod truetype;
+
+pub use chart::{ChartOptions, FractionalStitchShape, GridLines};
+pub use layout::*;
+#[cfg(feature = "pdf")]
+pub use pdf::*;
+#[cfg(feature = "preview")]
+pub use preview::*;
+pub use svg::*;
@@ -0,0 +1,708 @@
+//! The export of a pattern chart to a printable multi-page PDF.
+//!
+//! The chart is split into pages according to the `PrintSettings` of the pattern and followed by the legend pages.
+//! The text is printed with the bundled DejaVu Sans font and the symbols with their pattern fonts when they are given.
+//! The fonts are embedded as subsets of the used glyphs, so any Unicode text is printed as is.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
+
+use anyhow::{Context, Result};
+use pdf_writer::types::{CidFontType, FontFlags, LineCapStyle, SystemInfo, TextRenderingMode, UnicodeCmap};
+use pdf_writer::{Content, Finish, Name, Pdf, Rect, Ref, Str, TextStr};
+
+use super::chart::{ChartOptions, ChartScene, FractionalStitchShape, GridLines, Point, Shape};
+use super::layout::*;
+use super::truetype::TrueTypeFont;
+use crate::print_template::*;
+use crate::schemas::color::Rgb;
+use crate::schemas::xsd::*;
+
+#[cfg(test)]
+#[path = "pdf.test.rs"]
+mod tests;
+
+/// The font of the text and of the symbols missing from their fonts.
+const BUNDLED_FONT: &[u8] = include_bytes!("../../resources/fonts/DejaVuSans.ttf");
+const BUNDLED_FONT_NAME: &str = "DejaVu Sans";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PdfOptions {
+  pub layout: PageLayoutOptions,
+  pub fractional_stitch_shape: FractionalStitchShape,
+  pub include_legend: bool,
+  /// The TrueType data of the symbol fonts by their names, e.g. `CrossStitch3`.
+  /// The symbols are printed with the bundled font if their font is not given or has no glyphs for them.
+  pub symbol_fonts: HashMap<String, Vec<u8>>,
+}
+
+impl Default for PdfOptions {
+  fn default() -> Self {
+    PdfOptions {
+      layout: PageLayoutOptions::default(),
+      fractional_stitch_shape: FractionalStitchShape::Triangles,
+      include_legend: true,
+      symbol_fonts: HashMap::new(),
+    }
+  }
+}
+
+/// Renders the chart of the pattern as a PDF document.
+pub fn render_pdf_chart(pattern: &Pattern, options: &PdfOptions) -> Result<Vec<u8>> {
+  let print_settings = &pattern.print_settings;
+  let layout = layout_pages(pattern, &options.layout)?;
+  let (page_size, cell_size) = (layout.page_size, layout.cell_size);
+
+  let fonts = EmbeddedFonts::new(&options.symbol_fonts)?;
+  let font = PrintFont::new(&print_settings.font, fonts.bundled());
+  let legend_pages = if options.include_legend {
+    legend_pages(pattern, &font, layout.print_area)
+  } else {
+    Vec::new()
+  };
+  let page_count = layout.pages.len() + legend_pages.len();
+
+  let mut ids = (1..).map(Ref::new);
+  let mut next_id = || ids.next().unwrap();
+  let catalog_id = next_id();
+  let page_tree_id = next_id();
+  let info_id = next_id();
+  let chart_id = next_id();
+  let font_ids: Vec<_> = fonts
+    .fonts
+    .iter()
+    .map(|_| [next_id(), next_id(), next_id(), next_id(), next_id()])
+    .collect();
+  let page_ids: Vec<_> = (0..page_count).map(|_| (next_id(), next_id())).collect();
+
+  let mut pdf = Pdf::new();
+  pdf.catalog(catalog_id).pages(page_tree_id);
+  pdf
+    .pages(page_tree_id)
+    .kids(page_ids.iter().map(|(page_id, _)| *page_id))
+    .count(page_count as i32);
+  let mut info = pdf.document_info(info_id);
+  if !pattern.info.title.is_empty() {
+    info.title(TextStr(&pattern.info.title));
+  }
+  if !pattern.info.author.is_empty() {
+    info.author(TextStr(&pattern.info.author));
+  }
+  info.finish();
+
+  let chart_options = ChartOptions {
+    cell_size: 1.0,
+    grid_lines: GridLines::Printer,
+    fractional_stitch_shape: options.fractional_stitch_shape,
+  };
+  let scene = ChartScene::new(pattern, &chart_options);
+  let chart_size = (
+    f32::from(scene.width) * cell_size.0,
+    f32::from(scene.height) * cell_size.1,
+  );
+  let chart_content = chart_content(&scene, &fonts, cell_size);
+  let mut chart = pdf.form_xobject(chart_id, &chart_content);
+  chart.bbox(Rect::new(-1.0, -1.0, chart_size.0 + 1.0, chart_size.1 + 1.0));
+  let mut resources = chart.resources();
+  let mut font_resources = resources.fonts();
+  for (embedded, [font_id, ..]) in fonts.fonts.iter().zip(&font_ids) {
+    font_resources.pair(Name(embedded.resource_name.as_bytes()), *font_id);
+  }
+  font_resources.finish();
+  resources.finish();
+  chart.finish();
+
+  let page_contents = layout
+    .pages
+    .iter()
+    .map(|page| chart_page_content(pattern, options, &layout, page, &font, chart_size.1))
+    .chain(
+      legend_pages
+        .iter()
+        .map(|rows| legend_page_content(pattern, &fonts, &font, rows, layout.print_area, page_size)),
+    );
+  let header = print_template(&print_settings.header, print_settings.show_page_numbers);
+  let footer = print_template(&print_settings.footer, print_settings.show_page_numbers);
+  let has_page_numbers = header.contains(TemplateField::PageNumber) || footer.contains(TemplateField::PageNumber);
+  for (index, (mut content, (page_id, content_id))) in page_contents.zip(page_ids.iter()).enumerate() {
+    let page_context = PageContext { page_number: index + 1 };
+    let header = header.render(pattern, &page_context);
+    let footer = footer.render(pattern, &page_context);
+    let header_y = print_settings.margins.header * POINTS_PER_INCH + font.size;
+    let footer_y = page_size.1 - print_settings.margins.footer * POINTS_PER_INCH;
+    write_template(&mut content, &font, &header, header_y, &layout);
+    write_template(&mut content, &font, &footer, footer_y, &layout);
+
+    // Number the pages when the templates don't.
+    if print_settings.show_page_numbers && !has_page_numbers && footer.center.is_empty() {
+      let text = format!("Page {} of {page_count}", index + 1);
+      let x = (page_size.0 - font.width(&text)) / 2.0;
+      font.write(&mut content, (x, footer_y), &text, page_size);
+    }
+
+    let mut page = pdf.page(*page_id);
+    page
+      .parent(page_tree_id)
+      .media_box(Rect::new(0.0, 0.0, page_size.0, page_size.1))
+      .contents(*content_id);
+    let mut resources = page.resources();
+    resources.x_objects().pair(Name(b"Chart"), chart_id);
+    let mut font_resources = resources.fonts();
+    for (embedded, [font_id, ..]) in fonts.fonts.iter().zip(&font_ids) {
+      font_resources.pair(Name(embedded.resource_name.as_bytes()), *font_id);
+    }
+    font_resources.finish();
+    resources.finish();
+    page.finish();
+    pdf.stream(*content_id, &content.finish());
+  }
+
+  // The fonts are written last, when all the used glyphs are known.
+  for (embedded, ids) in fonts.fonts.iter().zip(font_ids) {
+    embedded.write(&mut pdf, ids);
+  }
+
+  Ok(pdf.finish())
+}
+
+/// Draws the whole chart with the origin in the bottom left corner.
+fn chart_content(scene: &ChartScene, fonts: &EmbeddedFonts, cell_size: Point) -> Vec<u8> {
+  let height = f32::from(scene.height);
+  let point = |(x, y): Point| (x * cell_size.0, (height - y) * cell_size.1);
+
+  let mut content = Content::new();
+  content.set_line_cap(LineCapStyle::RoundCap);
+  for shape in scene.shapes.iter() {
+    match shape {
+      Shape::Rect { origin, size, fill } => {
+        let (x, y) = point((origin.0, origin.1 + size.1));
+        set_fill(&mut content, *fill);
+        content
+          .rect(x, y, size.0 * cell_size.0, size.1 * cell_size.1)
+          .fill_nonzero();
+      }
+      Shape::Polygon { points, fill } => {
+        set_fill(&mut content, *fill);
+        path(&mut content, points.iter().map(|&p| point(p)));
+        content.close_path().fill_nonzero();
+      }
+      Shape::Line {
+        from,
+        to,
+        color,
+        thickness,
+      } => {
+        set_stroke(&mut content, *color, *thickness);
+        path(&mut content, [point(*from), point(*to)].into_iter());
+        content.stroke();
+      }
+      Shape::Polyline {
+        points,
+        color,
+        thickness,
+      } => {
+        set_stroke(&mut content, *color, *thickness);
+        path(&mut content, points.iter().map(|&p| point(p)));
+        content.stroke();
+      }
+      Shape::Circle { center, radius, fill } => {
+        set_fill(&mut content, *fill);
+        circle(&mut content, point(*center), *radius);
+        content.fill_nonzero();
+      }
+      Shape::Symbol {
+        center,
+        size,
+        symbol,
+        font,
+        bold,
+        italic,
+        color,
+      } => {
+        let style = TextStyle {
+          size: size * cell_size.0.min(cell_size.1),
+          bold: *bold,
+          italic: *italic,
+          color: *color,
+        };
+        let font = fonts.symbol_font(font, *symbol);
+        let symbol = symbol.to_string();
+        let (x, y) = point(*center);
+        let origin = (
+          x - font.width(&symbol, style.size) / 2.0,
+          y - font.cap_height(style.size) / 2.0,
+        );
+        show_text(&mut content, font, style, origin, &symbol);
+      }
+    }
+  }
+  content.finish()
+}
+
+fn chart_page_content(
+  pattern: &Pattern,
+  options: &PdfOptions,
+  layout: &PageLayout,
+  page: &ChartPage,
+  font: &PrintFont,
+  chart_height: f32,
+) -> Content {
+  let (page_size, cell_size) = (layout.page_size, layout.cell_size);
+  let (width, height) = layout.chart_size(page);
+  let (left, top) = layout.origin(page);
+  let bottom = page_size.1 - top - height;
+
+  let mut content = Content::new();
+  content.save_state();
+  // Leave the space for the halves of the border lines.
+  let border = pattern.grid.major_printer_lines.thickness;
+  content
+    .rect(
+      left - border,
+      bottom - border,
+      width + border * 2.0,
+      height + border * 2.0,
+    )
+    .clip_nonzero()
+    .end_path();
+  let chart_left = f32::from(page.columns.start) * cell_size.0;
+  let chart_bottom = chart_height - f32::from(page.rows.end) * cell_size.1;
+  content.transform([1.0, 0.0, 0.0, 1.0, left - chart_left, bottom - chart_bottom]);
+  content.x_object(Name(b"Chart"));
+  content.restore_state();
+
+  // Mark where the rows and columns repeated from the previous pages end.
+  let overlap = options.layout.overlap;
+  content.save_state();
+  set_stroke(&mut content, Rgb::BLACK, border);
+  content.set_dash_pattern([3.0, 3.0], 0.0);
+  if page.columns.start > 0 && overlap > 0 {
+    let x = left + f32::from(overlap) * cell_size.0;
+    content
+      .move_to(x, bottom - 6.0)
+      .line_to(x, bottom + height + 6.0)
+      .stroke();
+  }
+  if page.rows.start > 0 && overlap > 0 {
+    let y = bottom + height - f32::from(overlap) * cell_size.1;
+    content.move_to(left - 6.0, y).line_to(left + width + 6.0, y).stroke();
+  }
+  content.restore_state();
+
+  if pattern.print_settings.show_adjacent_page_numbers {
+    let adjacent = &page.adjacent_pages;
+    let gap = font.size / 2.0;
+    let labels = [
+      (adjacent.top, (left + width / 2.0, top - gap), 0.5),
+      (
+        adjacent.bottom,
+        (left + width / 2.0, top + height + gap + font.size),
+        0.5,
+      ),
+      (adjacent.left, (left - gap, top + height / 2.0), 1.0),
+      (adjacent.right, (left + width + gap, top + height / 2.0), 0.0),
+    ];
+    for (number, (x, y), alignment) in labels {
+      if let Some(number) = number {
+        let text = number.to_string();
+        let x = x - font.width(&text) * alignment;
+        font.write(&mut content, (x, y), &text, page_size);
+      }
+    }
+  }
+
+  content
+}
+
+/// Parses the header or footer template, dropping the page numbers if they are hidden.
+fn print_template(template: &str, show_page_numbers: bool) -> PrintTemplate {
+  let mut template = PrintTemplate::parse(template);
+  if !show_page_numbers {
+    template
+      .parts
+      .retain(|part| *part != TemplatePart::Field(TemplateField::PageNumber));
+  }
+  template
+}
+
+/// Writes the sections of the header or footer with the baseline at `y` points from the top of the page.
+fn write_template(content: &mut Content, font: &PrintFont, template: &RenderedTemplate, y: f32, layout: &PageLayout) {
+  let area = layout.print_area;
+  let sections = [
+    (&template.left, area.left),
+    (
+      &template.center,
+      (layout.page_size.0 - font.width(&template.center)) / 2.0,
+    ),
+    (&template.right, area.left + area.width - font.width(&template.right)),
+  ];
+  for (text, x) in sections {
+    if !text.is_empty() {
+      font.write(content, (x, y), text, layout.page_size);
+    }
+  }
+}
+
+/// The rows of the legend split by pages.
+fn legend_pages(pattern: &Pattern, font: &PrintFont, area: PrintArea) -> Vec<Range<usize>> {
+  let rows_per_page = ((area.height / font.line_height()) as usize).saturating_sub(3).max(1);
+  let palette_size = pattern.palette.len();
+  (0..palette_size.max(1))
+    .step_by(rows_per_page)
+    .map(|start| start..palette_size.min(start + rows_per_page))
+    .collect()
+}
+
+/// The columns of the legend table and their widths in characters.
+const LEGEND_COLUMNS: [(&str, usize); 6] = [
+  ("Symbol", 7),
+  ("Color", 6),
+  ("Brand", 14),
+  ("Number", 8),
+  ("Name", 24),
+  ("Strands", 10),
+];
+
+fn legend_page_content(
+  pattern: &Pattern,
+  fonts: &EmbeddedFonts,
+  font: &PrintFont,
+  rows: &Range<usize>,
+  area: PrintArea,
+  page_size: Point,
+) -> Content {
+  let mut content = Content::new();
+  let line_height = font.line_height();
+  let char_width = font.width("0");
+
+  let mut y = area.top + font.size;
+  font.write(&mut content, (area.left, y), "Legend", page_size);
+  y += line_height * 1.5;
+
+  let mut x = area.left;
+  for (title, width) in LEGEND_COLUMNS {
+    font.write(&mut content, (x, y), title, page_size);
+    x += char_width * width as f32;
+  }
+
+  for palindex in rows.clone() {
+    y += line_height;
+    let palitem = &pattern.palette[palindex];
+    let strands = pattern.strands(palindex);
+    let symbol = pattern
+      .symbols
+      .get(palindex)
+      .and_then(|symbols| symbols.full)
+      .and_then(|code| char::from_u32(code.into()));
+    let cells = [
+      symbol.map(String::from).unwrap_or_default(),
+      String::new(),
+      palitem.brand.clone(),
+      palitem.number.clone(),
+      palitem.name.clone(),
+      format!("{} / {}", strands.full, strands.back),
+    ];
+
+    let mut x = area.left;
+    for (index, (text, (_, width))) in cells.iter().zip(LEGEND_COLUMNS).enumerate() {
+      let column_width = char_width * width as f32;
+      if index == 1 {
+        // The color swatch.
+        let swatch = font.size;
+        set_fill(&mut content, palitem.color);
+        set_stroke(&mut content, Rgb::BLACK, 0.5);
+        content
+          .rect(x, page_size.1 - y, swatch * 1.5, swatch)
+          .fill_nonzero_and_stroke();
+      } else if index == 0 {
+        if let Some(symbol) = symbol {
+          let symbol_font = fonts.symbol_font(symbol_font_name(pattern, palindex), symbol);
+          let style = TextStyle {
+            bold: false,
+            italic: false,
+            ..font.style()
+          };
+          show_text(&mut content, symbol_font, style, (x, page_size.1 - y), text);
+        }
+      } else {
+        let text: String = text.chars().take(width - 1).collect();
+        font.write(&mut content, (x, y), &text, page_size);
+      }
+      x += column_width;
+    }
+  }
+
+  content
+}
+
+/// Returns the name of the font of the palette item symbols.
+fn symbol_font_name(pattern: &Pattern, palindex: usize) -> &str {
+  pattern
+    .formats
+    .get(palindex)
+    .and_then(|formats| formats.font.font_name.as_deref())
+    .unwrap_or(&pattern.pattern_settings.default_stitch_font)
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TextStyle {
+  size: f32,
+  bold: bool,
+  italic: bool,
+  color: Rgb,
+}
+
+/// The print font of the pattern, drawn with the bundled font.
+struct PrintFont<'a> {
+  font: &'a EmbeddedFont<'a>,
+  size: f32,
+  bold: bool,
+  italic: bool,
+}
+
+impl<'a> PrintFont<'a> {
+  fn new(font: &Font, bundled: &'a EmbeddedFont<'a>) -> Self {
+    PrintFont {
+      font: bundled,
+      size: f32::from(font.size.max(1)),
+      bold: font.weight >= 600,
+      italic: font.italic,
+    }
+  }
+
+  fn style(&self) -> TextStyle {
+    TextStyle {
+      size: self.size,
+      bold: self.bold,
+      italic: self.italic,
+      color: Rgb::BLACK,
+    }
+  }
+
+  fn line_height(&self) -> f32 {
+    self.size * 1.5
+  }
+
+  /// Returns the width of the text in points.
+  fn width(&self, text: &str) -> f32 {
+    self.font.width(text, self.size)
+  }
+
+  /// Writes a line of text with the baseline at `y` points from the top of the page.
+  fn write(&self, content: &mut Content, (x, y): Point, text: &str, page_size: Point) {
+    show_text(content, self.font, self.style(), (x, page_size.1 - y), text);
+  }
+}
+
+/// Draws a line of text with the baseline starting at the point.
+/// The bold and italic faces are simulated, as only the regular faces are embedded.
+fn show_text(content: &mut Content, font: &EmbeddedFont, style: TextStyle, (x, y): Point, text: &str) {
+  content.save_state();
+  set_fill(content, style.color);
+  if style.bold {
+    set_stroke(content, style.color, style.size * 0.03);
+  }
+  content
+    .begin_text()
+    .set_font(Name(font.resource_name.as_bytes()), style.size);
+  if style.bold {
+    content.set_text_rendering_mode(TextRenderingMode::FillStroke);
+  }
+  let skew = if style.italic { 0.2 } else { 0.0 };
+  content
+    .set_text_matrix([1.0, 0.0, skew, 1.0, x, y])
+    .show(Str(&font.encode(text)))
+    .end_text();
+  content.restore_state();
+}
+
+/// The fonts embedded in the document: the bundled one and the given symbol fonts.
+struct EmbeddedFonts<'a> {
+  fonts: Vec<EmbeddedFont<'a>>,
+}
+
+impl<'a> EmbeddedFonts<'a> {
+  fn new(symbol_fonts: &'a HashMap<String, Vec<u8>>) -> Result<Self> {
+    let mut fonts = vec![EmbeddedFont::new(0, BUNDLED_FONT_NAME, BUNDLED_FONT, false)?];
+    let mut names: Vec<_> = symbol_fonts.keys().collect();
+    names.sort();
+    for name in names {
+      let font = EmbeddedFont::new(fonts.len(), name, &symbol_fonts[name], true)
+        .with_context(|| format!("Failed to read the {name} font"))?;
+      fonts.push(font);
+    }
+    Ok(EmbeddedFonts { fonts })
+  }
+
+  fn bundled(&self) -> &EmbeddedFont<'a> {
+    &self.fonts[0]
+  }
+
+  /// Returns the font to draw the symbol with, falling back to the bundled one.
+  fn symbol_font(&self, name: &str, symbol: char) -> &EmbeddedFont<'a> {
+    self.fonts[1..]
+      .iter()
+      .find(|font| font.name.eq_ignore_ascii_case(name) && font.font.glyph_id(symbol).is_some())
+      .unwrap_or(self.bundled())
+  }
+}
+
+/// A TrueType font that is embedded as a subset of the glyphs used in the document.
+struct EmbeddedFont<'a> {
+  name: String,
+  /// The name of the font in the resources of the pages.
+  resource_name: String,
+  /// The six capital letters that tag the subset, unique within the document.
+  subset_tag: String,
+  font: TrueTypeFont<'a>,
+  symbolic: bool,
+  /// The used glyphs and the characters they are drawn for.
+  used_glyphs: RefCell<BTreeMap<u16, char>>,
+}
+
+impl<'a> EmbeddedFont<'a> {
+  fn new(index: usize, name: &str, data: &'a [u8], symbolic: bool) -> Result<Self> {
+    Ok(EmbeddedFont {
+      name: name.to_owned(),
+      resource_name: format!("F{index}"),
+      subset_tag: (0..6)
+        .map(|place| char::from(b'A' + (index / 26usize.pow(place) % 26) as u8))
+        .collect(),
+      font: TrueTypeFont::parse(data)?,
+      symbolic,
+      used_glyphs: RefCell::default(),
+    })
+  }
+
+  /// Encodes the text with the two-byte glyph IDs, drawing the missing characters with the glyph 0.
+  fn encode(&self, text: &str) -> Vec<u8> {
+    let mut used_glyphs = self.used_glyphs.borrow_mut();
+    let mut encoded = Vec::with_capacity(text.len() * 2);
+    for ch in text.chars() {
+      let glyph_id = self.font.glyph_id(ch).unwrap_or_default();
+      if glyph_id != 0 {
+        used_glyphs.entry(glyph_id).or_insert(ch);
+      }
+      encoded.extend(glyph_id.to_be_bytes());
+    }
+    encoded
+  }
+
+  /// Returns the width of the text in points.
+  fn width(&self, text: &str, size: f32) -> f32 {
+    let width: f32 = text
+      .chars()
+      .map(|ch| self.font.advance(self.font.glyph_id(ch).unwrap_or_default()))
+      .sum();
+    width * size / 1000.0
+  }
+
+  /// Returns the height of the capital letters in points.
+  fn cap_height(&self, size: f32) -> f32 {
+    self.font.to_thousandths(self.font.cap_height.into()) * size / 1000.0
+  }
+
+  /// Writes the Type 0 font with the subset of the used glyphs and their Unicode mapping.
+  fn write(&self, pdf: &mut Pdf, [font_id, cid_font_id, descriptor_id, file_id, cmap_id]: [Ref; 5]) {
+    let used_glyphs = self.used_glyphs.borrow();
+    let font = &self.font;
+
+    let postscript_name: String = self.name.chars().filter(char::is_ascii_alphanumeric).collect();
+    let base_font = format!("{}+{postscript_name}", self.subset_tag);
+    let base_font = Name(base_font.as_bytes());
+
+    let system_info = SystemInfo {
+      registry: Str(b"Adobe"),
+      ordering: Str(b"Identity"),
+      supplement: 0,
+    };
+    pdf
+      .type0_font(font_id)
+      .base_font(base_font)
+      .encoding_predefined(Name(b"Identity-H"))
+      .descendant_font(cid_font_id)
+      .to_unicode(cmap_id);
+
+    let mut cid_font = pdf.cid_font(cid_font_id);
+    cid_font
+      .subtype(CidFontType::Type2)
+      .base_font(base_font)
+      .system_info(system_info)
+      .font_descriptor(descriptor_id)
+      .default_width(font.advance(0))
+      .cid_to_gid_map_predefined(Name(b"Identity"));
+    let mut widths = cid_font.widths();
+    for &glyph_id in used_glyphs.keys() {
+      widths.consecutive(glyph_id, [font.advance(glyph_id)]);
+    }
+    widths.finish();
+    cid_font.finish();
+
+    let [left, bottom, right, top] = font.bbox.map(|value| font.to_thousandths(value.into()));
+    pdf
+      .font_descriptor(descriptor_id)
+      .name(base_font)
+      .flags(if self.symbolic {
+        FontFlags::SYMBOLIC
+      } else {
+        FontFlags::NON_SYMBOLIC
+      })
+      .bbox(Rect::new(left, bottom, right, top))
+      .italic_angle(0.0)
+      .ascent(font.to_thousandths(font.ascender.into()))
+      .descent(font.to_thousandths(font.descender.into()))
+      .cap_height(font.to_thousandths(font.cap_height.into()))
+      .stem_v(80.0)
+      .font_file2(file_id);
+
+    let data = font.subset(&used_glyphs.keys().copied().collect());
+    pdf.stream(file_id, &data).pair(Name(b"Length1"), data.len() as i32);
+
+    let mut cmap = UnicodeCmap::new(Name(b"Custom"), system_info);
+    for (&glyph_id, &ch) in used_glyphs.iter() {
+      cmap.pair(glyph_id, ch);
+    }
+    pdf.cmap(cmap_id, &cmap.finish());
+  }
+}
+
+fn set_fill(content: &mut Content, color: Rgb) {
+  let (r, g, b) = components(color);
+  content.set_fill_rgb(r, g, b);
+}
+
+fn set_stroke(content: &mut Content, color: Rgb, width: f32) {
+  let (r, g, b) = components(color);
+  content.set_stroke_rgb(r, g, b).set_line_width(width);
+}
+
+fn components(color: Rgb) -> (f32, f32, f32) {
+  (
+    f32::from(color.r) / 255.0,
+    f32::from(color.g) / 255.0,
+    f32::from(color.b) / 255.0,
+  )
+}
+
+fn path(content: &mut Content, mut points: impl Iterator<Item = Point>) {
+  if let Some((x, y)) = points.next() {
+    content.move_to(x, y);
+  }
+  for (x, y) in points {
+    content.line_to(x, y);
+  }
+}
+
+/// Approximates a circle with four Bézier curves.
+fn circle(content: &mut Content, (x, y): Point, radius: f32) {
+  const KAPPA: f32 = 0.552_284_8;
+  let k = radius * KAPPA;
+  content
+    .move_to(x + radius, y)
+    .cubic_to(x + radius, y + k, x + k, y + radius, x, y + radius)
+    .cubic_to(x - k, y + radius, x - radius, y + k, x - radius, y)
+    .cubic_to(x - radius, y - k, x - k, y - radius, x, y - radius)
+    .cubic_to(x + k, y - radius, x + radius, y - k, x + radius, y)
+    .close_path();
+}
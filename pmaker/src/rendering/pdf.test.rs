@@ -0,0 +1,183 @@
+use super::*;
+use crate::builder::PatternBuilder;
+use crate::schemas::coords::Coord;
+
+fn sample_pattern(width: u16, height: u16) -> Pattern {
+  let mut builder = PatternBuilder::new(width, height);
+  let palindex = builder
+    .add_palette_item_with(
+      PaletteItem {
+        name: String::from("Black"),
+        ..PaletteItem::new("DMC", "310", Rgb::BLACK)
+      },
+      Formats::default(),
+      Symbols {
+        full: Some(u16::from(b'X')),
+        ..Default::default()
+      },
+    )
+    .unwrap();
+  builder.add_fullstitch(FullStitch {
+    x: Coord::ZERO,
+    y: Coord::ZERO,
+    palindex,
+    kind: FullStitchKind::Full,
+  });
+  builder.build().unwrap()
+}
+
+fn count(haystack: &[u8], needle: &[u8]) -> usize {
+  haystack
+    .windows(needle.len())
+    .filter(|window| *window == needle)
+    .count()
+}
+
+/// Counts the text operations that select the font.
+fn font_selections(pdf: &[u8], font: &str) -> usize {
+  pdf
+    .split(|&byte| byte == b'\n')
+    .filter(|line| line.starts_with(font.as_bytes()) && line.ends_with(b" Tf"))
+    .count()
+}
+
+/// Returns the operation that shows the text with the bundled font.
+fn shown(text: &str) -> Vec<u8> {
+  let font = EmbeddedFont::new(0, BUNDLED_FONT_NAME, BUNDLED_FONT, false).unwrap();
+  let mut content = Content::new();
+  content.show(Str(&font.encode(text)));
+  content.finish()
+}
+
+#[test]
+fn fails_on_small_pages() {
+  let pattern = sample_pattern(10, 10);
+  let options = PdfOptions {
+    layout: PageLayoutOptions {
+      paper_size: PaperSize {
+        width: 1.0,
+        height: 11.0,
+      },
+      ..Default::default()
+    },
+    ..Default::default()
+  };
+  let error = render_pdf_chart(&pattern, &options).unwrap_err();
+  assert_eq!(error.to_string(), "The margins leave no space on the page");
+}
+
+#[test]
+fn renders_pdf_chart() {
+  let pattern = sample_pattern(100, 50);
+  let pdf = render_pdf_chart(&pattern, &PdfOptions::default()).unwrap();
+
+  assert!(pdf.starts_with(b"%PDF-"));
+  assert_eq!(count(&pdf, b"/Type /Page\n"), 3);
+  assert_eq!(count(&pdf, b"/Count 3"), 1);
+  // The default header numbers the pages, so there is no separate page number line.
+  assert_eq!(count(&pdf, &shown("3")), 1);
+  assert_eq!(count(&pdf, &shown("Page 1 of 3")), 0);
+  // The numbers of the adjacent pages beside the chart are added to the header ones.
+  assert_eq!(count(&pdf, &shown("2")), 2);
+  assert_eq!(count(&pdf, &shown("1")), 2);
+  // The chart is drawn once and placed on both chart pages.
+  assert_eq!(count(&pdf, b"/Chart Do"), 2);
+  assert_eq!(count(&pdf, &shown("X")), 2);
+  // The legend.
+  assert_eq!(count(&pdf, &shown("Legend")), 1);
+  for text in ["DMC", "310", "Black", "2 / 1"] {
+    assert_eq!(count(&pdf, &shown(text)), 1, "{text}");
+  }
+}
+
+#[test]
+fn respects_print_settings() {
+  let mut pattern = sample_pattern(100, 10);
+  let options = PdfOptions {
+    include_legend: false,
+    ..Default::default()
+  };
+  let pdf = render_pdf_chart(&pattern, &options).unwrap();
+  assert_eq!(count(&pdf, b"/Type /Page\n"), 2);
+  assert_eq!(count(&pdf, &shown("1")) + count(&pdf, &shown("2")), 4);
+  assert_eq!(count(&pdf, &shown("Legend")), 0);
+
+  pattern.print_settings.show_page_numbers = false;
+  pattern.print_settings.show_adjacent_page_numbers = false;
+  let pdf = render_pdf_chart(&pattern, &options).unwrap();
+  assert_eq!(count(&pdf, b"/Type /Page\n"), 2);
+  assert_eq!(count(&pdf, &shown("1")) + count(&pdf, &shown("2")), 0);
+  assert_eq!(count(&pdf, &shown("Page 1 of 2")), 0);
+}
+
+#[test]
+fn renders_headers_and_footers() {
+  let mut pattern = sample_pattern(10, 10);
+  pattern.info.title = String::from("Roses");
+  pattern.print_settings.header = String::from("&l&t&rRoses & Tulips");
+  pattern.print_settings.footer = String::from("&lChart");
+  let options = PdfOptions::default();
+  let pdf = render_pdf_chart(&pattern, &options).unwrap();
+  for text in ["Roses", "Roses & Tulips", "Chart"] {
+    assert_eq!(count(&pdf, &shown(text)), 2, "{text}");
+  }
+  // The templates don't number the pages.
+  assert_eq!(count(&pdf, &shown("Page 1 of 2")), 1);
+  assert_eq!(count(&pdf, &shown("Page 2 of 2")), 1);
+
+  pattern.print_settings.footer = String::from("Page &n");
+  let pdf = render_pdf_chart(&pattern, &options).unwrap();
+  assert_eq!(count(&pdf, &shown("Page 1")), 1);
+  assert_eq!(count(&pdf, &shown("Page 1 of 2")), 0);
+
+  pattern.print_settings.show_page_numbers = false;
+  let pdf = render_pdf_chart(&pattern, &options).unwrap();
+  assert_eq!(count(&pdf, &shown("Page")), 2);
+  assert_eq!(count(&pdf, &shown("Page 1")), 0);
+}
+
+#[test]
+fn prints_unicode_text() {
+  let mut pattern = sample_pattern(10, 10);
+  pattern.info.title = String::from("Троянди");
+  pattern.print_settings.header = String::from("&l&t&r&n");
+  pattern.symbols[0].full = Some('★' as u16);
+  let pdf = render_pdf_chart(&pattern, &PdfOptions::default()).unwrap();
+
+  // The title in the header of the chart and legend pages.
+  assert_eq!(count(&pdf, &shown("Троянди")), 2);
+  // The symbol on the chart and in the legend.
+  assert_eq!(count(&pdf, &shown("★")), 2);
+  assert_eq!(count(&pdf, b"/Encoding /Identity-H"), 1);
+  assert_eq!(count(&pdf, b"/FontFile2"), 1);
+  for mapping in [&b"<0422>"[..], b"<0440>", b"<0438>", b"<2605>"] {
+    assert_eq!(count(&pdf, mapping), 1, "{}", String::from_utf8_lossy(mapping));
+  }
+}
+
+#[test]
+fn embeds_symbol_fonts() {
+  let mut pattern = sample_pattern(10, 10);
+  pattern.symbols[0].full = Some('Ж' as u16);
+  let options = PdfOptions {
+    symbol_fonts: HashMap::from([(String::from("CrossStitch3"), BUNDLED_FONT.to_vec())]),
+    ..Default::default()
+  };
+  let pdf = render_pdf_chart(&pattern, &options).unwrap();
+  assert_eq!(count(&pdf, b"/BaseFont /BAAAAA+CrossStitch3"), 2);
+  assert_eq!(count(&pdf, b"/FontFile2"), 2);
+  // The symbol is drawn with the pattern font on the chart and in the legend.
+  assert_eq!(font_selections(&pdf, "/F1 "), 2);
+
+  // The symbols of the other fonts are drawn with the bundled font.
+  pattern.formats[0].font.font_name = Some(String::from("Other"));
+  let pdf = render_pdf_chart(&pattern, &options).unwrap();
+  assert_eq!(font_selections(&pdf, "/F1 "), 0);
+
+  let options = PdfOptions {
+    symbol_fonts: HashMap::from([(String::from("CrossStitch3"), b"OTTO".to_vec())]),
+    ..Default::default()
+  };
+  let error = render_pdf_chart(&pattern, &options).unwrap_err();
+  assert_eq!(error.to_string(), "Failed to read the CrossStitch3 font");
+}
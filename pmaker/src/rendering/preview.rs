@@ -0,0 +1,362 @@
+//! The realistic raster preview of a pattern.
+//!
+//! The stitches are drawn as thread strokes on the fabric, like a photo of the stitched work.
+//! All the geometry is computed in cells and scaled to pixels when drawing.
+
+use anyhow::{Result, bail};
+use tiny_skia::{FillRule, LineCap, LineJoin, Paint, Path, PathBuilder, Pixmap, Rect, Stroke, Transform};
+
+use super::chart::{Point, SpecialStitchTransform};
+use crate::schemas::color::Rgb;
+use crate::schemas::xsd::*;
+use crate::units::MILLIMETERS_PER_INCH;
+
+#[cfg(test)]
+#[path = "preview.test.rs"]
+mod tests;
+
+/// The part of a cell that one unit of the display thickness takes.
+const THICKNESS_UNIT: f32 = 0.2;
+
+/// The distance between the cell corners and the ends of the stitches.
+const STITCH_INSET: f32 = 0.08;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PreviewOptions {
+  /// The size of a cell in pixels.
+  pub cell_size: u32,
+  /// Whether to draw the holes of the fabric at the cell corners.
+  pub show_fabric_holes: bool,
+}
+
+impl Default for PreviewOptions {
+  fn default() -> Self {
+    PreviewOptions {
+      cell_size: 16,
+      show_fabric_holes: true,
+    }
+  }
+}
+
+/// An RGBA image with 8 bits per channel and non-premultiplied alpha, stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreviewImage {
+  pub width: u32,
+  pub height: u32,
+  pub pixels: Vec<u8>,
+}
+
+impl PreviewImage {
+  /// Returns the RGBA components of the pixel.
+  pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+    let offset = (y as usize * self.width as usize + x as usize) * 4;
+    self.pixels[offset..offset + 4].try_into().unwrap()
+  }
+}
+
+/// Renders the preview of the pattern as it would look stitched.
+pub fn render_preview(pattern: &Pattern, options: &PreviewOptions) -> Result<PreviewImage> {
+  if options.cell_size == 0 {
+    bail!("The cell size must be positive");
+  }
+  let (Some(width), Some(height)) = (
+    u32::from(pattern.fabric.width).checked_mul(options.cell_size),
+    u32::from(pattern.fabric.height).checked_mul(options.cell_size),
+  ) else {
+    bail!(
+      "The preview of the pattern is too large for the cell size {}",
+      options.cell_size
+    );
+  };
+  let Some(pixmap) = Pixmap::new(width, height) else {
+    bail!("Can't create a preview of {width}x{height} pixels");
+  };
+
+  let mut canvas = Canvas {
+    pixmap,
+    transform: Transform::from_scale(options.cell_size as f32, options.cell_size as f32),
+    pattern,
+  };
+  canvas.draw_fabric(options.show_fabric_holes);
+  for stitch in pattern.fullstitches.iter() {
+    canvas.draw_fullstitch(stitch);
+  }
+  for stitch in pattern.partstitches.iter() {
+    canvas.draw_partstitch(stitch);
+  }
+  for stitch in pattern.linestitches.iter() {
+    canvas.draw_linestitch(stitch);
+  }
+  for stitch in pattern.specialstitches.iter() {
+    canvas.draw_specialstitch(stitch);
+  }
+  for stitch in pattern.nodestitches.iter() {
+    canvas.draw_nodestitch(stitch, (stitch.x.to_f32(), stitch.y.to_f32()));
+  }
+
+  let pixels = canvas
+    .pixmap
+    .pixels()
+    .iter()
+    .flat_map(|pixel| {
+      let color = pixel.demultiply();
+      [color.red(), color.green(), color.blue(), color.alpha()]
+    })
+    .collect();
+  Ok(PreviewImage { width, height, pixels })
+}
+
+struct Canvas<'a> {
+  pixmap: Pixmap,
+  /// Converts the cells to pixels.
+  transform: Transform,
+  pattern: &'a Pattern,
+}
+
+impl Canvas<'_> {
+  fn draw_fabric(&mut self, show_holes: bool) {
+    let fabric = &self.pattern.fabric;
+    self.pixmap.fill(color(fabric.color));
+    if !show_holes {
+      return;
+    }
+
+    let mut builder = PathBuilder::new();
+    for x in 0..=fabric.width {
+      for y in 0..=fabric.height {
+        builder.push_circle(x.into(), y.into(), 0.06);
+      }
+    }
+    if let Some(path) = builder.finish() {
+      self.fill(&path, fabric.color.blend(Rgb::BLACK, 0.15));
+    }
+  }
+
+  fn draw_fullstitch(&mut self, stitch: &FullStitch) {
+    let (x, y) = (stitch.x.to_f32(), stitch.y.to_f32());
+    let (size, strands) = match stitch.kind {
+      FullStitchKind::Full => (1.0, self.strands(stitch.palindex).full),
+      FullStitchKind::Petite => (0.5, self.strands(stitch.palindex).petite),
+    };
+    let color = self.palette_color(stitch.palindex);
+    let width = thread_width(self.pattern, strands);
+
+    // The bottom leg goes from the bottom left to the top right corner and the top leg crosses it.
+    let inset = STITCH_INSET * size;
+    let (left, top, right, bottom) = (x + inset, y + inset, x + size - inset, y + size - inset);
+    self.draw_thread(&[(left, bottom), (right, top)], color, width);
+    self.draw_thread(&[(left, top), (right, bottom)], color, width);
+  }
+
+  fn draw_partstitch(&mut self, stitch: &PartStitch) {
+    let (x, y) = (stitch.x.to_f32(), stitch.y.to_f32());
+    let (size, strands) = match stitch.kind {
+      PartStitchKind::Half => (1.0, self.strands(stitch.palindex).half),
+      // A quarter stitch lies on the diagonal of its quarter, from the cell corner to the cell center.
+      PartStitchKind::Quarter => (0.5, self.strands(stitch.palindex).quarter),
+    };
+    let color = self.palette_color(stitch.palindex);
+    let width = thread_width(self.pattern, strands);
+
+    let inset = STITCH_INSET * size;
+    let (left, top, right, bottom) = (x + inset, y + inset, x + size - inset, y + size - inset);
+    let points = match stitch.direction {
+      PartStitchDirection::Forward => [(left, bottom), (right, top)],
+      PartStitchDirection::Backward => [(left, top), (right, bottom)],
+    };
+    self.draw_thread(&points, color, width);
+  }
+
+  fn draw_linestitch(&mut self, stitch: &LineStitch) {
+    let strands = match stitch.kind {
+      LineStitchKind::Back => self.strands(stitch.palindex).back,
+      LineStitchKind::Straight => self.strands(stitch.palindex).straight,
+    };
+    let points = [
+      (stitch.x.0.to_f32(), stitch.y.0.to_f32()),
+      (stitch.x.1.to_f32(), stitch.y.1.to_f32()),
+    ];
+    self.draw_thread(
+      &points,
+      self.palette_color(stitch.palindex),
+      thread_width(self.pattern, strands),
+    );
+  }
+
+  fn draw_specialstitch(&mut self, stitch: &SpecialStitch) {
+    let Some(model) = self.pattern.special_stitch_models.get(stitch.modindex as usize) else {
+      return;
+    };
+    let transform = SpecialStitchTransform::new(stitch, model);
+    let color = self.palette_color(stitch.palindex);
+    let width = thread_width(self.pattern, self.strands(stitch.palindex).special);
+
+    for linestitch in model.linestitches.iter() {
+      let points = [
+        transform.apply(linestitch.x.0.to_f32(), linestitch.y.0.to_f32()),
+        transform.apply(linestitch.x.1.to_f32(), linestitch.y.1.to_f32()),
+      ];
+      self.draw_thread(&points, color, width);
+    }
+    for curve in model.curvedstitches.iter() {
+      let points: Vec<_> = curve
+        .points
+        .iter()
+        .map(|(x, y)| transform.apply(x.to_f32(), y.to_f32()))
+        .collect();
+      self.draw_thread(&points, color, width);
+    }
+    for nodestitch in model.nodestitches.iter() {
+      let center = transform.apply(nodestitch.x.to_f32(), nodestitch.y.to_f32());
+      self.draw_nodestitch(
+        &NodeStitch {
+          palindex: stitch.palindex,
+          ..nodestitch.clone()
+        },
+        center,
+      );
+    }
+  }
+
+  fn draw_nodestitch(&mut self, stitch: &NodeStitch, (x, y): Point) {
+    let color = self.palette_color(stitch.palindex);
+    let path = match stitch.kind {
+      NodeStitchKind::FrenchKnot => {
+        let diameter = self.pattern.stitch_settings.display_thickness[12] * THICKNESS_UNIT;
+        PathBuilder::from_circle(x, y, diameter / 2.0)
+      }
+      NodeStitchKind::Bead => {
+        let (length, diameter) = self.bead_size(stitch.palindex);
+        // The bead lies along the vertical axis unless it is rotated.
+        let (width, height) = if stitch.rotated {
+          (length, diameter)
+        } else {
+          (diameter, length)
+        };
+        Rect::from_xywh(x - width / 2.0, y - height / 2.0, width, height).and_then(PathBuilder::from_oval)
+      }
+    };
+    let Some(path) = path else {
+      return;
+    };
+
+    self.fill(&path, color);
+    if let Some((outline_color, outline_width)) = self.outline(color) {
+      self.stroke(&path, outline_color, outline_width);
+    }
+  }
+
+  /// Returns the length and the diameter of the bead in cells.
+  fn bead_size(&self, palindex: u8) -> (f32, f32) {
+    let (length, diameter) = self
+      .pattern
+      .palette
+      .get(palindex as usize)
+      .and_then(|palitem| palitem.bead.as_ref())
+      .map(|bead| (bead.length, bead.diameter))
+      // The size of a common 11/0 seed bead.
+      .unwrap_or((1.5, 2.0));
+    let (x_count, y_count) = self.pattern.fabric.stitches_per_inch;
+    let x_count = f32::from(x_count.max(1));
+    let y_count = f32::from(y_count.max(1));
+    (
+      length * y_count / MILLIMETERS_PER_INCH,
+      diameter * x_count / MILLIMETERS_PER_INCH,
+    )
+  }
+
+  /// Draws a thread through the points, smoothing the corners of curves.
+  fn draw_thread(&mut self, points: &[Point], color: Rgb, width: f32) {
+    let Some(path) = thread_path(points) else {
+      return;
+    };
+    if let Some((outline_color, outline_width)) = self.outline(color) {
+      self.stroke(&path, outline_color, width + outline_width * 2.0);
+    }
+    self.stroke(&path, color, width);
+  }
+
+  /// Returns the color and the width of the stitch outline, if the stitches are outlined.
+  fn outline(&self, color: Rgb) -> Option<(Rgb, f32)> {
+    let settings = &self.pattern.stitch_settings;
+    if !settings.outlined_stitches {
+      return None;
+    }
+    let outline = &settings.stitch_outline;
+    let outline_color = outline.color.unwrap_or_else(|| {
+      let percentage = f32::from(outline.color_percentage.min(100)) / 100.0;
+      color.blend(Rgb::BLACK, 1.0 - percentage)
+    });
+    Some((outline_color, outline.thickness * THICKNESS_UNIT))
+  }
+
+  fn strands(&self, palindex: u8) -> StitchStrands<u8> {
+    self.pattern.strands(palindex as usize)
+  }
+
+  fn palette_color(&self, palindex: u8) -> Rgb {
+    self
+      .pattern
+      .palette
+      .get(palindex as usize)
+      .map(|palitem| palitem.color)
+      .unwrap_or(Rgb::BLACK)
+  }
+
+  fn fill(&mut self, path: &Path, fill: Rgb) {
+    self
+      .pixmap
+      .fill_path(path, &paint(fill), FillRule::Winding, self.transform, None);
+  }
+
+  fn stroke(&mut self, path: &Path, stroke: Rgb, width: f32) {
+    let stroke_style = Stroke {
+      width,
+      line_cap: LineCap::Round,
+      line_join: LineJoin::Round,
+      ..Default::default()
+    };
+    self
+      .pixmap
+      .stroke_path(path, &paint(stroke), &stroke_style, self.transform, None);
+  }
+}
+
+/// Returns the width of the thread in cells.
+fn thread_width(pattern: &Pattern, strands: u8) -> f32 {
+  let display_thickness = &pattern.stitch_settings.display_thickness;
+  let index = usize::from(strands.clamp(1, 12)) - 1;
+  display_thickness[index] * THICKNESS_UNIT
+}
+
+/// Builds a path through the points.
+/// The inner points of curves are smoothed with quadratic curves that pass the midpoints between them.
+fn thread_path(points: &[Point]) -> Option<Path> {
+  let (first, rest) = points.split_first()?;
+  let (last, middle) = rest.split_last()?;
+
+  let mut builder = PathBuilder::new();
+  builder.move_to(first.0, first.1);
+  for (index, point) in middle.iter().enumerate() {
+    let end = match middle.get(index + 1) {
+      Some(next) => ((point.0 + next.0) / 2.0, (point.1 + next.1) / 2.0),
+      None => *last,
+    };
+    builder.quad_to(point.0, point.1, end.0, end.1);
+  }
+  if middle.is_empty() {
+    builder.line_to(last.0, last.1);
+  }
+  builder.finish()
+}
+
+fn color(color: Rgb) -> tiny_skia::Color {
+  tiny_skia::Color::from_rgba8(color.r, color.g, color.b, 0xFF)
+}
+
+fn paint(fill: Rgb) -> Paint<'static> {
+  let mut paint = Paint::default();
+  paint.set_color(color(fill));
+  paint.anti_alias = true;
+  paint
+}
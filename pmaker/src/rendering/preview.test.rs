@@ -0,0 +1,249 @@
+use super::*;
+use crate::builder::PatternBuilder;
+use crate::schemas::coords::{Coord, CurveCoord};
+
+const RED: Rgb = Rgb::new(0xC0, 0x10, 0x20);
+
+fn options() -> PreviewOptions {
+  PreviewOptions {
+    cell_size: 20,
+    show_fabric_holes: false,
+  }
+}
+
+fn rgba(color: Rgb) -> [u8; 4] {
+  [color.r, color.g, color.b, 0xFF]
+}
+
+fn count_pixels(image: &PreviewImage, color: Rgb) -> usize {
+  image.pixels.chunks(4).filter(|pixel| *pixel == rgba(color)).count()
+}
+
+/// Returns the width and the height of the area that the color takes.
+fn extent(image: &PreviewImage, color: Rgb) -> (u32, u32) {
+  let (mut min, mut max) = ((u32::MAX, u32::MAX), (0, 0));
+  for y in 0..image.height {
+    for x in 0..image.width {
+      if image.pixel(x, y) == rgba(color) {
+        min = (min.0.min(x), min.1.min(y));
+        max = (max.0.max(x), max.1.max(y));
+      }
+    }
+  }
+  (max.0 + 1 - min.0, max.1 + 1 - min.1)
+}
+
+#[test]
+fn renders_fabric() {
+  let mut builder = PatternBuilder::new(3, 2);
+  builder.fabric(Fabric {
+    color: Rgb::new(0xFA, 0xEB, 0xD7),
+    ..PatternBuilder::new(3, 2).build().unwrap().fabric
+  });
+  let pattern = builder.build().unwrap();
+
+  let image = render_preview(&pattern, &options()).unwrap();
+  assert_eq!((image.width, image.height), (60, 40));
+  assert_eq!(image.pixels.len(), 60 * 40 * 4);
+  assert!(image.pixels.chunks(4).all(|pixel| pixel == [0xFA, 0xEB, 0xD7, 0xFF]));
+
+  let image = render_preview(&pattern, &PreviewOptions::default()).unwrap();
+  assert_eq!((image.width, image.height), (48, 32));
+  // The fabric holes are darker than the fabric.
+  assert_ne!(image.pixel(16, 16), [0xFA, 0xEB, 0xD7, 0xFF]);
+  assert_eq!(image.pixel(8, 8), [0xFA, 0xEB, 0xD7, 0xFF]);
+}
+
+#[test]
+fn draws_stitches_as_threads() {
+  let mut builder = PatternBuilder::new(3, 1);
+  let palindex = builder.add_palette_item(PaletteItem::new("DMC", "321", RED)).unwrap();
+  builder
+    .add_fullstitch(FullStitch {
+      x: Coord::ZERO,
+      y: Coord::ZERO,
+      palindex,
+      kind: FullStitchKind::Full,
+    })
+    .add_partstitch(PartStitch {
+      x: Coord::from_cells(1),
+      y: Coord::ZERO,
+      palindex,
+      direction: PartStitchDirection::Forward,
+      kind: PartStitchKind::Half,
+    })
+    .add_fullstitch(FullStitch {
+      x: Coord::from_halves(5),
+      y: Coord::from_halves(1),
+      palindex,
+      kind: FullStitchKind::Petite,
+    });
+  let pattern = builder.build().unwrap();
+  let image = render_preview(&pattern, &options()).unwrap();
+
+  let fabric = rgba(Rgb::WHITE);
+  // The crossing of the full stitch.
+  assert_eq!(image.pixel(10, 10), rgba(RED));
+  // The half stitch covers only one diagonal.
+  assert_eq!(image.pixel(30, 10), rgba(RED));
+  assert_eq!(image.pixel(23, 3), fabric);
+  assert_eq!(image.pixel(36, 16), fabric);
+  // The petite stitch lies in the bottom right quarter.
+  assert_eq!(image.pixel(55, 15), rgba(RED));
+  assert_eq!(image.pixel(45, 5), fabric);
+}
+
+#[test]
+fn scales_threads_with_strands() {
+  let render = |strands: u8| {
+    let mut builder = PatternBuilder::new(1, 1);
+    let palindex = builder
+      .add_palette_item(PaletteItem {
+        strands: Some(StitchStrands {
+          back: Some(strands),
+          ..Default::default()
+        }),
+        ..PaletteItem::new("DMC", "321", RED)
+      })
+      .unwrap();
+    builder.add_linestitch(LineStitch {
+      x: (Coord::ZERO, Coord::from_cells(1)),
+      y: (Coord::HALF, Coord::HALF),
+      palindex,
+      kind: LineStitchKind::Back,
+    });
+    let mut pattern = builder.build().unwrap();
+    pattern.stitch_settings.outlined_stitches = false;
+    render_preview(&pattern, &options()).unwrap()
+  };
+
+  // The display thicknesses of 1 and 6 strands are 1.0 and 4.0, that is 0.2 and 0.8 of a cell.
+  assert_eq!(extent(&render(1), RED).1, 4);
+  assert_eq!(extent(&render(6), RED).1, 16);
+}
+
+#[test]
+fn outlines_stitches() {
+  let mut builder = PatternBuilder::new(1, 1);
+  let palindex = builder.add_palette_item(PaletteItem::new("DMC", "321", RED)).unwrap();
+  builder.add_fullstitch(FullStitch {
+    x: Coord::ZERO,
+    y: Coord::ZERO,
+    palindex,
+    kind: FullStitchKind::Full,
+  });
+  let mut pattern = builder.build().unwrap();
+  let outline = Rgb::new(0x00, 0x00, 0xFF);
+  pattern.stitch_settings.stitch_outline.color = Some(outline);
+  pattern.stitch_settings.stitch_outline.thickness = 1.0;
+
+  let image = render_preview(&pattern, &options()).unwrap();
+  assert!(count_pixels(&image, outline) > 0);
+
+  pattern.stitch_settings.outlined_stitches = false;
+  let image = render_preview(&pattern, &options()).unwrap();
+  assert_eq!(count_pixels(&image, outline), 0);
+}
+
+#[test]
+fn draws_node_stitches() {
+  let mut builder = PatternBuilder::new(4, 2);
+  builder.fabric(Fabric {
+    stitches_per_inch: (10, 10),
+    ..PatternBuilder::new(4, 2).build().unwrap().fabric
+  });
+  let knot = builder.add_palette_item(PaletteItem::new("DMC", "321", RED)).unwrap();
+  let bead = builder
+    .add_palette_item(PaletteItem {
+      bead: Some(Bead {
+        length: 5.08,
+        diameter: 2.54,
+      }),
+      ..PaletteItem::new("DMC", "321", Rgb::new(0x10, 0x60, 0x20))
+    })
+    .unwrap();
+  builder
+    .add_nodestitch(NodeStitch {
+      x: Coord::from_cells(1),
+      y: Coord::from_cells(1),
+      rotated: false,
+      palindex: knot,
+      kind: NodeStitchKind::FrenchKnot,
+    })
+    .add_nodestitch(NodeStitch {
+      x: Coord::from_cells(3),
+      y: Coord::from_cells(1),
+      rotated: true,
+      palindex: bead,
+      kind: NodeStitchKind::Bead,
+    });
+  let mut pattern = builder.build().unwrap();
+  pattern.stitch_settings.outlined_stitches = false;
+  let image = render_preview(&pattern, &options()).unwrap();
+
+  // The default knot thickness is 4.0, that is 0.8 of a cell, without the antialiased edges.
+  let (width, height) = extent(&image, RED);
+  assert!((14..=16).contains(&width) && width == height, "{width}x{height}");
+  // The bead is 2 cells long and 1 cell thick on the fabric of 10 stitches per inch.
+  let (width, height) = extent(&image, Rgb::new(0x10, 0x60, 0x20));
+  assert!(
+    (38..=40).contains(&width) && (18..=20).contains(&height),
+    "{width}x{height}"
+  );
+}
+
+#[test]
+fn draws_curved_special_stitches() {
+  let mut builder = PatternBuilder::new(2, 2);
+  let palindex = builder.add_palette_item(PaletteItem::new("DMC", "321", RED)).unwrap();
+  let modindex = builder
+    .add_special_stitch_model(SpecialStitchModel {
+      width: Coord::from_cells(2),
+      height: Coord::from_cells(2),
+      curvedstitches: vec![CurvedStitch {
+        points: vec![
+          (CurveCoord::from_thirtieths(0), CurveCoord::from_thirtieths(30)),
+          (CurveCoord::from_thirtieths(30), CurveCoord::from_thirtieths(0)),
+          (CurveCoord::from_thirtieths(60), CurveCoord::from_thirtieths(30)),
+        ],
+      }],
+      ..Default::default()
+    })
+    .unwrap();
+  builder.add_specialstitch(SpecialStitch {
+    x: Coord::ZERO,
+    y: Coord::ZERO,
+    rotation: 0,
+    flip: (false, false),
+    palindex,
+    modindex,
+  });
+  let pattern = builder.build().unwrap();
+  let image = render_preview(&pattern, &options()).unwrap();
+
+  // The top of the curve lies halfway between the ends and the middle control point.
+  assert_eq!(image.pixel(20, 10), rgba(RED));
+  assert_eq!(image.pixel(20, 3), rgba(Rgb::WHITE));
+  assert_eq!(image.pixel(20, 20), rgba(Rgb::WHITE));
+}
+
+#[test]
+fn fails_on_invalid_options() {
+  let pattern = PatternBuilder::new(2, 2).build().unwrap();
+  let options = PreviewOptions {
+    cell_size: 0,
+    ..Default::default()
+  };
+  assert!(render_preview(&pattern, &options).is_err());
+}
+
+#[test]
+fn fails_on_too_large_previews() {
+  let pattern = PatternBuilder::new(1000, 1000).build().unwrap();
+  let options = PreviewOptions {
+    cell_size: u32::MAX / 100,
+    ..Default::default()
+  };
+  let error = render_preview(&pattern, &options).unwrap_err();
+  assert!(error.to_string().starts_with("The preview of the pattern is too large"));
+}
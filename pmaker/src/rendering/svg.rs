@@ -0,0 +1,153 @@
+//! The export of a pattern chart to SVG.
+
+use std::fmt::Write;
+
+use super::chart::{ChartOptions, ChartScene, Point, Shape};
+use crate::schemas::color::Rgb;
+use crate::schemas::xsd::Pattern;
+
+#[cfg(test)]
+#[path = "svg.test.rs"]
+mod tests;
+
+/// Renders the chart of the pattern as an SVG document.
+pub fn render_svg_chart(pattern: &Pattern, options: &ChartOptions) -> String {
+  let scene = ChartScene::new(pattern, options);
+  let scale = options.cell_size;
+  let (width, height) = (f32::from(scene.width) * scale, f32::from(scene.height) * scale);
+
+  let mut svg = String::new();
+  writeln!(
+    svg,
+    r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#
+  )
+  .unwrap();
+  if !pattern.info.title.is_empty() {
+    writeln!(svg, "<title>{}</title>", escape(&pattern.info.title)).unwrap();
+  }
+
+  for shape in scene.shapes.iter() {
+    write_shape(&mut svg, shape, scale);
+  }
+
+  svg.push_str("</svg>\n");
+  svg
+}
+
+fn write_shape(svg: &mut String, shape: &Shape, scale: f32) {
+  let scaled = |(x, y): Point| (x * scale, y * scale);
+  let points = |points: &[Point]| {
+    points
+      .iter()
+      .map(|&point| {
+        let (x, y) = scaled(point);
+        format!("{x},{y}")
+      })
+      .collect::<Vec<_>>()
+      .join(" ")
+  };
+
+  match shape {
+    Shape::Rect { origin, size, fill } => {
+      let (x, y) = scaled(*origin);
+      let (width, height) = scaled(*size);
+      writeln!(
+        svg,
+        r#"<rect x="{x}" y="{y}" width="{width}" height="{height}" fill="{}"/>"#,
+        color(fill)
+      )
+      .unwrap();
+    }
+    Shape::Polygon { points: vertices, fill } => {
+      writeln!(
+        svg,
+        r#"<polygon points="{}" fill="{}"/>"#,
+        points(vertices),
+        color(fill)
+      )
+      .unwrap();
+    }
+    Shape::Line {
+      from,
+      to,
+      color: stroke,
+      thickness,
+    } => {
+      let ((x1, y1), (x2, y2)) = (scaled(*from), scaled(*to));
+      writeln!(
+        svg,
+        r#"<line x1="{x1}" y1="{y1}" x2="{x2}" y2="{y2}" stroke="{}" stroke-width="{thickness}" stroke-linecap="round"/>"#,
+        color(stroke)
+      )
+      .unwrap();
+    }
+    Shape::Polyline {
+      points: vertices,
+      color: stroke,
+      thickness,
+    } => {
+      writeln!(
+        svg,
+        r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="{thickness}" stroke-linecap="round" stroke-linejoin="round"/>"#,
+        points(vertices),
+        color(stroke)
+      )
+      .unwrap();
+    }
+    Shape::Circle { center, radius, fill } => {
+      let (cx, cy) = scaled(*center);
+      writeln!(
+        svg,
+        r#"<circle cx="{cx}" cy="{cy}" r="{radius}" fill="{}"/>"#,
+        color(fill)
+      )
+      .unwrap();
+    }
+    Shape::Symbol {
+      center,
+      size,
+      symbol,
+      font,
+      bold,
+      italic,
+      color: fill,
+    } => {
+      let (x, y) = scaled(*center);
+      write!(
+        svg,
+        r#"<text x="{x}" y="{y}" font-family="{}" font-size="{}" fill="{}" text-anchor="middle" dominant-baseline="central""#,
+        escape(font),
+        size * scale,
+        color(fill)
+      )
+      .unwrap();
+      if *bold {
+        svg.push_str(r#" font-weight="bold""#);
+      }
+      if *italic {
+        svg.push_str(r#" font-style="italic""#);
+      }
+      writeln!(svg, ">{}</text>", escape(&symbol.to_string())).unwrap();
+    }
+  }
+}
+
+fn color(color: &Rgb) -> String {
+  format!("#{color}")
+}
+
+/// Escapes the characters that are not allowed in the XML text and attribute values.
+fn escape(text: &str) -> String {
+  let mut escaped = String::with_capacity(text.len());
+  for ch in text.chars() {
+    match ch {
+      '&' => escaped.push_str("&amp;"),
+      '<' => escaped.push_str("&lt;"),
+      '>' => escaped.push_str("&gt;"),
+      '"' => escaped.push_str("&quot;"),
+      '\'' => escaped.push_str("&apos;"),
+      _ => escaped.push(ch),
+    }
+  }
+  escaped
+}
@@ -0,0 +1,74 @@
+use super::*;
+use crate::builder::PatternBuilder;
+use crate::schemas::coords::Coord;
+use crate::schemas::xsd::*;
+
+fn sample_pattern() -> Pattern {
+  let mut builder = PatternBuilder::new(3, 2);
+  builder.info(PatternInfo {
+    title: String::from("Roses & <Tulips>"),
+    ..Default::default()
+  });
+  let palindex = builder
+    .add_palette_item_with(
+      PaletteItem {
+        name: String::from("Red"),
+        ..PaletteItem::new("DMC", "321", Rgb::new(0xC7, 0x2B, 0x3B))
+      },
+      Formats::default(),
+      Symbols {
+        full: Some('&' as u16),
+        ..Default::default()
+      },
+    )
+    .unwrap();
+  builder
+    .add_fullstitch(FullStitch {
+      x: Coord::from_cells(1),
+      y: Coord::from_cells(1),
+      palindex,
+      kind: FullStitchKind::Full,
+    })
+    .add_linestitch(LineStitch {
+      x: (Coord::ZERO, Coord::from_cells(3)),
+      y: (Coord::ZERO, Coord::ZERO),
+      palindex,
+      kind: LineStitchKind::Back,
+    });
+  builder.build().unwrap()
+}
+
+#[test]
+fn renders_svg_chart() {
+  let svg = render_svg_chart(&sample_pattern(), &ChartOptions::default());
+
+  assert!(svg.starts_with(r##"<svg xmlns="http://www.w3.org/2000/svg" width="30" height="20" viewBox="0 0 30 20">"##));
+  assert!(svg.ends_with("</svg>\n"));
+  assert!(svg.contains("<title>Roses &amp; &lt;Tulips&gt;</title>"));
+  assert!(svg.contains(r##"<rect x="0" y="0" width="30" height="20" fill="#FFFFFF"/>"##));
+  assert!(svg.contains(
+    r##"<text x="15" y="15" font-family="CrossStitch3" font-size="10" fill="#000000" text-anchor="middle" dominant-baseline="central">&amp;</text>"##
+  ));
+  assert!(
+    svg.contains(r##"<line x1="0" y1="0" x2="30" y2="0" stroke="#C72B3B" stroke-width="1" stroke-linecap="round"/>"##)
+  );
+}
+
+#[test]
+fn scales_chart() {
+  let options = ChartOptions {
+    cell_size: 20.0,
+    ..Default::default()
+  };
+  let svg = render_svg_chart(&sample_pattern(), &options);
+  assert!(svg.contains(r##"width="60" height="40""##));
+  assert!(svg.contains(r##"<text x="30" y="30""##));
+}
+
+#[test]
+fn escapes_xml() {
+  assert_eq!(
+    escape(r##"<a href="x">'&'</a>"##),
+    "&lt;a href=&quot;x&quot;&gt;&apos;&amp;&apos;&lt;/a&gt;"
+  );
+}
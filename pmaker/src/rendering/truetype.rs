@@ -0,0 +1,345 @@
+//! A minimal reader and subsetter of TrueType fonts for embedding them in PDF documents.
+//!
+//! Only the tables that a PDF viewer needs to draw the glyphs are read and written.
+//! The subset keeps the glyph IDs of the original font and drops the outlines of the unused glyphs,
+//! so the text can be encoded with the glyph IDs directly.
+
+use std::collections::{BTreeSet, HashMap};
+
+use anyhow::Result;
+
+#[cfg(test)]
+#[path = "truetype.test.rs"]
+mod tests;
+
+/// The tables that are copied into the subset as is, besides the rewritten `glyf`, `loca` and `head`.
+const COPIED_TABLES: [&[u8; 4]; 8] = [b"OS/2", b"cmap", b"cvt ", b"fpgm", b"hhea", b"hmtx", b"maxp", b"prep"];
+
+/// The flags of the components of a composite glyph.
+const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+const WE_HAVE_A_SCALE: u16 = 0x0008;
+const MORE_COMPONENTS: u16 = 0x0020;
+const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
+const WE_HAVE_A_TWO_BY_TWO: u16 = 0x0080;
+
+#[derive(Debug, Clone)]
+pub(crate) struct TrueTypeFont<'a> {
+  tables: HashMap<[u8; 4], &'a [u8]>,
+  units_per_em: u16,
+  /// The bounding box of all the glyphs in font units.
+  pub bbox: [i16; 4],
+  pub ascender: i16,
+  pub descender: i16,
+  pub cap_height: i16,
+  /// The offsets of the glyphs in the `glyf` table, one more than the number of glyphs.
+  glyph_offsets: Vec<usize>,
+  advances: Vec<u16>,
+  cmap: Cmap<'a>,
+}
+
+/// The subtable of the `cmap` table that maps the characters to the glyphs.
+#[derive(Debug, Clone, Copy)]
+enum Cmap<'a> {
+  SegmentMapping(&'a [u8]),
+  SegmentedCoverage(&'a [u8]),
+  /// The mapping of a symbol font, whose characters are usually shifted into the private use area.
+  Symbol(&'a [u8]),
+}
+
+impl<'a> TrueTypeFont<'a> {
+  pub fn parse(data: &'a [u8]) -> Result<Self> {
+    let malformed = || anyhow::anyhow!("The font is malformed");
+    if !matches!(read_u32(data, 0), Some(0x0001_0000 | 0x7472_7565)) {
+      anyhow::bail!("Only the TrueType fonts with glyph outlines are supported");
+    }
+
+    let table_count = read_u16(data, 4).ok_or_else(malformed)?;
+    let mut tables = HashMap::new();
+    for index in 0..usize::from(table_count) {
+      let record = 12 + index * 16;
+      let tag: [u8; 4] = data.get(record..record + 4).ok_or_else(malformed)?.try_into()?;
+      let offset = read_u32(data, record + 8).ok_or_else(malformed)? as usize;
+      let length = read_u32(data, record + 12).ok_or_else(malformed)? as usize;
+      let table = data.get(offset..offset + length).ok_or_else(malformed)?;
+      tables.insert(tag, table);
+    }
+    let table = |tag: &[u8; 4]| {
+      tables
+        .get(tag)
+        .copied()
+        .ok_or_else(|| anyhow::anyhow!("The font has no {} table", String::from_utf8_lossy(tag)))
+    };
+
+    let head = table(b"head")?;
+    let units_per_em = read_u16(head, 18).filter(|&units| units > 0).ok_or_else(malformed)?;
+    let mut bbox = [0; 4];
+    for (index, value) in bbox.iter_mut().enumerate() {
+      *value = read_i16(head, 36 + index * 2).ok_or_else(malformed)?;
+    }
+    let long_offsets = read_u16(head, 50).ok_or_else(malformed)? == 1;
+
+    let hhea = table(b"hhea")?;
+    let ascender = read_i16(hhea, 4).ok_or_else(malformed)?;
+    let descender = read_i16(hhea, 6).ok_or_else(malformed)?;
+    let metrics_count = usize::from(read_u16(hhea, 34).ok_or_else(malformed)?);
+
+    let glyph_count = usize::from(read_u16(table(b"maxp")?, 4).ok_or_else(malformed)?);
+    let loca = table(b"loca")?;
+    let glyf = table(b"glyf")?;
+    let glyph_offsets = (0..=glyph_count)
+      .map(|index| match long_offsets {
+        true => read_u32(loca, index * 4).map(|offset| offset as usize),
+        false => read_u16(loca, index * 2).map(|offset| usize::from(offset) * 2),
+      })
+      .collect::<Option<Vec<_>>>()
+      .filter(|offsets| offsets.windows(2).all(|pair| pair[0] <= pair[1]) && offsets[glyph_count] <= glyf.len())
+      .ok_or_else(malformed)?;
+
+    let hmtx = table(b"hmtx")?;
+    let mut advances = Vec::with_capacity(glyph_count);
+    for index in 0..glyph_count {
+      // The glyphs past the long metrics have the advance of the last one.
+      let advance = read_u16(hmtx, index.min(metrics_count.max(1) - 1) * 4).ok_or_else(malformed)?;
+      advances.push(advance);
+    }
+
+    // Fonts without the OS/2 table are rare, the capitals are about 0.7 of the em then.
+    let cap_height = tables
+      .get(b"OS/2")
+      .filter(|os2| read_u16(os2, 0).is_some_and(|version| version >= 2))
+      .and_then(|os2| read_i16(os2, 88))
+      .unwrap_or((i32::from(units_per_em) * 7 / 10) as i16);
+
+    let cmap = find_cmap(table(b"cmap")?).ok_or_else(|| anyhow::anyhow!("The font has no supported character map"))?;
+
+    Ok(TrueTypeFont {
+      tables,
+      units_per_em,
+      bbox,
+      ascender,
+      descender,
+      cap_height,
+      glyph_offsets,
+      advances,
+      cmap,
+    })
+  }
+
+  /// Returns the glyph of the character, if the font has one.
+  pub fn glyph_id(&self, ch: char) -> Option<u16> {
+    let code = u32::from(ch);
+    let glyph_id = match self.cmap {
+      Cmap::SegmentMapping(subtable) => segment_mapping_glyph(subtable, code),
+      Cmap::SegmentedCoverage(subtable) => segmented_coverage_glyph(subtable, code),
+      Cmap::Symbol(subtable) => segment_mapping_glyph(subtable, code)
+        .filter(|&glyph_id| glyph_id != 0)
+        .or_else(|| {
+          (code <= 0xFF)
+            .then(|| segment_mapping_glyph(subtable, 0xF000 + code))
+            .flatten()
+        }),
+    };
+    glyph_id.filter(|&glyph_id| glyph_id != 0 && usize::from(glyph_id) < self.advances.len())
+  }
+
+  /// Returns the advance width of the glyph in thousandths of the font size.
+  pub fn advance(&self, glyph_id: u16) -> f32 {
+    let advance = self.advances.get(usize::from(glyph_id)).copied().unwrap_or_default();
+    self.to_thousandths(advance.into())
+  }
+
+  /// Converts a length in font units to thousandths of the font size.
+  pub fn to_thousandths(&self, units: i32) -> f32 {
+    units as f32 * 1000.0 / f32::from(self.units_per_em)
+  }
+
+  /// Writes a font that has only the outlines of the glyphs and the components they are made of.
+  /// The glyph IDs are kept, so the other glyphs are left empty.
+  pub fn subset(&self, glyph_ids: &BTreeSet<u16>) -> Vec<u8> {
+    let glyf = self.tables[b"glyf"];
+    let mut kept = BTreeSet::new();
+    // The glyph 0 is the one drawn for the missing characters and is always kept.
+    let mut pending: Vec<u16> = std::iter::once(0).chain(glyph_ids.iter().copied()).collect();
+    while let Some(glyph_id) = pending.pop() {
+      if usize::from(glyph_id) >= self.advances.len() || !kept.insert(glyph_id) {
+        continue;
+      }
+      pending.extend(composite_components(self.glyph(glyf, glyph_id)));
+    }
+
+    let mut new_glyf = Vec::new();
+    let mut new_loca = Vec::with_capacity((self.advances.len() + 1) * 4);
+    for glyph_id in 0..self.advances.len() {
+      new_loca.extend((new_glyf.len() as u32).to_be_bytes());
+      if kept.contains(&(glyph_id as u16)) {
+        new_glyf.extend(self.glyph(glyf, glyph_id as u16));
+        pad(&mut new_glyf);
+      }
+    }
+    new_loca.extend((new_glyf.len() as u32).to_be_bytes());
+
+    let mut head = self.tables[b"head"].to_vec();
+    // The checksum adjustment is computed over the whole font at the end, and the offsets are long now.
+    head[8..12].fill(0);
+    head[50..52].copy_from_slice(&1u16.to_be_bytes());
+
+    let mut tables: Vec<([u8; 4], &[u8])> = vec![(*b"glyf", &new_glyf), (*b"head", &head), (*b"loca", &new_loca)];
+    for tag in COPIED_TABLES {
+      if let Some(table) = self.tables.get(tag) {
+        tables.push((*tag, table));
+      }
+    }
+    tables.sort_by_key(|(tag, _)| *tag);
+    write_font(&tables)
+  }
+
+  fn glyph<'b>(&self, glyf: &'b [u8], glyph_id: u16) -> &'b [u8] {
+    let index = usize::from(glyph_id);
+    &glyf[self.glyph_offsets[index]..self.glyph_offsets[index + 1]]
+  }
+}
+
+/// Finds the best supported subtable: the full Unicode one, then the BMP one, then the symbol one.
+fn find_cmap(cmap: &[u8]) -> Option<Cmap<'_>> {
+  let count = read_u16(cmap, 2)?;
+  let mut found = None;
+  for index in 0..usize::from(count) {
+    let record = 4 + index * 8;
+    let (platform, encoding) = (read_u16(cmap, record)?, read_u16(cmap, record + 2)?);
+    let subtable = cmap.get(read_u32(cmap, record + 4)? as usize..)?;
+    let candidate = match (platform, encoding, read_u16(subtable, 0)?) {
+      (0 | 3, _, 12) if platform == 0 || encoding == 10 => (0, Cmap::SegmentedCoverage(subtable)),
+      (0, _, 4) | (3, 1, 4) => (1, Cmap::SegmentMapping(subtable)),
+      (3, 0, 4) => (2, Cmap::Symbol(subtable)),
+      _ => continue,
+    };
+    if found.as_ref().is_none_or(|(rank, _)| candidate.0 < *rank) {
+      found = Some(candidate);
+    }
+  }
+  found.map(|(_, cmap)| cmap)
+}
+
+/// Looks up the glyph in a format 4 subtable.
+fn segment_mapping_glyph(subtable: &[u8], code: u32) -> Option<u16> {
+  let code = u16::try_from(code).ok()?;
+  let segments = usize::from(read_u16(subtable, 6)? / 2);
+  let end_codes = 14;
+  let start_codes = end_codes + segments * 2 + 2;
+  let deltas = start_codes + segments * 2;
+  let range_offsets = deltas + segments * 2;
+  for segment in 0..segments {
+    if code > read_u16(subtable, end_codes + segment * 2)? {
+      continue;
+    }
+    let start = read_u16(subtable, start_codes + segment * 2)?;
+    if code < start {
+      return None;
+    }
+    let delta = read_u16(subtable, deltas + segment * 2)?;
+    let range_offset_position = range_offsets + segment * 2;
+    let range_offset = read_u16(subtable, range_offset_position)?;
+    if range_offset == 0 {
+      return Some(code.wrapping_add(delta));
+    }
+    let position = range_offset_position + usize::from(range_offset) + usize::from(code - start) * 2;
+    let glyph_id = read_u16(subtable, position)?;
+    return (glyph_id != 0).then(|| glyph_id.wrapping_add(delta));
+  }
+  None
+}
+
+/// Looks up the glyph in a format 12 subtable.
+fn segmented_coverage_glyph(subtable: &[u8], code: u32) -> Option<u16> {
+  let groups = read_u32(subtable, 12)? as usize;
+  for group in 0..groups {
+    let record = 16 + group * 12;
+    let (start, end) = (read_u32(subtable, record)?, read_u32(subtable, record + 4)?);
+    if (start..=end).contains(&code) {
+      let glyph_id = read_u32(subtable, record + 8)? + (code - start);
+      return u16::try_from(glyph_id).ok();
+    }
+  }
+  None
+}
+
+/// Returns the glyphs that a composite glyph is made of.
+fn composite_components(glyph: &[u8]) -> Vec<u16> {
+  let mut components = Vec::new();
+  if read_i16(glyph, 0).is_none_or(|contours| contours >= 0) {
+    return components;
+  }
+  let mut offset = 10;
+  while let (Some(flags), Some(glyph_id)) = (read_u16(glyph, offset), read_u16(glyph, offset + 2)) {
+    components.push(glyph_id);
+    offset += 4 + if flags & ARG_1_AND_2_ARE_WORDS != 0 { 4 } else { 2 };
+    offset += match flags {
+      flags if flags & WE_HAVE_A_SCALE != 0 => 2,
+      flags if flags & WE_HAVE_AN_X_AND_Y_SCALE != 0 => 4,
+      flags if flags & WE_HAVE_A_TWO_BY_TWO != 0 => 8,
+      _ => 0,
+    };
+    if flags & MORE_COMPONENTS == 0 {
+      break;
+    }
+  }
+  components
+}
+
+/// Writes the font file with the tables sorted by their tags.
+fn write_font(tables: &[([u8; 4], &[u8])]) -> Vec<u8> {
+  let count = tables.len() as u16;
+  let entry_selector = 15 - count.leading_zeros() as u16;
+  let search_range = (1 << entry_selector) * 16;
+
+  let mut font = Vec::new();
+  font.extend(0x0001_0000u32.to_be_bytes());
+  for value in [count, search_range, entry_selector, count * 16 - search_range] {
+    font.extend(value.to_be_bytes());
+  }
+  let mut offset = 12 + tables.len() * 16;
+  for (tag, table) in tables {
+    font.extend(tag);
+    font.extend(checksum(table).to_be_bytes());
+    font.extend((offset as u32).to_be_bytes());
+    font.extend((table.len() as u32).to_be_bytes());
+    offset += table.len().next_multiple_of(4);
+  }
+  let mut head_offset = 0;
+  for (tag, table) in tables {
+    if tag == b"head" {
+      head_offset = font.len();
+    }
+    font.extend(*table);
+    pad(&mut font);
+  }
+
+  let adjustment = 0xB1B0_AFBAu32.wrapping_sub(checksum(&font));
+  font[head_offset + 8..head_offset + 12].copy_from_slice(&adjustment.to_be_bytes());
+  font
+}
+
+fn checksum(data: &[u8]) -> u32 {
+  data.chunks(4).fold(0u32, |sum, chunk| {
+    let mut word = [0; 4];
+    word[..chunk.len()].copy_from_slice(chunk);
+    sum.wrapping_add(u32::from_be_bytes(word))
+  })
+}
+
+/// Pads the data to a multiple of 4 bytes.
+fn pad(data: &mut Vec<u8>) {
+  data.resize(data.len().next_multiple_of(4), 0);
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+  Some(u16::from_be_bytes(data.get(offset..offset + 2)?.try_into().ok()?))
+}
+
+fn read_i16(data: &[u8], offset: usize) -> Option<i16> {
+  read_u16(data, offset).map(|value| value as i16)
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+  Some(u32::from_be_bytes(data.get(offset..offset + 4)?.try_into().ok()?))
+}
@@ -0,0 +1,51 @@
+use super::*;
+
+const DEJAVU_SANS: &[u8] = include_bytes!("../../resources/fonts/DejaVuSans.ttf");
+
+#[test]
+fn reads_fonts() {
+  let font = TrueTypeFont::parse(DEJAVU_SANS).unwrap();
+  assert_eq!(font.units_per_em, 2048);
+  assert!(font.ascender > 0 && font.descender < 0 && font.cap_height > 0);
+
+  let glyph_a = font.glyph_id('A').unwrap();
+  assert_eq!(font.glyph_id('Ж').map(|glyph_id| glyph_id != glyph_a), Some(true));
+  assert!(font.glyph_id('★').is_some());
+  assert_eq!(font.glyph_id('\u{E000}'), None);
+  assert!(font.advance(glyph_a) > 500.0 && font.advance(glyph_a) < 800.0);
+}
+
+#[test]
+fn fails_on_unsupported_fonts() {
+  let error = TrueTypeFont::parse(b"OTTO\0\0\0\0").unwrap_err();
+  assert_eq!(
+    error.to_string(),
+    "Only the TrueType fonts with glyph outlines are supported"
+  );
+  let error = TrueTypeFont::parse(b"\0\x01\0\0\0\x01").unwrap_err();
+  assert_eq!(error.to_string(), "The font is malformed");
+}
+
+#[test]
+fn subsets_fonts() {
+  let font = TrueTypeFont::parse(DEJAVU_SANS).unwrap();
+  let glyph_a = font.glyph_id('A').unwrap();
+  let glyph_b = font.glyph_id('B').unwrap();
+  // The letter is made of the base letter and the breve.
+  let glyph_short_i = font.glyph_id('Й').unwrap();
+  let components = composite_components(font.glyph(font.tables[b"glyf"], glyph_short_i));
+  assert!(!components.is_empty());
+
+  let data = font.subset(&BTreeSet::from([glyph_a, glyph_short_i]));
+  assert!(data.len() < DEJAVU_SANS.len() / 10);
+  assert_eq!(checksum(&data), 0xB1B0_AFBA);
+
+  let subset = TrueTypeFont::parse(&data).unwrap();
+  let glyf = subset.tables[b"glyf"];
+  assert_eq!(subset.glyph_id('A'), Some(glyph_a));
+  assert_eq!(subset.advance(glyph_a), font.advance(glyph_a));
+  for glyph_id in [0, glyph_a, glyph_short_i].into_iter().chain(components) {
+    assert_eq!(subset.glyph(glyf, glyph_id), font.glyph(font.tables[b"glyf"], glyph_id));
+  }
+  assert!(subset.glyph(glyf, glyph_b).is_empty());
+}
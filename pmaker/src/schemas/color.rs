@@ -0,0 +1,237 @@
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::Result;
+
+#[cfg(test)]
+#[path = "color.test.rs"]
+mod tests;
+
+/// An sRGB color.
+/// It is serialized as an uppercase hex string, like `2C3225`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Rgb {
+  pub r: u8,
+  pub g: u8,
+  pub b: u8,
+}
+
+impl Rgb {
+  pub const BLACK: Rgb = Rgb::new(0x00, 0x00, 0x00);
+  pub const WHITE: Rgb = Rgb::new(0xFF, 0xFF, 0xFF);
+
+  pub const fn new(r: u8, g: u8, b: u8) -> Self {
+    Rgb { r, g, b }
+  }
+
+  /// Parses a color from a hex string, like `2C3225` or `#2C3225`.
+  pub fn from_hex(hex: &str) -> Result<Self> {
+    let digits = hex.strip_prefix('#').unwrap_or(hex);
+    if digits.len() != 6 {
+      anyhow::bail!("Invalid hex color {hex:?}: expected 6 hex digits");
+    }
+    let mut buf = [0; 3];
+    hex::decode_to_slice(digits, &mut buf).map_err(|err| anyhow::anyhow!("Invalid hex color {hex:?}: {err}"))?;
+    Ok(Rgb::from(buf))
+  }
+
+  /// Formats the color as an uppercase hex string without the leading `#`.
+  pub fn to_hex(&self) -> String {
+    hex::encode_upper([self.r, self.g, self.b])
+  }
+
+  /// Linearly interpolates between two colors.
+  /// `ratio` is the fraction of the `other` color, from `0.0` to `1.0`.
+  pub fn blend(&self, other: Rgb, ratio: f32) -> Rgb {
+    let ratio = ratio.clamp(0.0, 1.0);
+    let channel = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * ratio).round() as u8;
+    Rgb::new(
+      channel(self.r, other.r),
+      channel(self.g, other.g),
+      channel(self.b, other.b),
+    )
+  }
+
+  /// Mixes the colors proportionally to their weights, e.g. the strands of blended threads.
+  /// Returns `None` if there are no colors or all the weights are zero.
+  pub fn mix<I: IntoIterator<Item = (Rgb, u32)>>(colors: I) -> Option<Rgb> {
+    let mut total_weight = 0;
+    let mut mixed = [0u32; 3];
+    for (color, weight) in colors {
+      total_weight += weight;
+      mixed[0] += color.r as u32 * weight;
+      mixed[1] += color.g as u32 * weight;
+      mixed[2] += color.b as u32 * weight;
+    }
+    if total_weight == 0 {
+      return None;
+    }
+    Some(Rgb::from(
+      mixed.map(|channel| ((channel + total_weight / 2) / total_weight) as u8),
+    ))
+  }
+
+  /// Converts the color to the CIELAB color space under the D65 illuminant.
+  pub fn to_lab(&self) -> Lab {
+    let [r, g, b] = [self.r, self.g, self.b].map(|channel| {
+      let channel = channel as f32 / 255.0;
+      if channel <= 0.04045 {
+        channel / 12.92
+      } else {
+        ((channel + 0.055) / 1.055).powf(2.4)
+      }
+    });
+
+    let x = (0.4124564 * r + 0.3575761 * g + 0.1804375 * b) / 0.95047;
+    let y = 0.2126729 * r + 0.7151522 * g + 0.0721750 * b;
+    let z = (0.0193339 * r + 0.119192 * g + 0.9503041 * b) / 1.08883;
+
+    fn f(t: f32) -> f32 {
+      if t > 216.0 / 24389.0 {
+        t.cbrt()
+      } else {
+        (24389.0 / 27.0 * t + 16.0) / 116.0
+      }
+    }
+
+    let (fx, fy, fz) = (f(x), f(y), f(z));
+    Lab {
+      l: 116.0 * fy - 16.0,
+      a: 500.0 * (fx - fy),
+      b: 200.0 * (fy - fz),
+    }
+  }
+}
+
+impl From<[u8; 3]> for Rgb {
+  fn from([r, g, b]: [u8; 3]) -> Self {
+    Rgb { r, g, b }
+  }
+}
+
+impl From<Rgb> for [u8; 3] {
+  fn from(color: Rgb) -> Self {
+    [color.r, color.g, color.b]
+  }
+}
+
+impl FromStr for Rgb {
+  type Err = anyhow::Error;
+
+  fn from_str(s: &str) -> Result<Self> {
+    Rgb::from_hex(s)
+  }
+}
+
+impl fmt::Display for Rgb {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{:02X}{:02X}{:02X}", self.r, self.g, self.b)
+  }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Rgb {
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+    serializer.collect_str(self)
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Rgb {
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+    let hex = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+    Rgb::from_hex(&hex).map_err(serde::de::Error::custom)
+  }
+}
+
+#[cfg(feature = "json-schema")]
+impl schemars::JsonSchema for Rgb {
+  fn schema_name() -> std::borrow::Cow<'static, str> {
+    "Rgb".into()
+  }
+
+  fn json_schema(_generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+    schemars::json_schema!({
+      "type": "string",
+      "pattern": "^#?[0-9A-Fa-f]{6}$",
+    })
+  }
+}
+
+/// A color in the CIELAB color space.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Lab {
+  pub l: f32,
+  pub a: f32,
+  pub b: f32,
+}
+
+impl Lab {
+  /// Computes the CIEDE2000 color difference.
+  pub fn delta_e(&self, other: &Lab) -> f32 {
+    use std::f64::consts::PI;
+
+    let (l1, a1, b1) = (self.l as f64, self.a as f64, self.b as f64);
+    let (l2, a2, b2) = (other.l as f64, other.a as f64, other.b as f64);
+
+    let c_mean = ((a1.hypot(b1)) + (a2.hypot(b2))) / 2.0;
+    let g = 0.5 * (1.0 - (c_mean.powi(7) / (c_mean.powi(7) + 25f64.powi(7))).sqrt());
+    let a1 = a1 * (1.0 + g);
+    let a2 = a2 * (1.0 + g);
+
+    let c1 = a1.hypot(b1);
+    let c2 = a2.hypot(b2);
+    let hue = |a: f64, b: f64| {
+      if a == 0.0 && b == 0.0 {
+        0.0
+      } else {
+        b.atan2(a).rem_euclid(2.0 * PI)
+      }
+    };
+    let h1 = hue(a1, b1);
+    let h2 = hue(a2, b2);
+
+    let delta_l = l2 - l1;
+    let delta_c = c2 - c1;
+    let delta_h = if c1 * c2 == 0.0 {
+      0.0
+    } else {
+      let delta = h2 - h1;
+      if delta > PI {
+        delta - 2.0 * PI
+      } else if delta < -PI {
+        delta + 2.0 * PI
+      } else {
+        delta
+      }
+    };
+    let delta_h = 2.0 * (c1 * c2).sqrt() * (delta_h / 2.0).sin();
+
+    let l_mean = (l1 + l2) / 2.0;
+    let c_mean = (c1 + c2) / 2.0;
+    let h_mean = if c1 * c2 == 0.0 {
+      h1 + h2
+    } else if (h1 - h2).abs() <= PI {
+      (h1 + h2) / 2.0
+    } else if h1 + h2 < 2.0 * PI {
+      (h1 + h2 + 2.0 * PI) / 2.0
+    } else {
+      (h1 + h2 - 2.0 * PI) / 2.0
+    };
+
+    let t =
+      1.0 - 0.17 * (h_mean - PI / 6.0).cos() + 0.24 * (2.0 * h_mean).cos() + 0.32 * (3.0 * h_mean + PI / 30.0).cos()
+        - 0.20 * (4.0 * h_mean - 63.0 * PI / 180.0).cos();
+    let delta_theta = PI / 6.0 * (-((h_mean * 180.0 / PI - 275.0) / 25.0).powi(2)).exp();
+    let r_c = 2.0 * (c_mean.powi(7) / (c_mean.powi(7) + 25f64.powi(7))).sqrt();
+    let s_l = 1.0 + 0.015 * (l_mean - 50.0).powi(2) / (20.0 + (l_mean - 50.0).powi(2)).sqrt();
+    let s_c = 1.0 + 0.045 * c_mean;
+    let s_h = 1.0 + 0.015 * c_mean * t;
+    let r_t = -(2.0 * delta_theta).sin() * r_c;
+
+    let l_term = delta_l / s_l;
+    let c_term = delta_c / s_c;
+    let h_term = delta_h / s_h;
+    (l_term.powi(2) + c_term.powi(2) + h_term.powi(2) + r_t * c_term * h_term).sqrt() as f32
+  }
+}
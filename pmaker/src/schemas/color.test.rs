@@ -0,0 +1,76 @@
+use super::*;
+
+#[test]
+fn parses_and_formats_hex_colors() {
+  assert_eq!(Rgb::from_hex("2C3225").unwrap(), Rgb::new(0x2C, 0x32, 0x25));
+  assert_eq!(Rgb::from_hex("#a6d3d9").unwrap(), Rgb::new(0xA6, 0xD3, 0xD9));
+  assert_eq!("FFFFFF".parse::<Rgb>().unwrap(), Rgb::WHITE);
+
+  assert!(Rgb::from_hex("FFF").is_err());
+  assert!(Rgb::from_hex("GGGGGG").is_err());
+  assert!(Rgb::from_hex("#FFFFFFF").is_err());
+
+  assert_eq!(Rgb::new(0xA6, 0xD3, 0xD9).to_hex(), "A6D3D9");
+  assert_eq!(Rgb::new(0x00, 0x0A, 0xFF).to_string(), "000AFF");
+}
+
+#[test]
+fn blends_colors() {
+  assert_eq!(Rgb::BLACK.blend(Rgb::WHITE, 0.0), Rgb::BLACK);
+  assert_eq!(Rgb::BLACK.blend(Rgb::WHITE, 1.0), Rgb::WHITE);
+  assert_eq!(Rgb::BLACK.blend(Rgb::WHITE, 0.5), Rgb::new(0x80, 0x80, 0x80));
+  assert_eq!(Rgb::BLACK.blend(Rgb::WHITE, 2.0), Rgb::WHITE);
+}
+
+#[test]
+fn mixes_colors() {
+  assert_eq!(
+    Rgb::mix([(Rgb::BLACK, 1), (Rgb::WHITE, 1)]),
+    Some(Rgb::new(0x80, 0x80, 0x80))
+  );
+  assert_eq!(
+    Rgb::mix([(Rgb::BLACK, 1), (Rgb::WHITE, 3)]),
+    Some(Rgb::new(0xBF, 0xBF, 0xBF))
+  );
+  assert_eq!(Rgb::mix([]), None);
+  assert_eq!(Rgb::mix([(Rgb::WHITE, 0)]), None);
+}
+
+#[test]
+fn converts_to_lab() {
+  let white = Rgb::WHITE.to_lab();
+  assert!((white.l - 100.0).abs() < 1e-3 && white.a.abs() < 1e-2 && white.b.abs() < 1e-2);
+
+  let black = Rgb::BLACK.to_lab();
+  assert!(black.l.abs() < 1e-3 && black.a.abs() < 1e-3 && black.b.abs() < 1e-3);
+
+  let red = Rgb::new(0xFF, 0x00, 0x00).to_lab();
+  assert!((red.l - 53.24).abs() < 0.01 && (red.a - 80.09).abs() < 0.01 && (red.b - 67.2).abs() < 0.01);
+}
+
+#[test]
+fn computes_ciede2000() {
+  // The reference values are taken from "The CIEDE2000 Color-Difference Formula: Implementation Notes,
+  // Supplementary Test Data, and Mathematical Observations" by G. Sharma, W. Wu and E. N. Dalal.
+  for (lab1, lab2, expected) in [
+    ((50.0, 2.6772, -79.7751), (50.0, 0.0, -82.7485), 2.0425),
+    ((50.0, 0.0, 0.0), (50.0, -1.0, 2.0), 2.3669),
+    ((50.0, 2.5, 0.0), (50.0, 3.1736, 0.5854), 1.0),
+    ((50.0, 2.5, 0.0), (73.0, 25.0, -18.0), 27.1492),
+    ((60.2574, -34.0099, 36.2677), (60.4626, -34.1751, 39.4387), 1.2644),
+    ((2.0776, 0.0795, -1.135), (0.9033, -0.0636, -0.5514), 0.9082),
+  ] {
+    let lab1 = Lab {
+      l: lab1.0,
+      a: lab1.1,
+      b: lab1.2,
+    };
+    let lab2 = Lab {
+      l: lab2.0,
+      a: lab2.1,
+      b: lab2.2,
+    };
+    assert!((lab1.delta_e(&lab2) - expected).abs() < 1e-3);
+    assert!((lab2.delta_e(&lab1) - expected).abs() < 1e-3);
+  }
+}
@@ -0,0 +1,168 @@
+use std::fmt;
+use std::ops::{Add, Sub};
+
+#[cfg(test)]
+#[path = "coords.test.rs"]
+mod tests;
+
+/// A coordinate measured in halves of a cell.
+///
+/// It is used for the positions of the stitches and joints, which always lie on the half-cell grid.
+/// It is serialized as the number of half-cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct Coord(i32);
+
+impl Coord {
+  pub const ZERO: Coord = Coord(0);
+  pub const HALF: Coord = Coord(1);
+
+  pub const fn from_halves(halves: i32) -> Self {
+    Coord(halves)
+  }
+
+  pub const fn from_cells(cells: i32) -> Self {
+    Coord(cells * 2)
+  }
+
+  /// Returns the coordinate in halves of a cell.
+  pub const fn halves(self) -> i32 {
+    self.0
+  }
+
+  /// Returns the index of the cell that contains the coordinate.
+  pub const fn cell(self) -> i32 {
+    self.0.div_euclid(2)
+  }
+
+  /// Returns the coordinate in cells.
+  pub fn to_f32(self) -> f32 {
+    self.0 as f32 / 2.0
+  }
+
+  /// Returns the coordinate in cells.
+  pub fn to_f64(self) -> f64 {
+    self.0 as f64 / 2.0
+  }
+}
+
+impl From<Coord> for f32 {
+  fn from(coord: Coord) -> Self {
+    coord.to_f32()
+  }
+}
+
+impl From<Coord> for f64 {
+  fn from(coord: Coord) -> Self {
+    coord.to_f64()
+  }
+}
+
+impl TryFrom<f32> for Coord {
+  type Error = anyhow::Error;
+
+  /// Converts a coordinate in cells, which must be a multiple of a half.
+  fn try_from(value: f32) -> Result<Self, Self::Error> {
+    let halves = value * 2.0;
+    if !halves.is_finite() || halves.fract() != 0.0 || halves.abs() > i32::MAX as f32 {
+      anyhow::bail!("The coordinate {value} is not a multiple of a half-cell");
+    }
+    Ok(Coord(halves as i32))
+  }
+}
+
+impl Add for Coord {
+  type Output = Coord;
+
+  fn add(self, rhs: Coord) -> Coord {
+    Coord(self.0 + rhs.0)
+  }
+}
+
+impl Sub for Coord {
+  type Output = Coord;
+
+  fn sub(self, rhs: Coord) -> Coord {
+    Coord(self.0 - rhs.0)
+  }
+}
+
+impl fmt::Display for Coord {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.to_f64())
+  }
+}
+
+/// A coordinate of a curve point measured in thirtieths of a cell.
+///
+/// This is the resolution of the curved stitches in the XSD format.
+/// It is serialized as the number of thirtieths.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct CurveCoord(i32);
+
+impl CurveCoord {
+  /// The number of curve units in a cell.
+  pub const RESOLUTION: i32 = 30;
+
+  pub const fn from_thirtieths(thirtieths: i32) -> Self {
+    CurveCoord(thirtieths)
+  }
+
+  /// Returns the coordinate in thirtieths of a cell.
+  pub const fn thirtieths(self) -> i32 {
+    self.0
+  }
+
+  /// Returns the coordinate in cells.
+  pub fn to_f32(self) -> f32 {
+    self.0 as f32 / CurveCoord::RESOLUTION as f32
+  }
+
+  /// Returns the coordinate in cells.
+  pub fn to_f64(self) -> f64 {
+    self.0 as f64 / CurveCoord::RESOLUTION as f64
+  }
+}
+
+impl From<Coord> for CurveCoord {
+  fn from(coord: Coord) -> Self {
+    CurveCoord(coord.0 * CurveCoord::RESOLUTION / 2)
+  }
+}
+
+impl From<CurveCoord> for f32 {
+  fn from(coord: CurveCoord) -> Self {
+    coord.to_f32()
+  }
+}
+
+impl From<CurveCoord> for f64 {
+  fn from(coord: CurveCoord) -> Self {
+    coord.to_f64()
+  }
+}
+
+impl Add for CurveCoord {
+  type Output = CurveCoord;
+
+  fn add(self, rhs: CurveCoord) -> CurveCoord {
+    CurveCoord(self.0 + rhs.0)
+  }
+}
+
+impl Sub for CurveCoord {
+  type Output = CurveCoord;
+
+  fn sub(self, rhs: CurveCoord) -> CurveCoord {
+    CurveCoord(self.0 - rhs.0)
+  }
+}
+
+impl fmt::Display for CurveCoord {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}/{}", self.0, CurveCoord::RESOLUTION)
+  }
+}
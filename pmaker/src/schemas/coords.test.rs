@@ -0,0 +1,44 @@
+use std::collections::BTreeSet;
+
+use super::*;
+
+#[test]
+fn converts_coords() {
+  let coord = Coord::from_halves(5);
+  assert_eq!(coord.halves(), 5);
+  assert_eq!(coord.cell(), 2);
+  assert_eq!(coord.to_f32(), 2.5);
+  assert_eq!(f64::from(coord), 2.5);
+  assert_eq!(coord, Coord::from_cells(2) + Coord::HALF);
+  assert_eq!(coord - Coord::HALF, Coord::from_cells(2));
+  assert_eq!(Coord::from_halves(-1).cell(), -1);
+  assert_eq!(coord.to_string(), "2.5");
+
+  assert_eq!(Coord::try_from(2.5).unwrap(), coord);
+  assert_eq!(Coord::try_from(-0.5).unwrap(), Coord::from_halves(-1));
+  assert!(Coord::try_from(2.25).is_err());
+  assert!(Coord::try_from(f32::NAN).is_err());
+}
+
+#[test]
+fn converts_curve_coords() {
+  let coord = CurveCoord::from_thirtieths(47);
+  assert_eq!(coord.thirtieths(), 47);
+  assert_eq!(coord.to_f64(), 47.0 / 30.0);
+  assert_eq!(coord.to_string(), "47/30");
+  assert_eq!(CurveCoord::from(Coord::from_halves(3)), CurveCoord::from_thirtieths(45));
+  assert_eq!(coord - CurveCoord::from(Coord::HALF), CurveCoord::from_thirtieths(32));
+}
+
+#[test]
+fn coords_are_exact_keys() {
+  let a = Coord::from_halves(1) + Coord::from_halves(4);
+  let b = Coord::from_halves(3) + Coord::from_halves(2);
+  assert_eq!(a, b);
+
+  let cells: BTreeSet<(Coord, Coord)> = [(a, Coord::ZERO), (b, Coord::ZERO), (Coord::HALF, Coord::ZERO)].into();
+  assert_eq!(
+    cells.into_iter().collect::<Vec<_>>(),
+    vec![(Coord::HALF, Coord::ZERO), (a, Coord::ZERO)]
+  );
+}
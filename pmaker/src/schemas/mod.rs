@@ -1 +1,3 @@
+pub mod color;
+pub mod coords;
 pub mod xsd;
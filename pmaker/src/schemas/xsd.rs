@@ -1,4 +1,9 @@
-#[derive(Debug, PartialEq)]
+use super::color::Rgb;
+use super::coords::{Coord, CurveCoord};
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Pattern {
   pub info: PatternInfo,
   pub fabric: Fabric,
@@ -18,7 +23,9 @@ pub struct Pattern {
   pub print_settings: PrintSettings,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct PatternInfo {
   pub title: String,
   pub author: String,
@@ -27,28 +34,38 @@ pub struct PatternInfo {
   pub description: String,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Fabric {
   pub width: u16,
   pub height: u16,
   pub stitches_per_inch: (u8, u8),
   pub kind: String,
   pub name: String,
-  pub color: String,
+  pub color: Rgb,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct PaletteItem {
   pub brand: String,
   pub number: String,
   pub name: String,
-  pub color: String,
+  pub color: Rgb,
   pub blends: Option<Vec<Blend>>,
   pub bead: Option<Bead>,
   pub strands: Option<StitchStrands<Option<u8>>>,
 }
 
-#[derive(Debug, PartialEq, Default)]
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+  feature = "json-schema",
+  derive(schemars::JsonSchema),
+  schemars(rename = "StitchStrands_{T}")
+)]
 pub struct StitchStrands<T> {
   pub full: T,
   pub petite: T,
@@ -60,20 +77,26 @@ pub struct StitchStrands<T> {
   pub special: T,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Blend {
   pub brand: String,
   pub number: String,
   pub strands: u8,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Bead {
   pub length: f32,
   pub diameter: f32,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Formats {
   pub symbol: SymbolFormat,
   pub back_stitch: LineStitchFormat,
@@ -84,30 +107,38 @@ pub struct Formats {
   pub font: FontFormat,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct SymbolFormat {
   pub use_alt_bg_color: bool,
-  pub bg_color: String,
-  pub fg_color: String,
+  pub bg_color: Rgb,
+  pub fg_color: Rgb,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct LineStitchFormat {
   pub use_alt_color: bool,
-  pub color: String,
+  pub color: Rgb,
   pub style: u16,
   pub thickness: f32,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct NodeStitchFormat {
   pub use_dot_style: bool,
   pub use_alt_color: bool,
-  pub color: String,
+  pub color: Rgb,
   pub thickness: f32,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct FontFormat {
   pub font_name: Option<String>,
   pub bold: bool,
@@ -116,7 +147,9 @@ pub struct FontFormat {
   pub small_stitch_size: u8,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Symbols {
   pub full: Option<u16>,
   pub petite: Option<u16>,
@@ -126,97 +159,128 @@ pub struct Symbols {
   pub bead: Option<u16>,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct FullStitch {
-  pub x: f32,
-  pub y: f32,
+  pub x: Coord,
+  pub y: Coord,
   pub palindex: u8,
   pub kind: FullStitchKind,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum FullStitchKind {
   Full,
   Petite,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct PartStitch {
-  pub x: f32,
-  pub y: f32,
+  pub x: Coord,
+  pub y: Coord,
   pub palindex: u8,
   pub direction: PartStitchDirection,
   pub kind: PartStitchKind,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum PartStitchDirection {
   Forward,
   Backward,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum PartStitchKind {
   Half,
   Quarter,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct LineStitch {
-  pub x: (f32, f32),
-  pub y: (f32, f32),
+  pub x: (Coord, Coord),
+  pub y: (Coord, Coord),
   pub palindex: u8,
   pub kind: LineStitchKind,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum LineStitchKind {
   Back,
   Straight,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct NodeStitch {
-  pub x: f32,
-  pub y: f32,
+  pub x: Coord,
+  pub y: Coord,
   pub rotated: bool,
   pub palindex: u8,
   pub kind: NodeStitchKind,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum NodeStitchKind {
   FrenchKnot,
   Bead,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct SpecialStitch {
-  pub x: f32,
-  pub y: f32,
+  pub x: Coord,
+  pub y: Coord,
   pub rotation: u16,
   pub flip: (bool, bool),
   pub palindex: u8,
   pub modindex: u8,
 }
 
-#[derive(Debug, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct SpecialStitchModel {
   pub unique_name: String,
   pub name: String,
-  pub width: f32,
-  pub height: f32,
+  pub width: Coord,
+  pub height: Coord,
   pub linestitches: Vec<LineStitch>,
   pub nodestitches: Vec<NodeStitch>,
   pub curvedstitches: Vec<CurvedStitch>,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct CurvedStitch {
-  pub points: Vec<(f32, f32)>,
+  pub points: Vec<(CurveCoord, CurveCoord)>,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Grid {
   pub major_lines_interval: u16,
   pub minor_screen_lines: GridLineStyle,
@@ -225,13 +289,17 @@ pub struct Grid {
   pub major_printer_lines: GridLineStyle,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct GridLineStyle {
-  pub color: String,
+  pub color: Rgb,
   pub thickness: f32,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct PatternSettings {
   pub default_stitch_font: String,
   pub view: u16,
@@ -243,7 +311,9 @@ pub struct PatternSettings {
   pub gaps_between_stitches: bool,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct StitchSettings {
   pub default_strands: StitchStrands<u8>,
   pub display_thickness: [f32; 13], // 1..=12 - strands, 13 - french knot.
@@ -251,14 +321,18 @@ pub struct StitchSettings {
   pub stitch_outline: StitchOutline,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct StitchOutline {
-  pub color: Option<String>,
+  pub color: Option<Rgb>,
   pub color_percentage: u8,
   pub thickness: f32,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct SymbolSettings {
   pub screen_spacing: (u16, u16),
   pub printer_spacing: (u16, u16),
@@ -272,7 +346,9 @@ pub struct SymbolSettings {
   pub use_triangles_behind_quarter_stitches: bool,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct PrintSettings {
   pub font: Font,
   pub header: String,
@@ -283,7 +359,9 @@ pub struct PrintSettings {
   pub center_chart_on_pages: bool,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Font {
   pub name: String,
   pub size: u16,
@@ -291,7 +369,9 @@ pub struct Font {
   pub italic: bool,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct PageMargins {
   pub left: f32,
   pub right: f32,
@@ -300,3 +380,189 @@ pub struct PageMargins {
   pub header: f32,
   pub footer: f32,
 }
+
+// The defaults below are the settings of a new pattern in Pattern Maker.
+
+impl Default for Formats {
+  fn default() -> Self {
+    let line_format = LineStitchFormat {
+      use_alt_color: false,
+      color: Rgb::BLACK,
+      style: 5,
+      thickness: 1.0,
+    };
+    let node_format = NodeStitchFormat {
+      use_dot_style: true,
+      use_alt_color: false,
+      color: Rgb::BLACK,
+      thickness: 4.0,
+    };
+    Formats {
+      symbol: SymbolFormat {
+        use_alt_bg_color: false,
+        bg_color: Rgb::WHITE,
+        fg_color: Rgb::BLACK,
+      },
+      back_stitch: line_format.clone(),
+      straight_stitch: line_format.clone(),
+      french_knot: node_format.clone(),
+      bead: node_format,
+      special_stitch: line_format,
+      font: FontFormat {
+        font_name: Some(String::from("CrossStitch3")),
+        bold: false,
+        italic: false,
+        stitch_size: 100,
+        small_stitch_size: 60,
+      },
+    }
+  }
+}
+
+impl Default for Grid {
+  fn default() -> Self {
+    Grid {
+      major_lines_interval: 10,
+      minor_screen_lines: GridLineStyle {
+        color: Rgb::new(0xC8, 0xC8, 0xC8),
+        thickness: 0.072,
+      },
+      major_screen_lines: GridLineStyle {
+        color: Rgb::new(0x64, 0x64, 0x64),
+        thickness: 0.072,
+      },
+      minor_printer_lines: GridLineStyle {
+        color: Rgb::BLACK,
+        thickness: 0.144,
+      },
+      major_printer_lines: GridLineStyle {
+        color: Rgb::BLACK,
+        thickness: 0.504,
+      },
+    }
+  }
+}
+
+impl Default for PatternSettings {
+  fn default() -> Self {
+    PatternSettings {
+      default_stitch_font: String::from("CrossStitch3"),
+      view: 2,
+      zoom: 0,
+      show_grid: true,
+      show_rulers: true,
+      show_centering_marks: false,
+      show_fabric_colors_with_symbols: false,
+      gaps_between_stitches: false,
+    }
+  }
+}
+
+impl Default for StitchSettings {
+  fn default() -> Self {
+    StitchSettings {
+      default_strands: StitchStrands {
+        full: 2,
+        petite: 2,
+        half: 2,
+        quarter: 2,
+        back: 1,
+        straight: 1,
+        french_knot: 2,
+        special: 2,
+      },
+      display_thickness: [1.0, 1.5, 2.5, 3.0, 3.5, 4.0, 4.5, 5.0, 5.5, 6.0, 6.5, 7.0, 4.0],
+      outlined_stitches: true,
+      stitch_outline: StitchOutline {
+        color: None,
+        color_percentage: 80,
+        thickness: 0.2,
+      },
+    }
+  }
+}
+
+impl Default for SymbolSettings {
+  fn default() -> Self {
+    SymbolSettings {
+      screen_spacing: (1, 1),
+      printer_spacing: (1, 1),
+      scale_using_maximum_font_width: true,
+      scale_using_font_height: true,
+      stitch_size: 100,
+      small_stitch_size: 60,
+      draw_symbols_over_backstitches: false,
+      show_stitch_color: false,
+      use_large_half_stitch_symbol: false,
+      use_triangles_behind_quarter_stitches: false,
+    }
+  }
+}
+
+impl Default for PrintSettings {
+  fn default() -> Self {
+    PrintSettings {
+      font: Font {
+        name: String::from("Courier New"),
+        size: 10,
+        weight: 400,
+        italic: false,
+      },
+      header: String::from("&l&t &r&n"),
+      footer: String::new(),
+      margins: PageMargins {
+        left: 0.5,
+        right: 0.5,
+        top: 0.5,
+        bottom: 0.5,
+        header: 0.5,
+        footer: 0.5,
+      },
+      show_page_numbers: true,
+      show_adjacent_page_numbers: true,
+      center_chart_on_pages: false,
+    }
+  }
+}
+
+impl PaletteItem {
+  /// Creates a plain thread without a name, blends, bead and own strands.
+  pub fn new(brand: &str, number: &str, color: Rgb) -> Self {
+    PaletteItem {
+      brand: brand.to_string(),
+      number: number.to_string(),
+      name: String::new(),
+      color,
+      blends: None,
+      bead: None,
+      strands: None,
+    }
+  }
+}
+
+impl StitchStrands<Option<u8>> {
+  /// Fills the strands that are not set with the default ones.
+  pub fn or(&self, defaults: &StitchStrands<u8>) -> StitchStrands<u8> {
+    StitchStrands {
+      full: self.full.unwrap_or(defaults.full),
+      petite: self.petite.unwrap_or(defaults.petite),
+      half: self.half.unwrap_or(defaults.half),
+      quarter: self.quarter.unwrap_or(defaults.quarter),
+      back: self.back.unwrap_or(defaults.back),
+      straight: self.straight.unwrap_or(defaults.straight),
+      french_knot: self.french_knot.unwrap_or(defaults.french_knot),
+      special: self.special.unwrap_or(defaults.special),
+    }
+  }
+}
+
+impl Pattern {
+  /// Returns the strands of the palette item, falling back to the default strands of the pattern.
+  pub fn strands(&self, palindex: usize) -> StitchStrands<u8> {
+    let defaults = &self.stitch_settings.default_strands;
+    match self.palette.get(palindex).and_then(|palitem| palitem.strands.as_ref()) {
+      Some(strands) => strands.or(defaults),
+      None => defaults.clone(),
+    }
+  }
+}
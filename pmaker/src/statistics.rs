@@ -0,0 +1,267 @@
+//! The statistics of a pattern: the stitch counts, the used colors and the size of the stitched area.
+
+use crate::schemas::xsd::*;
+use crate::units::CENTIMETERS_PER_INCH;
+
+#[cfg(test)]
+#[path = "statistics.test.rs"]
+mod tests;
+
+/// The number of stitches of each kind.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StitchCounts {
+  pub full: usize,
+  pub petite: usize,
+  pub half: usize,
+  pub quarter: usize,
+  pub back: usize,
+  pub straight: usize,
+  pub french_knot: usize,
+  pub bead: usize,
+  pub special: usize,
+}
+
+impl StitchCounts {
+  pub fn total(&self) -> usize {
+    self.full
+      + self.petite
+      + self.half
+      + self.quarter
+      + self.back
+      + self.straight
+      + self.french_knot
+      + self.bead
+      + self.special
+  }
+}
+
+/// Counts the stitches of each palette item.
+pub fn count_stitches(pattern: &Pattern) -> Vec<StitchCounts> {
+  let mut counts = vec![StitchCounts::default(); pattern.palette.len()];
+  for stitch in &pattern.fullstitches {
+    if let Some(counts) = counts.get_mut(usize::from(stitch.palindex)) {
+      match stitch.kind {
+        FullStitchKind::Full => counts.full += 1,
+        FullStitchKind::Petite => counts.petite += 1,
+      }
+    }
+  }
+  for stitch in &pattern.partstitches {
+    if let Some(counts) = counts.get_mut(usize::from(stitch.palindex)) {
+      match stitch.kind {
+        PartStitchKind::Half => counts.half += 1,
+        PartStitchKind::Quarter => counts.quarter += 1,
+      }
+    }
+  }
+  for stitch in &pattern.linestitches {
+    if let Some(counts) = counts.get_mut(usize::from(stitch.palindex)) {
+      match stitch.kind {
+        LineStitchKind::Back => counts.back += 1,
+        LineStitchKind::Straight => counts.straight += 1,
+      }
+    }
+  }
+  for stitch in &pattern.nodestitches {
+    if let Some(counts) = counts.get_mut(usize::from(stitch.palindex)) {
+      match stitch.kind {
+        NodeStitchKind::FrenchKnot => counts.french_knot += 1,
+        NodeStitchKind::Bead => counts.bead += 1,
+      }
+    }
+  }
+  for stitch in &pattern.specialstitches {
+    if let Some(counts) = counts.get_mut(usize::from(stitch.palindex)) {
+      counts.special += 1;
+    }
+  }
+
+  counts
+}
+
+/// The number of beads of a palette item that is a bead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BeadCount {
+  pub palindex: usize,
+  pub brand: String,
+  pub number: String,
+  pub count: usize,
+}
+
+/// The area of the fabric that is covered by the stitches, in whole cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StitchedArea {
+  pub left: u16,
+  pub top: u16,
+  pub width: u16,
+  pub height: u16,
+}
+
+/// The size of a design in stitches and on the fabric.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DesignSize {
+  /// The width and the height in stitches.
+  pub stitches: (u16, u16),
+  pub inches: (f32, f32),
+  pub centimeters: (f32, f32),
+}
+
+impl DesignSize {
+  /// Computes the size of the design stitched on a fabric of the given count.
+  /// The count is in stitches per inch, which is fractional for the fabrics worked over two threads,
+  /// e.g. 12.5 on 25-count linen.
+  pub fn new(stitches: (u16, u16), stitches_per_inch: (f32, f32)) -> Self {
+    let inches = (
+      f32::from(stitches.0) / stitches_per_inch.0.max(1.0),
+      f32::from(stitches.1) / stitches_per_inch.1.max(1.0),
+    );
+    DesignSize {
+      stitches,
+      inches,
+      centimeters: (inches.0 * CENTIMETERS_PER_INCH, inches.1 * CENTIMETERS_PER_INCH),
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PatternStatistics {
+  /// The stitch counts of each palette item.
+  pub stitches: Vec<StitchCounts>,
+  /// The stitch counts of the whole pattern.
+  pub total_stitches: StitchCounts,
+  /// The number of palette items that are used by any stitch.
+  pub used_colors: usize,
+  pub palette_size: usize,
+  /// The bead counts of the palette items that are beads and are used.
+  pub beads: Vec<BeadCount>,
+  /// The total length of the back stitches in inches.
+  pub backstitch_length: f32,
+  /// The bounding box of the stitches, or `None` if the pattern has no stitches.
+  pub stitched_area: Option<StitchedArea>,
+  /// The size of the stitched area on the pattern fabric.
+  pub design_size: DesignSize,
+}
+
+impl Pattern {
+  /// Collects the statistics of the pattern.
+  pub fn statistics(&self) -> PatternStatistics {
+    let stitches = count_stitches(self);
+    let total_stitches = stitches
+      .iter()
+      .fold(StitchCounts::default(), |total, counts| StitchCounts {
+        full: total.full + counts.full,
+        petite: total.petite + counts.petite,
+        half: total.half + counts.half,
+        quarter: total.quarter + counts.quarter,
+        back: total.back + counts.back,
+        straight: total.straight + counts.straight,
+        french_knot: total.french_knot + counts.french_knot,
+        bead: total.bead + counts.bead,
+        special: total.special + counts.special,
+      });
+    let beads = self
+      .palette
+      .iter()
+      .zip(&stitches)
+      .enumerate()
+      .filter(|(_, (palitem, counts))| palitem.bead.is_some() && counts.bead > 0)
+      .map(|(palindex, (palitem, counts))| BeadCount {
+        palindex,
+        brand: palitem.brand.clone(),
+        number: palitem.number.clone(),
+        count: counts.bead,
+      })
+      .collect();
+
+    let (spi_x, spi_y) = self.fabric.stitches_per_inch;
+    let backstitch_length = self
+      .linestitches
+      .iter()
+      .filter(|stitch| stitch.kind == LineStitchKind::Back)
+      .map(|stitch| {
+        let width = (stitch.x.1.to_f32() - stitch.x.0.to_f32()) / f32::from(spi_x.max(1));
+        let height = (stitch.y.1.to_f32() - stitch.y.0.to_f32()) / f32::from(spi_y.max(1));
+        width.hypot(height)
+      })
+      .sum();
+
+    let stitched_area = stitched_area(self);
+    let design_size = DesignSize::new(
+      stitched_area.map_or((0, 0), |area| (area.width, area.height)),
+      (f32::from(spi_x), f32::from(spi_y)),
+    );
+
+    PatternStatistics {
+      used_colors: stitches.iter().filter(|counts| counts.total() > 0).count(),
+      palette_size: self.palette.len(),
+      stitches,
+      total_stitches,
+      beads,
+      backstitch_length,
+      stitched_area,
+      design_size,
+    }
+  }
+}
+
+/// Computes the bounding box of all the stitches.
+/// The stitches that lie on the cell borders, like back stitches and knots, count the cells after them.
+fn stitched_area(pattern: &Pattern) -> Option<StitchedArea> {
+  let mut bounds: Option<(f32, f32, f32, f32)> = None;
+  let mut extend = |left: f32, top: f32, right: f32, bottom: f32| {
+    let right = right.max(left.floor() + 1.0);
+    let bottom = bottom.max(top.floor() + 1.0);
+    let (l, t, r, b) = bounds.get_or_insert((left, top, right, bottom));
+    *l = l.min(left);
+    *t = t.min(top);
+    *r = r.max(right);
+    *b = b.max(bottom);
+  };
+
+  for stitch in &pattern.fullstitches {
+    let size = match stitch.kind {
+      FullStitchKind::Full => 1.0,
+      FullStitchKind::Petite => 0.5,
+    };
+    let (x, y) = (stitch.x.to_f32(), stitch.y.to_f32());
+    extend(x, y, x + size, y + size);
+  }
+  for stitch in &pattern.partstitches {
+    let size = match stitch.kind {
+      PartStitchKind::Half => 1.0,
+      PartStitchKind::Quarter => 0.5,
+    };
+    let (x, y) = (stitch.x.to_f32(), stitch.y.to_f32());
+    extend(x, y, x + size, y + size);
+  }
+  for stitch in &pattern.linestitches {
+    let (x0, x1) = (stitch.x.0.to_f32(), stitch.x.1.to_f32());
+    let (y0, y1) = (stitch.y.0.to_f32(), stitch.y.1.to_f32());
+    extend(x0.min(x1), y0.min(y1), x0.max(x1), y0.max(y1));
+  }
+  for stitch in &pattern.nodestitches {
+    let (x, y) = (stitch.x.to_f32(), stitch.y.to_f32());
+    extend(x, y, x, y);
+  }
+  for stitch in &pattern.specialstitches {
+    let Some(model) = pattern.special_stitch_models.get(usize::from(stitch.modindex)) else {
+      continue;
+    };
+    let (mut width, mut height) = (model.width.to_f32(), model.height.to_f32());
+    if matches!(stitch.rotation % 360, 90 | 270) {
+      (width, height) = (height, width);
+    }
+    let (x, y) = (stitch.x.to_f32(), stitch.y.to_f32());
+    extend(x, y, x + width, y + height);
+  }
+
+  let (left, top, right, bottom) = bounds?;
+  let (left, top) = (left.floor().max(0.0), top.floor().max(0.0));
+  let (right, bottom) = (right.ceil(), bottom.ceil());
+  Some(StitchedArea {
+    left: left as u16,
+    top: top as u16,
+    width: (right - left) as u16,
+    height: (bottom - top) as u16,
+  })
+}
@@ -0,0 +1,172 @@
+use super::*;
+use crate::builder::PatternBuilder;
+use crate::schemas::color::Rgb;
+use crate::schemas::coords::Coord;
+
+#[test]
+fn collects_statistics() {
+  let mut builder = PatternBuilder::new(100, 80);
+  let black = builder
+    .add_palette_item(PaletteItem::new("DMC", "310", Rgb::BLACK))
+    .unwrap();
+  let red = builder
+    .add_palette_item(PaletteItem::new("DMC", "321", Rgb::BLACK))
+    .unwrap();
+  builder
+    .add_palette_item(PaletteItem::new("DMC", "666", Rgb::BLACK))
+    .unwrap();
+  let bead = builder
+    .add_palette_item(PaletteItem {
+      bead: Some(Bead {
+        length: 2.5,
+        diameter: 1.5,
+      }),
+      ..PaletteItem::new("DMC", "00557", Rgb::BLACK)
+    })
+    .unwrap();
+  for x in 10..20 {
+    builder.add_fullstitch(FullStitch {
+      x: Coord::from_cells(x),
+      y: Coord::from_cells(5),
+      palindex: black,
+      kind: FullStitchKind::Full,
+    });
+  }
+  builder
+    .add_partstitch(PartStitch {
+      x: Coord::from_cells(12),
+      y: Coord::from_cells(30),
+      palindex: red,
+      direction: PartStitchDirection::Backward,
+      kind: PartStitchKind::Quarter,
+    })
+    .add_linestitch(LineStitch {
+      x: (Coord::from_cells(10), Coord::from_cells(24)),
+      y: (Coord::from_cells(6), Coord::from_cells(6)),
+      palindex: red,
+      kind: LineStitchKind::Back,
+    })
+    .add_linestitch(LineStitch {
+      x: (Coord::from_cells(10), Coord::from_cells(10)),
+      y: (Coord::from_cells(6), Coord::from_cells(20)),
+      palindex: red,
+      kind: LineStitchKind::Straight,
+    })
+    .add_nodestitch(NodeStitch {
+      x: Coord::from_cells(11),
+      y: Coord::from_cells(7),
+      rotated: false,
+      palindex: bead,
+      kind: NodeStitchKind::Bead,
+    })
+    .add_nodestitch(NodeStitch {
+      x: Coord::from_cells(12),
+      y: Coord::from_cells(7),
+      rotated: false,
+      palindex: bead,
+      kind: NodeStitchKind::Bead,
+    });
+  let statistics = builder.build().unwrap().statistics();
+
+  assert_eq!(statistics.stitches.len(), 4);
+  assert_eq!(statistics.stitches[usize::from(black)].full, 10);
+  assert_eq!(
+    statistics.stitches[usize::from(red)],
+    StitchCounts {
+      quarter: 1,
+      back: 1,
+      straight: 1,
+      ..Default::default()
+    }
+  );
+  assert_eq!(statistics.total_stitches.total(), 15);
+  assert_eq!(statistics.used_colors, 3);
+  assert_eq!(statistics.palette_size, 4);
+  assert_eq!(
+    statistics.beads,
+    vec![BeadCount {
+      palindex: usize::from(bead),
+      brand: String::from("DMC"),
+      number: String::from("00557"),
+      count: 2,
+    }]
+  );
+  // 14 cells of the default 14-count fabric, the straight stitch isn't counted.
+  assert!((statistics.backstitch_length - 1.0).abs() < 1e-6);
+
+  // The quarter stitch in the cell 30 sets the bottom, the back stitch ending on the 24th line sets the right.
+  assert_eq!(
+    statistics.stitched_area,
+    Some(StitchedArea {
+      left: 10,
+      top: 5,
+      width: 14,
+      height: 26,
+    })
+  );
+  assert_eq!(statistics.design_size.stitches, (14, 26));
+  assert!((statistics.design_size.inches.0 - 1.0).abs() < 1e-6);
+  assert!((statistics.design_size.centimeters.1 - 26.0 / 14.0 * 2.54).abs() < 1e-5);
+}
+
+#[test]
+fn handles_empty_patterns() {
+  let statistics = PatternBuilder::new(10, 10).build().unwrap().statistics();
+  assert_eq!(statistics.used_colors, 0);
+  assert_eq!(statistics.stitched_area, None);
+  assert_eq!(statistics.design_size.stitches, (0, 0));
+  assert_eq!(statistics.design_size.inches, (0.0, 0.0));
+}
+
+#[test]
+fn measures_special_stitches_and_knots() {
+  let mut builder = PatternBuilder::new(20, 20);
+  let palindex = builder
+    .add_palette_item(PaletteItem::new("DMC", "310", Rgb::BLACK))
+    .unwrap();
+  let modindex = builder
+    .add_special_stitch_model(SpecialStitchModel {
+      unique_name: String::from("Leaf"),
+      name: String::from("Leaf"),
+      width: Coord::from_cells(3),
+      height: Coord::from_cells(1),
+      linestitches: Vec::new(),
+      nodestitches: Vec::new(),
+      curvedstitches: Vec::new(),
+    })
+    .unwrap();
+  builder
+    .add_specialstitch(SpecialStitch {
+      x: Coord::from_cells(2),
+      y: Coord::from_cells(2),
+      rotation: 90,
+      flip: (false, false),
+      palindex,
+      modindex,
+    })
+    .add_nodestitch(NodeStitch {
+      x: Coord::from_cells(6),
+      y: Coord::from_cells(2),
+      rotated: false,
+      palindex,
+      kind: NodeStitchKind::FrenchKnot,
+    });
+  let statistics = builder.build().unwrap().statistics();
+  // The rotated model takes 1x3 cells, the knot on the corner takes the cell after it.
+  assert_eq!(
+    statistics.stitched_area,
+    Some(StitchedArea {
+      left: 2,
+      top: 2,
+      width: 5,
+      height: 3,
+    })
+  );
+}
+
+#[test]
+fn computes_design_size_on_fractional_counts() {
+  let design_size = DesignSize::new((25, 50), (12.5, 12.5));
+  assert_eq!(design_size.inches, (2.0, 4.0));
+  assert!((design_size.centimeters.0 - 5.08).abs() < 1e-5);
+}
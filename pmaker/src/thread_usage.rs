@@ -0,0 +1,318 @@
+//! The estimation of the thread length and the number of skeins that a pattern needs.
+//!
+//! The lengths are the paths of a single strand on the front and the back of the fabric:
+//!
+//! - a full stitch is two cell diagonals on the front, and the width and the height of the cell on the back,
+//! - a petite and a half stitch are one diagonal and one side, a quarter stitch is half of it,
+//! - a back, straight and curved stitch is assumed to take the same length on the back as on the front,
+//! - a french knot takes a fixed length.
+//!
+//! The path is multiplied by the number of strands, so the lengths are in meters of a single strand.
+//! Each blend component is stitched with its own strands, so the blends take the sum of them.
+
+use std::collections::HashMap;
+
+use crate::schemas::xsd::*;
+use crate::units::MILLIMETERS_PER_INCH;
+
+#[cfg(test)]
+#[path = "thread_usage.test.rs"]
+mod tests;
+
+/// The length of a single strand that a french knot takes, in millimeters.
+const FRENCH_KNOT_LENGTH: f32 = 25.0;
+
+/// The size of a skein of a thread brand.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkeinSize {
+  pub brand: String,
+  /// The length of the skein in meters.
+  pub length: f32,
+  /// The number of strands that the skein can be separated into.
+  pub strands: u8,
+}
+
+impl SkeinSize {
+  pub fn new(brand: &str, length: f32, strands: u8) -> Self {
+    SkeinSize {
+      brand: brand.to_string(),
+      length,
+      strands,
+    }
+  }
+
+  /// Returns the length of a single strand in the skein.
+  pub fn strand_length(&self) -> f32 {
+    self.length * f32::from(self.strands.max(1))
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThreadUsageOptions {
+  /// The share of the length that is added for the thread tails and the waste, e.g. `0.2` for 20%.
+  pub waste_factor: f32,
+  /// The skein sizes of the thread brands.
+  pub skein_sizes: Vec<SkeinSize>,
+  /// The skein size of the brands that are missing in `skein_sizes`.
+  pub default_skein_size: SkeinSize,
+}
+
+impl Default for ThreadUsageOptions {
+  fn default() -> Self {
+    ThreadUsageOptions {
+      waste_factor: 0.2,
+      skein_sizes: vec![
+        SkeinSize::new("DMC", 8.0, 6),
+        SkeinSize::new("Anchor", 8.0, 6),
+        SkeinSize::new("Madeira", 10.0, 6),
+      ],
+      default_skein_size: SkeinSize::new("", 8.0, 6),
+    }
+  }
+}
+
+impl ThreadUsageOptions {
+  /// Returns the skein size of the brand.
+  pub fn skein_size(&self, brand: &str) -> &SkeinSize {
+    self
+      .skein_sizes
+      .iter()
+      .find(|size| size.brand.eq_ignore_ascii_case(brand))
+      .unwrap_or(&self.default_skein_size)
+  }
+}
+
+/// The thread lengths of each stitch kind in meters of a single strand.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StitchLengths {
+  pub full: f32,
+  pub petite: f32,
+  pub half: f32,
+  pub quarter: f32,
+  pub back: f32,
+  pub straight: f32,
+  pub french_knot: f32,
+  pub special: f32,
+}
+
+impl StitchLengths {
+  pub fn total(&self) -> f32 {
+    self.full + self.petite + self.half + self.quarter + self.back + self.straight + self.french_knot + self.special
+  }
+}
+
+/// The amount of a single thread.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThreadAmount {
+  pub brand: String,
+  pub number: String,
+  /// The length in meters of a single strand, including the waste.
+  pub length: f32,
+  /// The number of skeins to buy.
+  pub skeins: u32,
+}
+
+/// The thread usage of a palette item.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThreadUsage {
+  pub palindex: usize,
+  /// The lengths of each stitch kind without the waste.
+  pub lengths: StitchLengths,
+  /// The total length in meters of a single strand, including the waste.
+  pub length: f32,
+  /// The threads to buy. It is the palette item itself or the components of a blend.
+  pub threads: Vec<ThreadAmount>,
+}
+
+/// Estimates the thread usage of each palette item that is not a bead.
+pub fn estimate_thread_usage(pattern: &Pattern, options: &ThreadUsageOptions) -> Vec<ThreadUsage> {
+  let cell_size = cell_size(&pattern.fabric);
+  let mut paths = vec![StitchLengths::default(); pattern.palette.len()];
+
+  let full_path = 2.0 * cell_size.0.hypot(cell_size.1) + cell_size.0 + cell_size.1;
+  for stitch in &pattern.fullstitches {
+    if let Some(paths) = paths.get_mut(usize::from(stitch.palindex)) {
+      match stitch.kind {
+        FullStitchKind::Full => paths.full += full_path,
+        FullStitchKind::Petite => paths.petite += full_path / 2.0,
+      }
+    }
+  }
+  for stitch in &pattern.partstitches {
+    if let Some(paths) = paths.get_mut(usize::from(stitch.palindex)) {
+      match stitch.kind {
+        PartStitchKind::Half => paths.half += full_path / 2.0,
+        PartStitchKind::Quarter => paths.quarter += full_path / 4.0,
+      }
+    }
+  }
+  for stitch in &pattern.linestitches {
+    if let Some(paths) = paths.get_mut(usize::from(stitch.palindex)) {
+      let length = 2.0 * line_length(stitch, cell_size);
+      match stitch.kind {
+        LineStitchKind::Back => paths.back += length,
+        LineStitchKind::Straight => paths.straight += length,
+      }
+    }
+  }
+  for stitch in &pattern.nodestitches {
+    if stitch.kind == NodeStitchKind::FrenchKnot
+      && let Some(paths) = paths.get_mut(usize::from(stitch.palindex))
+    {
+      paths.french_knot += FRENCH_KNOT_LENGTH;
+    }
+  }
+  for stitch in &pattern.specialstitches {
+    let Some(model) = pattern.special_stitch_models.get(usize::from(stitch.modindex)) else {
+      continue;
+    };
+    if let Some(paths) = paths.get_mut(usize::from(stitch.palindex)) {
+      paths.special += special_stitch_length(model, cell_size);
+    }
+  }
+
+  pattern
+    .palette
+    .iter()
+    .zip(paths)
+    .enumerate()
+    .filter(|(_, (palitem, _))| palitem.bead.is_none())
+    .map(|(palindex, (palitem, paths))| {
+      let blends = palitem.blends.as_deref().filter(|blends| !blends.is_empty());
+      let strands = match blends {
+        Some(blends) => {
+          let strands = blends.iter().map(|blend| blend.strands.max(1)).sum();
+          StitchStrands {
+            full: strands,
+            petite: strands,
+            half: strands,
+            quarter: strands,
+            back: strands,
+            straight: strands,
+            french_knot: strands,
+            special: strands,
+          }
+        }
+        None => pattern.strands(palindex),
+      };
+      let waste = 1.0 + options.waste_factor.max(0.0);
+      // Convert the paths from millimeters to meters of a single strand.
+      let meters = |path: f32, strands: u8| path * f32::from(strands) / 1000.0;
+      let lengths = StitchLengths {
+        full: meters(paths.full, strands.full),
+        petite: meters(paths.petite, strands.petite),
+        half: meters(paths.half, strands.half),
+        quarter: meters(paths.quarter, strands.quarter),
+        back: meters(paths.back, strands.back),
+        straight: meters(paths.straight, strands.straight),
+        french_knot: meters(paths.french_knot, strands.french_knot),
+        special: meters(paths.special, strands.special),
+      };
+      let length = lengths.total() * waste;
+
+      let threads = match blends {
+        Some(blends) => blends
+          .iter()
+          .map(|blend| {
+            let length = meters(paths.total(), blend.strands.max(1)) * waste;
+            thread_amount(&blend.brand, &blend.number, length, options)
+          })
+          .collect(),
+        None => vec![thread_amount(&palitem.brand, &palitem.number, length, options)],
+      };
+
+      ThreadUsage {
+        palindex,
+        lengths,
+        length,
+        threads,
+      }
+    })
+    .collect()
+}
+
+/// Sums up the amounts of the same threads, e.g. a thread that is used on its own and in a blend.
+/// The threads are returned in the order of their first use.
+pub fn total_thread_amounts(usage: &[ThreadUsage], options: &ThreadUsageOptions) -> Vec<ThreadAmount> {
+  let mut indices: HashMap<(String, String), usize> = HashMap::new();
+  let mut totals: Vec<ThreadAmount> = Vec::new();
+  for thread in usage.iter().flat_map(|usage| &usage.threads) {
+    let key = (thread.brand.clone(), thread.number.clone());
+    match indices.get(&key) {
+      Some(&index) => totals[index].length += thread.length,
+      None => {
+        indices.insert(key, totals.len());
+        totals.push(thread.clone());
+      }
+    }
+  }
+  for total in totals.iter_mut() {
+    total.skeins = skeins(total.length, options.skein_size(&total.brand));
+  }
+  totals
+}
+
+fn thread_amount(brand: &str, number: &str, length: f32, options: &ThreadUsageOptions) -> ThreadAmount {
+  ThreadAmount {
+    brand: brand.to_string(),
+    number: number.to_string(),
+    length,
+    skeins: skeins(length, options.skein_size(brand)),
+  }
+}
+
+fn skeins(length: f32, skein_size: &SkeinSize) -> u32 {
+  if length <= 0.0 {
+    return 0;
+  }
+  (length / skein_size.strand_length()).ceil() as u32
+}
+
+/// Returns the width and the height of a cell in millimeters.
+fn cell_size(fabric: &Fabric) -> (f32, f32) {
+  let (x, y) = fabric.stitches_per_inch;
+  (
+    MILLIMETERS_PER_INCH / f32::from(x.max(1)),
+    MILLIMETERS_PER_INCH / f32::from(y.max(1)),
+  )
+}
+
+/// Returns the length of the line on the front of the fabric in millimeters.
+fn line_length(stitch: &LineStitch, cell_size: (f32, f32)) -> f32 {
+  let width = (stitch.x.1.to_f32() - stitch.x.0.to_f32()) * cell_size.0;
+  let height = (stitch.y.1.to_f32() - stitch.y.0.to_f32()) * cell_size.1;
+  width.hypot(height)
+}
+
+/// Returns the path of a single strand of the special stitch in millimeters.
+/// The rotation and the flip of the stitch don't change the length.
+fn special_stitch_length(model: &SpecialStitchModel, cell_size: (f32, f32)) -> f32 {
+  let lines: f32 = model
+    .linestitches
+    .iter()
+    .map(|line| 2.0 * line_length(line, cell_size))
+    .sum();
+  let curves: f32 = model
+    .curvedstitches
+    .iter()
+    .map(|curve| {
+      let points = curve
+        .points
+        .iter()
+        .map(|(x, y)| (x.to_f32() * cell_size.0, y.to_f32() * cell_size.1));
+      let length: f32 = points
+        .clone()
+        .zip(points.skip(1))
+        .map(|(from, to)| (to.0 - from.0).hypot(to.1 - from.1))
+        .sum();
+      2.0 * length
+    })
+    .sum();
+  let knots = model
+    .nodestitches
+    .iter()
+    .filter(|node| node.kind == NodeStitchKind::FrenchKnot)
+    .count() as f32
+    * FRENCH_KNOT_LENGTH;
+  lines + curves + knots
+}
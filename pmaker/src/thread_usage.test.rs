@@ -0,0 +1,304 @@
+use super::*;
+use crate::builder::PatternBuilder;
+use crate::schemas::color::Rgb;
+use crate::schemas::coords::{Coord, CurveCoord};
+
+const FULL_PATH: f32 = 2.54 * (2.0 * std::f32::consts::SQRT_2 + 2.0);
+
+fn assert_close(actual: f32, expected: f32) {
+  assert!((actual - expected).abs() < 1e-4, "{actual} != {expected}");
+}
+
+/// Returns a builder of a pattern on a 10-count fabric, so a cell is 2.54 mm wide.
+fn builder() -> PatternBuilder {
+  let mut builder = PatternBuilder::new(20, 20);
+  builder.fabric(Fabric {
+    width: 20,
+    height: 20,
+    stitches_per_inch: (10, 10),
+    kind: String::from("Aida"),
+    name: String::from("White"),
+    color: Rgb::WHITE,
+  });
+  builder
+}
+
+/// Adds the full stitches row by row, starting from the given cell index.
+fn add_fullstitches(builder: &mut PatternBuilder, palindex: u8, start: i32, count: i32) {
+  for index in start..start + count {
+    builder.add_fullstitch(FullStitch {
+      x: Coord::from_cells(index % 20),
+      y: Coord::from_cells(index / 20),
+      palindex,
+      kind: FullStitchKind::Full,
+    });
+  }
+}
+
+#[test]
+fn estimates_lengths_per_stitch_kind() {
+  let mut builder = builder();
+  let palindex = builder
+    .add_palette_item(PaletteItem {
+      strands: Some(StitchStrands {
+        half: Some(3),
+        ..Default::default()
+      }),
+      ..PaletteItem::new("DMC", "310", Rgb::BLACK)
+    })
+    .unwrap();
+  add_fullstitches(&mut builder, palindex, 0, 100);
+  builder
+    .add_partstitch(PartStitch {
+      x: Coord::ZERO,
+      y: Coord::from_cells(10),
+      palindex,
+      direction: PartStitchDirection::Forward,
+      kind: PartStitchKind::Half,
+    })
+    .add_linestitch(LineStitch {
+      x: (Coord::ZERO, Coord::from_cells(10)),
+      y: (Coord::ZERO, Coord::ZERO),
+      palindex,
+      kind: LineStitchKind::Back,
+    })
+    .add_nodestitch(NodeStitch {
+      x: Coord::ZERO,
+      y: Coord::ZERO,
+      rotated: false,
+      palindex,
+      kind: NodeStitchKind::FrenchKnot,
+    });
+  let pattern = builder.build().unwrap();
+
+  let options = ThreadUsageOptions {
+    waste_factor: 0.0,
+    ..Default::default()
+  };
+  let usage = estimate_thread_usage(&pattern, &options);
+  assert_eq!(usage.len(), 1);
+  let lengths = &usage[0].lengths;
+  // 100 stitches of 2 strands.
+  assert_close(lengths.full, 100.0 * FULL_PATH * 2.0 / 1000.0);
+  // The half stitch has its own 3 strands.
+  assert_close(lengths.half, FULL_PATH / 2.0 * 3.0 / 1000.0);
+  // 1 inch on the front and the back of a single strand.
+  assert_close(lengths.back, 2.0 * 25.4 / 1000.0);
+  assert_close(lengths.french_knot, 2.0 * 25.0 / 1000.0);
+  assert_eq!(lengths.petite, 0.0);
+  assert_close(usage[0].length, lengths.total());
+
+  assert_eq!(usage[0].threads.len(), 1);
+  assert_eq!(usage[0].threads[0].number, "310");
+  assert_eq!(usage[0].threads[0].skeins, 1);
+}
+
+#[test]
+fn applies_waste_factor_and_skein_sizes() {
+  let mut builder = builder();
+  let dmc = builder
+    .add_palette_item(PaletteItem::new("DMC", "310", Rgb::BLACK))
+    .unwrap();
+  let unknown = builder
+    .add_palette_item(PaletteItem::new("Unknown", "1", Rgb::BLACK))
+    .unwrap();
+  add_fullstitches(&mut builder, dmc, 0, 100);
+  add_fullstitches(&mut builder, unknown, 100, 100);
+  let pattern = builder.build().unwrap();
+
+  let options = ThreadUsageOptions {
+    waste_factor: 0.5,
+    skein_sizes: vec![SkeinSize::new("dmc", 0.2, 6)],
+    default_skein_size: SkeinSize::new("", 1.0, 1),
+  };
+  let usage = estimate_thread_usage(&pattern, &options);
+  let length = 100.0 * FULL_PATH * 2.0 / 1000.0 * 1.5;
+  assert_close(usage[0].length, length);
+  // The skeins of 1.2 m and 1 m of a single strand.
+  assert_eq!(usage[0].threads[0].skeins, (length / 1.2).ceil() as u32);
+  assert_eq!(usage[1].threads[0].skeins, length.ceil() as u32);
+}
+
+#[test]
+fn splits_blends_into_components() {
+  let mut builder = builder();
+  let black = builder
+    .add_palette_item(PaletteItem::new("DMC", "310", Rgb::BLACK))
+    .unwrap();
+  let blend = builder
+    .add_palette_item(PaletteItem {
+      blends: Some(vec![
+        Blend {
+          brand: String::from("DMC"),
+          number: String::from("310"),
+          strands: 1,
+        },
+        Blend {
+          brand: String::from("Anchor"),
+          number: String::from("403"),
+          strands: 3,
+        },
+      ]),
+      ..PaletteItem::new("Blends", "310+403", Rgb::BLACK)
+    })
+    .unwrap();
+  let bead = builder
+    .add_palette_item(PaletteItem {
+      bead: Some(Bead {
+        length: 2.5,
+        diameter: 1.5,
+      }),
+      ..PaletteItem::new("Mill Hill Glass Seed Bead", "00557", Rgb::BLACK)
+    })
+    .unwrap();
+  add_fullstitches(&mut builder, black, 0, 10);
+  add_fullstitches(&mut builder, blend, 10, 40);
+  builder.add_nodestitch(NodeStitch {
+    x: Coord::ZERO,
+    y: Coord::ZERO,
+    rotated: false,
+    palindex: bead,
+    kind: NodeStitchKind::Bead,
+  });
+  let pattern = builder.build().unwrap();
+
+  let options = ThreadUsageOptions {
+    waste_factor: 0.0,
+    ..Default::default()
+  };
+  let usage = estimate_thread_usage(&pattern, &options);
+  // The bead isn't a thread.
+  assert_eq!(usage.len(), 2);
+
+  let stitch = FULL_PATH * 2.0 / 1000.0;
+  let threads = &usage[1].threads;
+  assert_eq!(threads.len(), 2);
+  assert_eq!((threads[0].brand.as_str(), threads[0].number.as_str()), ("DMC", "310"));
+  // Each component is measured with its own strands instead of the strands of the palette item.
+  let stitch_strand = FULL_PATH / 1000.0;
+  assert_close(threads[0].length, 40.0 * stitch_strand);
+  assert_eq!(
+    (threads[1].brand.as_str(), threads[1].number.as_str()),
+    ("Anchor", "403")
+  );
+  assert_close(threads[1].length, 40.0 * stitch_strand * 3.0);
+  assert_close(usage[1].length, 40.0 * stitch_strand * 4.0);
+
+  let totals = total_thread_amounts(&usage, &options);
+  assert_eq!(totals.len(), 2);
+  assert_eq!(totals[0].number, "310");
+  assert_close(totals[0].length, 10.0 * stitch + 40.0 * stitch_strand);
+  assert_eq!(totals[0].skeins, 1);
+  assert_eq!(totals[1].number, "403");
+}
+
+#[test]
+fn measures_unequal_blends() {
+  let mut builder = builder();
+  let blend = builder
+    .add_palette_item(PaletteItem {
+      blends: Some(vec![
+        Blend {
+          brand: String::from("DMC"),
+          number: String::from("310"),
+          strands: 2,
+        },
+        Blend {
+          brand: String::from("DMC"),
+          number: String::from("B5200"),
+          strands: 1,
+        },
+      ]),
+      strands: Some(StitchStrands {
+        full: Some(2),
+        ..Default::default()
+      }),
+      ..PaletteItem::new("Blends", "310+B5200", Rgb::BLACK)
+    })
+    .unwrap();
+  add_fullstitches(&mut builder, blend, 0, 10);
+  let pattern = builder.build().unwrap();
+
+  let options = ThreadUsageOptions {
+    waste_factor: 0.0,
+    ..Default::default()
+  };
+  let usage = estimate_thread_usage(&pattern, &options);
+  let stitch_strand = FULL_PATH / 1000.0;
+  assert_close(usage[0].threads[0].length, 10.0 * stitch_strand * 2.0);
+  assert_close(usage[0].threads[1].length, 10.0 * stitch_strand);
+  assert_close(usage[0].lengths.full, 10.0 * stitch_strand * 3.0);
+  assert_close(usage[0].length, 10.0 * stitch_strand * 3.0);
+}
+
+#[test]
+fn measures_stitches_on_non_square_cells() {
+  let mut builder = builder();
+  builder.fabric(Fabric {
+    width: 20,
+    height: 20,
+    stitches_per_inch: (10, 20),
+    kind: String::from("Aida"),
+    name: String::from("White"),
+    color: Rgb::WHITE,
+  });
+  let palindex = builder
+    .add_palette_item(PaletteItem::new("DMC", "310", Rgb::BLACK))
+    .unwrap();
+  add_fullstitches(&mut builder, palindex, 0, 1);
+  let pattern = builder.build().unwrap();
+
+  let usage = estimate_thread_usage(
+    &pattern,
+    &ThreadUsageOptions {
+      waste_factor: 0.0,
+      ..Default::default()
+    },
+  );
+  // The back of the fabric takes the width and the height of the cell.
+  let path = 2.0 * 2.54f32.hypot(1.27) + 2.54 + 1.27;
+  assert_close(usage[0].lengths.full, path * 2.0 / 1000.0);
+}
+
+#[test]
+fn measures_special_stitches() {
+  let mut builder = builder();
+  let palindex = builder
+    .add_palette_item(PaletteItem::new("DMC", "310", Rgb::BLACK))
+    .unwrap();
+  let modindex = builder
+    .add_special_stitch_model(SpecialStitchModel {
+      unique_name: String::from("Arc"),
+      name: String::from("Arc"),
+      width: Coord::from_cells(2),
+      height: Coord::from_cells(1),
+      linestitches: vec![LineStitch {
+        x: (Coord::ZERO, Coord::ZERO),
+        y: (Coord::ZERO, Coord::from_cells(1)),
+        palindex: 0,
+        kind: LineStitchKind::Straight,
+      }],
+      nodestitches: Vec::new(),
+      curvedstitches: vec![CurvedStitch {
+        points: vec![
+          (CurveCoord::from_thirtieths(0), CurveCoord::from_thirtieths(0)),
+          (CurveCoord::from_thirtieths(30), CurveCoord::from_thirtieths(0)),
+          (CurveCoord::from_thirtieths(30), CurveCoord::from_thirtieths(30)),
+        ],
+      }],
+    })
+    .unwrap();
+  builder.add_specialstitch(SpecialStitch {
+    x: Coord::ZERO,
+    y: Coord::ZERO,
+    rotation: 90,
+    flip: (true, false),
+    palindex,
+    modindex,
+  });
+  let pattern = builder.build().unwrap();
+
+  let usage = estimate_thread_usage(&pattern, &ThreadUsageOptions::default());
+  // A cell of the line and two cells of the curve on both sides of the fabric in 2 strands.
+  assert_close(usage[0].lengths.special, 3.0 * 2.54 * 2.0 * 2.0 / 1000.0);
+}
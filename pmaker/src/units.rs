@@ -0,0 +1,4 @@
+//! The conversion factors between the units of length used across the crate.
+
+pub(crate) const CENTIMETERS_PER_INCH: f32 = 2.54;
+pub(crate) const MILLIMETERS_PER_INCH: f32 = 25.4;
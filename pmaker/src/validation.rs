@@ -0,0 +1,240 @@
+//! Consistency checks of patterns.
+//!
+//! The parsers and the hand-written code may produce patterns that reference missing palette items or models,
+//! or contain stitches outside the fabric. The validation reports all such problems at once.
+
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::fmt;
+use std::hash::Hash;
+
+use crate::schemas::coords::Coord;
+use crate::schemas::xsd::*;
+
+// The tests use the test patterns.
+#[cfg(all(test, feature = "xsd"))]
+#[path = "validation.test.rs"]
+mod tests;
+
+/// Pattern Maker supports up to 240 colors in the palette.
+pub const MAX_PALETTE_SIZE: usize = 240;
+
+/// A reference to a stitch by its index in the corresponding collection of the pattern.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StitchRef {
+  Full(usize),
+  Part(usize),
+  Line(usize),
+  Node(usize),
+  Special(usize),
+}
+
+impl fmt::Display for StitchRef {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      StitchRef::Full(index) => write!(f, "full stitch #{index}"),
+      StitchRef::Part(index) => write!(f, "part stitch #{index}"),
+      StitchRef::Line(index) => write!(f, "line stitch #{index}"),
+      StitchRef::Node(index) => write!(f, "node stitch #{index}"),
+      StitchRef::Special(index) => write!(f, "special stitch #{index}"),
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationError {
+  /// The stitch references a palette item that doesn't exist.
+  PalindexOutOfRange {
+    stitch: StitchRef,
+    palindex: u8,
+  },
+  /// The stitch lies outside the fabric.
+  OutOfFabric {
+    stitch: StitchRef,
+  },
+  /// The special stitch references a model that doesn't exist.
+  MissingSpecialStitchModel {
+    stitch: StitchRef,
+    modindex: u8,
+  },
+  /// The stitch has the same position and kind as one of the previous stitches, whatever their colors are.
+  DuplicateStitch {
+    stitch: StitchRef,
+    original: StitchRef,
+  },
+  FormatsLengthMismatch {
+    formats: usize,
+    palette: usize,
+  },
+  SymbolsLengthMismatch {
+    symbols: usize,
+    palette: usize,
+  },
+  TooManyColors {
+    palette: usize,
+  },
+}
+
+impl fmt::Display for ValidationError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      ValidationError::PalindexOutOfRange { stitch, palindex } => {
+        write!(f, "The {stitch} references the missing palette item #{palindex}")
+      }
+      ValidationError::OutOfFabric { stitch } => write!(f, "The {stitch} lies outside the fabric"),
+      ValidationError::MissingSpecialStitchModel { stitch, modindex } => {
+        write!(
+          f,
+          "The {stitch} references the missing special stitch model #{modindex}"
+        )
+      }
+      ValidationError::DuplicateStitch { stitch, original } => {
+        write!(f, "The {stitch} is a duplicate of the {original}")
+      }
+      ValidationError::FormatsLengthMismatch { formats, palette } => {
+        write!(f, "There are {formats} formats for {palette} palette items")
+      }
+      ValidationError::SymbolsLengthMismatch { symbols, palette } => {
+        write!(f, "There are {symbols} symbols for {palette} palette items")
+      }
+      ValidationError::TooManyColors { palette } => {
+        write!(
+          f,
+          "The palette has {palette} colors, but at most {MAX_PALETTE_SIZE} are supported"
+        )
+      }
+    }
+  }
+}
+
+/// All the problems found in a pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationErrors(pub Vec<ValidationError>);
+
+impl fmt::Display for ValidationErrors {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    writeln!(f, "The pattern has {} problem(s):", self.0.len())?;
+    for error in &self.0 {
+      writeln!(f, "- {error}")?;
+    }
+    Ok(())
+  }
+}
+
+impl std::error::Error for ValidationErrors {}
+
+impl Pattern {
+  /// Checks that the pattern is consistent and reports every problem it finds.
+  pub fn validate(&self) -> Result<(), ValidationErrors> {
+    let mut errors = Vec::new();
+
+    let palette = self.palette.len();
+    if palette > MAX_PALETTE_SIZE {
+      errors.push(ValidationError::TooManyColors { palette });
+    }
+    if self.formats.len() != palette {
+      errors.push(ValidationError::FormatsLengthMismatch {
+        formats: self.formats.len(),
+        palette,
+      });
+    }
+    if self.symbols.len() != palette {
+      errors.push(ValidationError::SymbolsLengthMismatch {
+        symbols: self.symbols.len(),
+        palette,
+      });
+    }
+
+    // Full and part stitches occupy cells, so their coordinates must be less than the fabric size.
+    // Line, node and special stitches are placed on the joints, which include the far edges of the fabric.
+    let width = Coord::from_cells(self.fabric.width.into());
+    let height = Coord::from_cells(self.fabric.height.into());
+    let in_cells = |x: Coord, y: Coord| x >= Coord::ZERO && y >= Coord::ZERO && x < width && y < height;
+    let on_joints = |x: Coord, y: Coord| x >= Coord::ZERO && y >= Coord::ZERO && x <= width && y <= height;
+
+    let mut check_stitch = |stitch: StitchRef, palindex: u8, inside: bool| {
+      if palindex as usize >= palette {
+        errors.push(ValidationError::PalindexOutOfRange { stitch, palindex });
+      }
+      if !inside {
+        errors.push(ValidationError::OutOfFabric { stitch });
+      }
+    };
+
+    for (index, stitch) in self.fullstitches.iter().enumerate() {
+      check_stitch(StitchRef::Full(index), stitch.palindex, in_cells(stitch.x, stitch.y));
+    }
+    for (index, stitch) in self.partstitches.iter().enumerate() {
+      check_stitch(StitchRef::Part(index), stitch.palindex, in_cells(stitch.x, stitch.y));
+    }
+    for (index, stitch) in self.linestitches.iter().enumerate() {
+      let inside = on_joints(stitch.x.0, stitch.y.0) && on_joints(stitch.x.1, stitch.y.1);
+      check_stitch(StitchRef::Line(index), stitch.palindex, inside);
+    }
+    for (index, stitch) in self.nodestitches.iter().enumerate() {
+      check_stitch(StitchRef::Node(index), stitch.palindex, on_joints(stitch.x, stitch.y));
+    }
+    for (index, stitch) in self.specialstitches.iter().enumerate() {
+      check_stitch(
+        StitchRef::Special(index),
+        stitch.palindex,
+        on_joints(stitch.x, stitch.y),
+      );
+    }
+
+    for (index, stitch) in self.specialstitches.iter().enumerate() {
+      if stitch.modindex as usize >= self.special_stitch_models.len() {
+        errors.push(ValidationError::MissingSpecialStitchModel {
+          stitch: StitchRef::Special(index),
+          modindex: stitch.modindex,
+        });
+      }
+    }
+
+    // The stitches of different colors in the same place conflict, so the palette index isn't a part of the keys.
+    find_duplicates(&mut errors, &self.fullstitches, StitchRef::Full, |stitch| {
+      (stitch.x, stitch.y, stitch.kind.clone())
+    });
+    find_duplicates(&mut errors, &self.partstitches, StitchRef::Part, |stitch| {
+      (stitch.x, stitch.y, stitch.direction.clone(), stitch.kind.clone())
+    });
+    find_duplicates(&mut errors, &self.linestitches, StitchRef::Line, |stitch| {
+      // A line stitch is the same regardless of the direction it is drawn in.
+      let start = (stitch.x.0, stitch.y.0);
+      let end = (stitch.x.1, stitch.y.1);
+      (start.min(end), start.max(end), stitch.kind.clone())
+    });
+    find_duplicates(&mut errors, &self.nodestitches, StitchRef::Node, |stitch| {
+      (stitch.x, stitch.y, stitch.rotated, stitch.kind.clone())
+    });
+    find_duplicates(&mut errors, &self.specialstitches, StitchRef::Special, |stitch| {
+      (stitch.x, stitch.y, stitch.rotation, stitch.flip, stitch.modindex)
+    });
+
+    if errors.is_empty() {
+      Ok(())
+    } else {
+      Err(ValidationErrors(errors))
+    }
+  }
+}
+
+fn find_duplicates<T, K: Eq + Hash>(
+  errors: &mut Vec<ValidationError>,
+  stitches: &[T],
+  stitch_ref: fn(usize) -> StitchRef,
+  key: impl Fn(&T) -> K,
+) {
+  let mut seen = HashMap::with_capacity(stitches.len());
+  for (index, stitch) in stitches.iter().enumerate() {
+    match seen.entry(key(stitch)) {
+      Entry::Occupied(entry) => errors.push(ValidationError::DuplicateStitch {
+        stitch: stitch_ref(index),
+        original: stitch_ref(*entry.get()),
+      }),
+      Entry::Vacant(entry) => {
+        entry.insert(index);
+      }
+    }
+  }
+}
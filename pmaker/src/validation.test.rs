@@ -0,0 +1,138 @@
+use super::*;
+use crate::parse_xsd_pattern;
+
+fn load_pattern() -> Pattern {
+  let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata/patterns/specials.xsd");
+  parse_xsd_pattern(path).unwrap()
+}
+
+#[test]
+fn accepts_test_patterns() {
+  let patterns_path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata/patterns");
+  for entry in patterns_path.read_dir().unwrap() {
+    let path = entry.unwrap().path();
+    let pattern = parse_xsd_pattern(path.clone()).unwrap();
+    assert_eq!(pattern.validate(), Ok(()), "{path:?} is invalid");
+  }
+}
+
+#[test]
+fn reports_every_problem() {
+  let mut pattern = load_pattern();
+  let palette = pattern.palette.len();
+
+  let stitch = FullStitch {
+    x: Coord::ZERO,
+    y: Coord::ZERO,
+    palindex: 0,
+    kind: FullStitchKind::Full,
+  };
+  let linestitch = LineStitch {
+    x: (Coord::ZERO, Coord::from_cells(1)),
+    y: (Coord::ZERO, Coord::from_halves(1)),
+    palindex: 0,
+    kind: LineStitchKind::Back,
+  };
+  pattern.fullstitches = vec![stitch.clone()];
+  pattern.linestitches = vec![linestitch.clone()];
+
+  pattern.formats.pop();
+  pattern.symbols.push(pattern.symbols[0].clone());
+  pattern.fullstitches.push(FullStitch {
+    x: Coord::from_cells(1),
+    palindex: palette as u8,
+    ..stitch.clone()
+  });
+  pattern.fullstitches.push(FullStitch {
+    x: Coord::from_cells(pattern.fabric.width.into()),
+    ..stitch.clone()
+  });
+  pattern.fullstitches.push(stitch);
+  pattern.linestitches.push(LineStitch {
+    x: (linestitch.x.1, linestitch.x.0),
+    y: (linestitch.y.1, linestitch.y.0),
+    ..linestitch
+  });
+  pattern.specialstitches.push(SpecialStitch {
+    modindex: pattern.special_stitch_models.len() as u8,
+    ..pattern.specialstitches[0].clone()
+  });
+
+  let full = pattern.fullstitches.len();
+  let line = pattern.linestitches.len();
+  let special = pattern.specialstitches.len();
+  assert_eq!(
+    pattern.validate().unwrap_err().0,
+    [
+      ValidationError::FormatsLengthMismatch {
+        formats: palette - 1,
+        palette,
+      },
+      ValidationError::SymbolsLengthMismatch {
+        symbols: palette + 1,
+        palette,
+      },
+      ValidationError::PalindexOutOfRange {
+        stitch: StitchRef::Full(full - 3),
+        palindex: palette as u8,
+      },
+      ValidationError::OutOfFabric {
+        stitch: StitchRef::Full(full - 2),
+      },
+      ValidationError::MissingSpecialStitchModel {
+        stitch: StitchRef::Special(special - 1),
+        modindex: pattern.special_stitch_models.len() as u8,
+      },
+      ValidationError::DuplicateStitch {
+        stitch: StitchRef::Full(full - 1),
+        original: StitchRef::Full(0),
+      },
+      ValidationError::DuplicateStitch {
+        stitch: StitchRef::Line(line - 1),
+        original: StitchRef::Line(0),
+      },
+    ]
+  );
+}
+
+#[test]
+fn reports_stitches_of_different_colors_in_the_same_place() {
+  let mut pattern = load_pattern();
+  let stitch = FullStitch {
+    x: Coord::ZERO,
+    y: Coord::ZERO,
+    palindex: 0,
+    kind: FullStitchKind::Full,
+  };
+  pattern.fullstitches = vec![
+    stitch.clone(),
+    FullStitch {
+      kind: FullStitchKind::Petite,
+      ..stitch.clone()
+    },
+    FullStitch { palindex: 1, ..stitch },
+  ];
+  assert_eq!(
+    pattern.validate().unwrap_err().0,
+    [ValidationError::DuplicateStitch {
+      stitch: StitchRef::Full(2),
+      original: StitchRef::Full(0),
+    }]
+  );
+}
+
+#[test]
+fn reports_too_many_colors() {
+  let mut pattern = load_pattern();
+  while pattern.palette.len() <= MAX_PALETTE_SIZE {
+    pattern.palette.push(pattern.palette[0].clone());
+    pattern.formats.push(pattern.formats[0].clone());
+    pattern.symbols.push(pattern.symbols[0].clone());
+  }
+  assert_eq!(
+    pattern.validate().unwrap_err().0,
+    [ValidationError::TooManyColors {
+      palette: MAX_PALETTE_SIZE + 1,
+    }]
+  );
+}
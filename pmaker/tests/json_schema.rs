@@ -0,0 +1,21 @@
+#![cfg(feature = "json-schema")]
+
+//! Run with `UPDATE_JSON_SCHEMA=1` to regenerate the checked-in schema.
+
+#[test]
+fn json_schema_is_up_to_date() {
+  let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("schemas/pattern.schema.json");
+  let schema = schemars::schema_for!(pmaker::Pattern);
+  let generated = serde_json::to_string_pretty(&schema).unwrap() + "\n";
+
+  if std::env::var_os("UPDATE_JSON_SCHEMA").is_some() {
+    std::fs::write(&path, generated).unwrap();
+    return;
+  }
+
+  let checked_in = std::fs::read_to_string(&path).unwrap_or_default();
+  assert!(
+    checked_in == generated,
+    "{path:?} is out of date, run the tests with `UPDATE_JSON_SCHEMA=1` to regenerate it"
+  );
+}
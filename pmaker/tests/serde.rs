@@ -0,0 +1,32 @@
+#![cfg(all(feature = "serde", feature = "xsd"))]
+
+#[test]
+fn round_trips_test_patterns_through_json() {
+  let patterns_path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata/patterns");
+  for entry in patterns_path.read_dir().unwrap() {
+    let path = entry.unwrap().path();
+    let pattern = pmaker::parse_xsd_pattern(path.clone()).unwrap();
+    let json = serde_json::to_string(&pattern).unwrap();
+    let deserialized: pmaker::Pattern = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized, pattern, "{path:?} changed after the round trip");
+  }
+}
+
+#[test]
+fn uses_stable_names() {
+  let stitch = pmaker::PartStitch {
+    x: pmaker::Coord::from_halves(3),
+    y: pmaker::Coord::from_cells(2),
+    palindex: 1,
+    direction: pmaker::PartStitchDirection::Forward,
+    kind: pmaker::PartStitchKind::Quarter,
+  };
+  let json = serde_json::json!({ "x": 3, "y": 4, "palindex": 1, "direction": "forward", "kind": "quarter" });
+  assert_eq!(serde_json::to_value(&stitch).unwrap(), json);
+  assert_eq!(serde_json::from_value::<pmaker::PartStitch>(json).unwrap(), stitch);
+
+  let color = pmaker::Rgb::new(0x2C, 0x32, 0x25);
+  assert_eq!(serde_json::to_value(color).unwrap(), serde_json::json!("2C3225"));
+  assert_eq!(serde_json::from_str::<pmaker::Rgb>("\"2c3225\"").unwrap(), color);
+  assert!(serde_json::from_str::<pmaker::Rgb>("\"black\"").is_err());
+}
@@ -1,3 +1,5 @@
+#![cfg(feature = "xsd")]
+
 #[test]
 fn parses_all_test_patterns() {
   let patterns_path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata/patterns");
@@ -0,0 +1,57 @@
+//! The reader of the CHART format for the common pattern interface.
+
+use xsp_core::{PatternFormat, PatternReader};
+
+use crate::parsers::chart::*;
+use crate::schemas::*;
+
+#[cfg(test)]
+#[path = "format.test.rs"]
+mod tests;
+
+/// The WinStitch and MacStitch format.
+///
+/// The support is experimental, the parser hasn't been verified against files saved by these applications.
+pub struct ChartFormat;
+
+impl PatternFormat for ChartFormat {
+  fn name(&self) -> &'static str {
+    "WinStitch"
+  }
+
+  fn extensions(&self) -> &'static [&'static str] {
+    &["chart"]
+  }
+}
+
+impl PatternReader for ChartFormat {
+  /// The format is chosen only by the file extension until the layout is verified.
+  fn sniff(&self, _buf: &[u8]) -> bool {
+    false
+  }
+
+  fn read(&self, buf: &[u8]) -> anyhow::Result<xsp_core::Pattern> {
+    Ok(parse_chart_pattern_from_bytes(buf)?.into())
+  }
+}
+
+impl From<Pattern> for xsp_core::Pattern {
+  fn from(pattern: Pattern) -> Self {
+    xsp_core::Pattern {
+      info: pattern.info,
+      fabric: pattern.fabric,
+      palette: pattern.palette,
+      fullstitches: pattern.fullstitches,
+      partstitches: pattern.partstitches,
+      linestitches: pattern.linestitches,
+      nodestitches: Vec::new(),
+      specialstitches: Vec::new(),
+      special_stitch_models: Vec::new(),
+      display: xsp_core::DisplayHints {
+        symbols: pattern.symbols,
+        ..Default::default()
+      },
+      extensions: Default::default(),
+    }
+  }
+}
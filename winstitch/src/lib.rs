@@ -0,0 +1,7 @@
+mod format;
+mod parsers;
+mod schemas;
+
+pub use format::*;
+pub use parsers::chart::*;
+pub use schemas::*;
@@ -0,0 +1,350 @@
+//! An experimental parser for the proprietary chart format of WinStitch and MacStitch by Ursa Software.
+//!
+//! The layout below is a best guess that hasn't been verified against files saved by either application yet:
+//! the test data is hand-made to follow it, so real files may fail to parse or be read incorrectly.
+//! For the same reason, the files aren't detected by their content, only by the extension.
+//!
+//! The applications are assumed to share the format, with only the signature differing.
+//! All numbers are big-endian and the strings are in UTF-8 encoding.
+
+use std::io::{self, Read};
+
+use anyhow::Result;
+use byteorder::{BigEndian, ReadBytesExt};
+
+use super::ReadChartExt as _;
+use crate::schemas::*;
+
+#[cfg(test)]
+#[path = "chart.test.rs"]
+mod tests;
+
+const VALID_SIGNATURES: [&str; 2] = ["WinStitch", "MacStitch"];
+
+pub fn parse_chart_pattern<P: AsRef<std::path::Path>>(file_path: P) -> Result<Pattern> {
+  let buf = std::fs::read(file_path.as_ref())?;
+  parse_chart_pattern_from_bytes(&buf)
+}
+
+pub fn parse_chart_pattern_from_bytes(buf: &[u8]) -> Result<Pattern> {
+  let mut cursor = std::io::Cursor::new(buf);
+
+  let signature = read_signature(&mut cursor)?;
+  if !VALID_SIGNATURES.contains(&signature.as_str()) {
+    anyhow::bail!("The signature of WinStitch or MacStitch is incorrect! Found: {signature:?}");
+  }
+  let version = cursor.read_u16::<BigEndian>()?;
+  log::debug!("{signature} chart version: {version}");
+
+  let pattern_info = read_pattern_info(&mut cursor)?;
+  let fabric = read_fabric(&mut cursor)?;
+  let (palette, symbols) = read_palette(&mut cursor)?;
+  let (fullstitches, partstitches) = read_stitches(&mut cursor, palette.len())?;
+  let linestitches = read_backstitches(&mut cursor, palette.len())?;
+
+  Ok(Pattern {
+    info: pattern_info,
+    fabric,
+    palette,
+    symbols,
+    fullstitches,
+    partstitches,
+    linestitches,
+  })
+}
+
+fn read_signature<R: Read>(reader: &mut R) -> io::Result<String> {
+  let length: usize = reader.read_u16::<BigEndian>()?.into();
+  // The signature is short, so a longer string is definitely not the one.
+  if length > VALID_SIGNATURES[0].len() {
+    return Ok(String::new());
+  }
+  let mut buf = vec![0; length];
+  reader.read_exact(&mut buf)?;
+  Ok(String::from_utf8_lossy(&buf).into_owned())
+}
+
+fn read_pattern_info<R: Read>(reader: &mut R) -> io::Result<PatternInfo> {
+  log::trace!("Reading pattern info");
+  Ok(PatternInfo {
+    title: reader.read_utf()?,
+    author: reader.read_utf()?,
+    company: String::new(),
+    copyright: reader.read_utf()?,
+    description: reader.read_utf()?,
+  })
+}
+
+/// Reads the chart size and the fabric properties.
+fn read_fabric<R: Read>(reader: &mut R) -> io::Result<Fabric> {
+  log::trace!("Reading fabric");
+  let width = reader.read_u16::<BigEndian>()?;
+  let height = reader.read_u16::<BigEndian>()?;
+  // The fabric count is the same in both directions.
+  let count = reader.read_u16::<BigEndian>()? as u8;
+  let kind = reader.read_utf()?;
+  let name = reader.read_utf()?;
+  let color = reader.read_color()?;
+  Ok(Fabric {
+    width,
+    height,
+    stitches_per_inch: (count, count),
+    kind,
+    name,
+    color,
+  })
+}
+
+/// Reads the color palette of the pattern and the symbols of its items.
+fn read_palette<R: Read>(reader: &mut R) -> io::Result<(Vec<PaletteItem>, Vec<Option<char>>)> {
+  log::trace!("Reading palette");
+
+  let palette_size: usize = reader.read_u16::<BigEndian>()?.into();
+  let mut palette = Vec::with_capacity(palette_size);
+  let mut symbols = Vec::with_capacity(palette_size);
+
+  for _ in 0..palette_size {
+    let (palitem, symbol) = read_palette_item(reader)?;
+    palette.push(palitem);
+    symbols.push(symbol);
+  }
+
+  Ok((palette, symbols))
+}
+
+fn read_palette_item<R: Read>(reader: &mut R) -> io::Result<(PaletteItem, Option<char>)> {
+  /// Reads the blend colors of the palette item.
+  fn read_blends<R: Read>(reader: &mut R) -> io::Result<Option<Vec<Blend>>> {
+    let blends_count: usize = reader.read_u8()?.into();
+    let mut blends = Vec::with_capacity(blends_count);
+    for _ in 0..blends_count {
+      blends.push(Blend {
+        brand: reader.read_utf()?,
+        number: reader.read_utf()?,
+        strands: reader.read_u8()?,
+      });
+    }
+    Ok(if blends.is_empty() { None } else { Some(blends) })
+  }
+
+  let brand = reader.read_utf()?;
+  let number = reader.read_utf()?;
+  let name = reader.read_utf()?;
+  let color = reader.read_color()?;
+  let strands = read_palette_item_strands(reader)?;
+  let blends = read_blends(reader)?;
+
+  // The symbol is a UTF-16 code unit, just like Java's `char`.
+  let symbol = match reader.read_u16::<BigEndian>()? {
+    0 => None,
+    code => char::from_u32(code.into()),
+  };
+
+  let palitem = PaletteItem {
+    brand,
+    number,
+    name,
+    color,
+    blends,
+    bead: None,
+    strands,
+  };
+  Ok((palitem, symbol))
+}
+
+/// Reads the numbers of strands for the cross and back stitches.
+/// Zero means the default number of strands.
+fn read_palette_item_strands<R: Read>(reader: &mut R) -> io::Result<Option<StitchStrands<Option<u8>>>> {
+  let read_strands = |reader: &mut R| -> io::Result<Option<u8>> {
+    let strands = reader.read_u8()?;
+    Ok(if strands == 0 { None } else { Some(strands) })
+  };
+
+  let cross = read_strands(reader)?;
+  let back = read_strands(reader)?;
+
+  if cross.is_none() && back.is_none() {
+    return Ok(None);
+  }
+
+  Ok(Some(StitchStrands {
+    full: cross,
+    petite: cross,
+    half: cross,
+    quarter: cross,
+    back,
+    straight: back,
+    french_knot: None,
+    special: None,
+  }))
+}
+
+/// Reads the full and fractional stitches.
+/// A three-quarter stitch is split into a half stitch and a quarter stitch in its corner.
+fn read_stitches<R: Read>(reader: &mut R, palette_size: usize) -> io::Result<(Vec<FullStitch>, Vec<PartStitch>)> {
+  log::trace!("Reading stitches");
+
+  let mut fullstitches = Vec::new();
+  let mut partstitches = Vec::new();
+
+  let stitches_count = reader.read_u32::<BigEndian>()?;
+  for _ in 0..stitches_count {
+    let x = Coord::from_cells(reader.read_u16::<BigEndian>()?.into());
+    let y = Coord::from_cells(reader.read_u16::<BigEndian>()?.into());
+    let kind = ChartStitchKind::try_from(reader.read_u8()?)?;
+    let palindex = read_palindex(reader, palette_size)?;
+
+    match kind {
+      ChartStitchKind::Full => fullstitches.push(FullStitch {
+        x,
+        y,
+        palindex,
+        kind: FullStitchKind::Full,
+      }),
+      ChartStitchKind::Half(direction) => partstitches.push(PartStitch {
+        x,
+        y,
+        palindex,
+        direction,
+        kind: PartStitchKind::Half,
+      }),
+      ChartStitchKind::Quarter(corner) => {
+        let (x, y, direction) = corner.locate(x, y);
+        partstitches.push(PartStitch {
+          x,
+          y,
+          palindex,
+          direction,
+          kind: PartStitchKind::Quarter,
+        });
+      }
+      ChartStitchKind::ThreeQuarter(corner) => {
+        // The half stitch goes along the diagonal that doesn't touch the corner of the short leg.
+        let (qx, qy, quarter_direction) = corner.locate(x, y);
+        let half_direction = match quarter_direction {
+          PartStitchDirection::Forward => PartStitchDirection::Backward,
+          PartStitchDirection::Backward => PartStitchDirection::Forward,
+        };
+        partstitches.push(PartStitch {
+          x,
+          y,
+          palindex,
+          direction: half_direction,
+          kind: PartStitchKind::Half,
+        });
+        partstitches.push(PartStitch {
+          x: qx,
+          y: qy,
+          palindex,
+          direction: quarter_direction,
+          kind: PartStitchKind::Quarter,
+        });
+      }
+      ChartStitchKind::Petite(corner) => {
+        let (x, y, _) = corner.locate(x, y);
+        fullstitches.push(FullStitch {
+          x,
+          y,
+          palindex,
+          kind: FullStitchKind::Petite,
+        });
+      }
+    }
+  }
+
+  Ok((fullstitches, partstitches))
+}
+
+/// Reads the back stitches whose ends are stored in half-cells.
+fn read_backstitches<R: Read>(reader: &mut R, palette_size: usize) -> io::Result<Vec<LineStitch>> {
+  log::trace!("Reading backstitches");
+
+  let backstitches_count = reader.read_u32::<BigEndian>()?;
+  let mut linestitches = Vec::with_capacity(backstitches_count as usize);
+  for _ in 0..backstitches_count {
+    let x1 = read_coord(reader)?;
+    let y1 = read_coord(reader)?;
+    let x2 = read_coord(reader)?;
+    let y2 = read_coord(reader)?;
+    let palindex = read_palindex(reader, palette_size)?;
+    linestitches.push(LineStitch {
+      x: (x1, x2),
+      y: (y1, y2),
+      palindex,
+      kind: LineStitchKind::Back,
+    });
+  }
+
+  Ok(linestitches)
+}
+
+fn read_coord<R: Read>(reader: &mut R) -> io::Result<Coord> {
+  Ok(Coord::from_halves(reader.read_u16::<BigEndian>()?.into()))
+}
+
+/// Reads a palette index and checks that it refers to an existing palette item.
+fn read_palindex<R: Read>(reader: &mut R, palette_size: usize) -> io::Result<u8> {
+  let palindex = reader.read_u16::<BigEndian>()?;
+  match u8::try_from(palindex) {
+    Ok(palindex) if usize::from(palindex) < palette_size => Ok(palindex),
+    _ => Err(io::Error::new(
+      io::ErrorKind::InvalidData,
+      format!("The palette index {palindex} is out of the palette"),
+    )),
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ChartCorner {
+  TopLeft,
+  TopRight,
+  BottomLeft,
+  BottomRight,
+}
+
+impl ChartCorner {
+  /// Returns the position of the cell quarter in the corner and the direction of a quarter stitch in it.
+  fn locate(self, x: Coord, y: Coord) -> (Coord, Coord, PartStitchDirection) {
+    match self {
+      ChartCorner::TopLeft => (x, y, PartStitchDirection::Backward),
+      ChartCorner::TopRight => (x + Coord::HALF, y, PartStitchDirection::Forward),
+      ChartCorner::BottomLeft => (x, y + Coord::HALF, PartStitchDirection::Forward),
+      ChartCorner::BottomRight => (x + Coord::HALF, y + Coord::HALF, PartStitchDirection::Backward),
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ChartStitchKind {
+  Full,
+  Half(PartStitchDirection),
+  Quarter(ChartCorner),
+  ThreeQuarter(ChartCorner),
+  Petite(ChartCorner),
+}
+
+impl TryFrom<u8> for ChartStitchKind {
+  type Error = io::Error;
+
+  fn try_from(value: u8) -> Result<Self, Self::Error> {
+    const CORNERS: [ChartCorner; 4] = [
+      ChartCorner::TopLeft,
+      ChartCorner::TopRight,
+      ChartCorner::BottomLeft,
+      ChartCorner::BottomRight,
+    ];
+
+    match value {
+      0 => Ok(ChartStitchKind::Full),
+      1 => Ok(ChartStitchKind::Half(PartStitchDirection::Forward)),
+      2 => Ok(ChartStitchKind::Half(PartStitchDirection::Backward)),
+      3..=6 => Ok(ChartStitchKind::Quarter(CORNERS[usize::from(value - 3)])),
+      7..=10 => Ok(ChartStitchKind::ThreeQuarter(CORNERS[usize::from(value - 7)])),
+      11..=14 => Ok(ChartStitchKind::Petite(CORNERS[usize::from(value - 11)])),
+      _ => Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("The stitch kind {value} is unknown"),
+      )),
+    }
+  }
+}
@@ -0,0 +1,185 @@
+use std::fs::File;
+use std::io::Cursor;
+
+use super::*;
+
+fn load_fixture(name: &str) -> File {
+  let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+    .join("testdata/chart")
+    .join(name);
+  File::open(path).unwrap()
+}
+
+#[test]
+fn reads_pattern_info() {
+  let loaded_pattern_info = read_pattern_info(&mut load_fixture("pattern_info")).unwrap();
+  let expected_pattern_info = PatternInfo {
+    title: String::from("Sample"),
+    author: String::from("Nazar Antoniuk"),
+    company: String::new(),
+    copyright: String::from("© Embroidery Studio"),
+    description: String::from("Shows different stitch types"),
+  };
+  assert_eq!(loaded_pattern_info, expected_pattern_info);
+}
+
+#[test]
+fn reads_fabric() {
+  let loaded_fabric = read_fabric(&mut load_fixture("fabric")).unwrap();
+  let expected_fabric = Fabric {
+    width: 5,
+    height: 4,
+    stitches_per_inch: (16, 16),
+    kind: String::from("Aida"),
+    name: String::from("Antique White"),
+    color: Rgb::new(0xFA, 0xEB, 0xD7),
+  };
+  assert_eq!(loaded_fabric, expected_fabric);
+}
+
+#[test]
+fn reads_palette() {
+  let (palette, symbols) = read_palette(&mut load_fixture("palette")).unwrap();
+  let expected_palette = [
+    PaletteItem {
+      brand: String::from("DMC"),
+      number: String::from("310"),
+      name: String::from("Black"),
+      color: Rgb::BLACK,
+      blends: None,
+      bead: None,
+      strands: Some(StitchStrands {
+        full: Some(2),
+        petite: Some(2),
+        half: Some(2),
+        quarter: Some(2),
+        back: Some(1),
+        straight: Some(1),
+        french_knot: None,
+        special: None,
+      }),
+    },
+    PaletteItem {
+      brand: String::from("DMC"),
+      number: String::from("3865"),
+      name: String::from("Winter White"),
+      color: Rgb::new(0xF9, 0xF7, 0xF1),
+      blends: None,
+      bead: None,
+      strands: None,
+    },
+    PaletteItem {
+      brand: String::from("Blend"),
+      number: String::new(),
+      name: String::from("Tweed"),
+      color: Rgb::new(0x7C, 0x7B, 0x78),
+      blends: Some(vec![
+        Blend {
+          brand: String::from("DMC"),
+          number: String::from("310"),
+          strands: 1,
+        },
+        Blend {
+          brand: String::from("DMC"),
+          number: String::from("3865"),
+          strands: 1,
+        },
+      ]),
+      bead: None,
+      strands: None,
+    },
+  ];
+  assert_eq!(palette, expected_palette);
+  assert_eq!(symbols, [Some('X'), Some('○'), Some('◆')]);
+}
+
+#[test]
+fn reads_stitches() {
+  let (fullstitches, partstitches) = read_stitches(&mut load_fixture("stitches"), 3).unwrap();
+  let expected_fullstitches = [
+    FullStitch {
+      x: Coord::from_cells(0),
+      y: Coord::from_cells(0),
+      palindex: 0,
+      kind: FullStitchKind::Full,
+    },
+    FullStitch {
+      x: Coord::from_halves(3),
+      y: Coord::from_halves(3),
+      palindex: 2,
+      kind: FullStitchKind::Petite,
+    },
+  ];
+  let expected_partstitches = [
+    PartStitch {
+      x: Coord::from_cells(1),
+      y: Coord::from_cells(0),
+      palindex: 1,
+      direction: PartStitchDirection::Forward,
+      kind: PartStitchKind::Half,
+    },
+    PartStitch {
+      x: Coord::from_cells(2),
+      y: Coord::from_cells(0),
+      palindex: 2,
+      direction: PartStitchDirection::Backward,
+      kind: PartStitchKind::Half,
+    },
+    PartStitch {
+      x: Coord::from_halves(7),
+      y: Coord::from_cells(0),
+      palindex: 0,
+      direction: PartStitchDirection::Forward,
+      kind: PartStitchKind::Quarter,
+    },
+    // The three-quarter stitch with the short leg in the bottom left corner.
+    PartStitch {
+      x: Coord::from_cells(0),
+      y: Coord::from_cells(1),
+      palindex: 1,
+      direction: PartStitchDirection::Backward,
+      kind: PartStitchKind::Half,
+    },
+    PartStitch {
+      x: Coord::from_cells(0),
+      y: Coord::from_halves(3),
+      palindex: 1,
+      direction: PartStitchDirection::Forward,
+      kind: PartStitchKind::Quarter,
+    },
+  ];
+  assert_eq!(fullstitches, expected_fullstitches);
+  assert_eq!(partstitches, expected_partstitches);
+}
+
+#[test]
+fn fails_on_invalid_stitches() {
+  // The stitch kind is unknown.
+  let buf = vec![0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x0F, 0x00, 0x00];
+  assert!(read_stitches(&mut Cursor::new(buf), 1).is_err());
+
+  // The palette index is out of the palette.
+  let buf = vec![0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01];
+  let error = read_stitches(&mut Cursor::new(buf), 1).unwrap_err();
+  assert_eq!(error.to_string(), "The palette index 1 is out of the palette");
+}
+
+#[test]
+fn reads_backstitches() {
+  let loaded_backstitches = read_backstitches(&mut load_fixture("backstitches"), 3).unwrap();
+  let expected_backstitches = [
+    LineStitch {
+      x: (Coord::from_cells(0), Coord::from_cells(2)),
+      y: (Coord::from_cells(0), Coord::from_cells(0)),
+      palindex: 0,
+      kind: LineStitchKind::Back,
+    },
+    LineStitch {
+      x: (Coord::from_cells(1), Coord::from_cells(3)),
+      y: (Coord::from_cells(1), Coord::from_cells(4)),
+      palindex: 1,
+      kind: LineStitchKind::Back,
+    },
+  ];
+  assert_eq!(loaded_backstitches, expected_backstitches);
+}
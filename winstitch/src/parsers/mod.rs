@@ -0,0 +1,4 @@
+mod read;
+use read::ReadChartExt;
+
+pub mod chart;
@@ -0,0 +1,30 @@
+use std::io;
+
+use byteorder::BigEndian;
+
+use crate::schemas::Rgb;
+
+#[cfg(test)]
+#[path = "read.test.rs"]
+mod tests;
+
+/// Provides additional methods for reading chart data.
+pub trait ReadChartExt: io::Read + byteorder::ReadBytesExt {
+  /// Reads a UTF-8 string prefixed with its length, as written by Java's `DataOutput.writeUTF`.
+  fn read_utf(&mut self) -> io::Result<String> {
+    let length: usize = self.read_u16::<BigEndian>()?.into();
+    let mut buf = vec![0; length];
+    self.read_exact(&mut buf)?;
+    Ok(String::from_utf8_lossy(&buf).into_owned())
+  }
+
+  /// Reads an RGB color.
+  fn read_color(&mut self) -> io::Result<Rgb> {
+    let mut buf: [u8; 3] = [0; 3];
+    self.read_exact(&mut buf)?;
+    Ok(Rgb::from(buf))
+  }
+}
+
+/// All types that implement `Read` get methods defined in `ReadChartExt`.
+impl<R: io::Read + ?Sized> ReadChartExt for R {}
@@ -0,0 +1,28 @@
+use std::io::Cursor;
+
+use super::ReadChartExt as _;
+use crate::schemas::Rgb;
+
+#[test]
+fn reads_utf() {
+  let buf = vec![0x00, 0x05, 0x57, 0x68, 0x69, 0x74, 0x65, 0xFF];
+  assert_eq!(Cursor::new(buf).read_utf().unwrap(), String::from("White"));
+
+  let cyrillic_buf = vec![0x00, 0x04, 0xD0, 0xBB, 0xD0, 0xBD];
+  assert_eq!(Cursor::new(cyrillic_buf).read_utf().unwrap(), String::from("лн"));
+
+  let empty_buf = vec![0x00, 0x00];
+  assert_eq!(Cursor::new(empty_buf).read_utf().unwrap(), String::new());
+}
+
+#[test]
+fn fails_on_truncated_utf() {
+  let truncated_buf = vec![0x00, 0x05, 0x57, 0x68];
+  assert!(Cursor::new(truncated_buf).read_utf().is_err());
+}
+
+#[test]
+fn reads_color() {
+  let color_buf = vec![0x2C, 0x32, 0x25];
+  assert_eq!(Cursor::new(color_buf).read_color().unwrap(), Rgb::new(0x2C, 0x32, 0x25));
+}
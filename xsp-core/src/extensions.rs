@@ -0,0 +1,97 @@
+//! The data that only a single format can store.
+
+use std::any::Any;
+use std::fmt;
+use std::sync::Arc;
+
+#[cfg(test)]
+#[path = "extensions.test.rs"]
+mod tests;
+
+/// The data of the formats that the common model has no place for.
+///
+/// The reader of a format stores its own data here, so that the pattern is written back to the same format losslessly.
+/// The other formats can't interpret the data, so their writers only report it as dropped.
+#[derive(Clone, Default)]
+pub struct FormatExtensions {
+  entries: Vec<Extension>,
+}
+
+#[derive(Clone)]
+struct Extension {
+  format: &'static str,
+  description: &'static str,
+  data: Arc<dyn ExtensionData>,
+}
+
+trait ExtensionData: Any + fmt::Debug + Send + Sync {
+  fn as_any(&self) -> &dyn Any;
+  fn eq_data(&self, other: &dyn ExtensionData) -> bool;
+}
+
+impl<T: Any + fmt::Debug + PartialEq + Send + Sync> ExtensionData for T {
+  fn as_any(&self) -> &dyn Any {
+    self
+  }
+
+  fn eq_data(&self, other: &dyn ExtensionData) -> bool {
+    other.as_any().downcast_ref::<T>().is_some_and(|other| self == other)
+  }
+}
+
+impl FormatExtensions {
+  /// Stores the data of the format, replacing its previous data.
+  /// The description tells what the data is about, for the reports of the other formats.
+  pub fn insert<T: Any + fmt::Debug + PartialEq + Send + Sync>(
+    &mut self,
+    format: &'static str,
+    description: &'static str,
+    data: T,
+  ) {
+    self.entries.retain(|entry| entry.format != format);
+    self.entries.push(Extension {
+      format,
+      description,
+      data: Arc::new(data),
+    });
+  }
+
+  /// Returns the data of the format, if there is some of the given type.
+  pub fn get<T: Any>(&self, format: &str) -> Option<&T> {
+    self
+      .entries
+      .iter()
+      .find(|entry| entry.format == format)
+      .and_then(|entry| entry.data.as_any().downcast_ref())
+  }
+
+  /// Returns the formats with their data descriptions.
+  pub fn iter(&self) -> impl Iterator<Item = (&'static str, &'static str)> + '_ {
+    self.entries.iter().map(|entry| (entry.format, entry.description))
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.entries.is_empty()
+  }
+}
+
+impl fmt::Debug for FormatExtensions {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_map()
+      .entries(self.entries.iter().map(|entry| (entry.format, &entry.data)))
+      .finish()
+  }
+}
+
+impl PartialEq for FormatExtensions {
+  fn eq(&self, other: &Self) -> bool {
+    self.entries.len() == other.entries.len()
+      && self.entries.iter().all(|entry| {
+        other
+          .entries
+          .iter()
+          .find(|other| other.format == entry.format)
+          .is_some_and(|other| entry.data.eq_data(other.data.as_ref()))
+      })
+  }
+}
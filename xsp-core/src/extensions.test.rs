@@ -0,0 +1,34 @@
+use super::*;
+
+#[test]
+fn stores_format_data() {
+  let mut extensions = FormatExtensions::default();
+  assert!(extensions.is_empty());
+
+  extensions.insert("First", "a number", 1u32);
+  extensions.insert("Second", "a text", String::from("text"));
+  extensions.insert("First", "a number", 2u32);
+  assert_eq!(extensions.get::<u32>("First"), Some(&2));
+  assert_eq!(extensions.get::<u8>("First"), None);
+  assert_eq!(extensions.get::<String>("Third"), None);
+  assert_eq!(
+    extensions.iter().collect::<Vec<_>>(),
+    [("Second", "a text"), ("First", "a number")]
+  );
+}
+
+#[test]
+fn compares_format_data() {
+  let mut first = FormatExtensions::default();
+  first.insert("First", "a number", 1u32);
+  first.insert("Second", "a text", String::from("text"));
+  let mut second = FormatExtensions::default();
+  second.insert("Second", "a text", String::from("text"));
+  second.insert("First", "a number", 1u32);
+  assert_eq!(first, second);
+
+  second.insert("First", "a number", 2u32);
+  assert_ne!(first, second);
+  second.insert("First", "a number", 1u8);
+  assert_ne!(first, second);
+}
@@ -0,0 +1,41 @@
+//! The common interface of the pattern formats.
+
+use anyhow::Result;
+
+use crate::pattern::Pattern;
+
+/// A pattern file format.
+pub trait PatternFormat: Sync {
+  /// The human-readable name of the format.
+  fn name(&self) -> &'static str;
+
+  /// The file extensions of the format, in lowercase and without the leading dot.
+  fn extensions(&self) -> &'static [&'static str];
+}
+
+pub trait PatternReader: PatternFormat {
+  /// Checks whether the file content looks like this format, usually by its magic bytes.
+  fn sniff(&self, buf: &[u8]) -> bool;
+
+  /// Reads the pattern from the file content.
+  fn read(&self, buf: &[u8]) -> Result<Pattern>;
+}
+
+pub trait PatternWriter: PatternFormat {
+  /// Writes the pattern to the writer.
+  /// Returns the report of the data that the format can't represent.
+  fn write(&self, pattern: &Pattern, writer: &mut dyn std::io::Write) -> Result<WriteReport>;
+}
+
+/// The data that was dropped or simplified while writing a pattern.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WriteReport {
+  /// The human-readable descriptions of the unmapped data.
+  pub unmapped: Vec<String>,
+}
+
+impl WriteReport {
+  pub fn is_lossless(&self) -> bool {
+    self.unmapped.is_empty()
+  }
+}
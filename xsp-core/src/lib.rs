@@ -0,0 +1,14 @@
+//! The common pattern model and the interface of the pattern formats.
+//!
+//! The format crates depend on this crate to implement the readers and the writers of their formats.
+
+mod extensions;
+mod format;
+mod pattern;
+mod read;
+mod xsd;
+
+pub use extensions::*;
+pub use format::*;
+pub use pattern::*;
+pub use read::*;
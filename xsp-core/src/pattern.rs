@@ -0,0 +1,59 @@
+//! The common pattern model.
+//!
+//! Every supported format is converted from and to this model.
+//! It is built from Pattern Maker's schema types, because Pattern Maker's model is the richest one:
+//! the palette, the fabric and the stitches are shared by all the formats,
+//! while the display hints are the subset of the settings that the other formats can store too.
+//! The data that only a single format stores, like the rest of Pattern Maker's settings, is kept in `extensions`.
+
+pub use pmaker::{
+  Bead, Blend, Coord, CurveCoord, CurvedStitch, Fabric, Formats, FullStitch, FullStitchKind, Grid, LineStitch,
+  LineStitchKind, NodeStitch, NodeStitchKind, PaletteItem, PartStitch, PartStitchDirection, PartStitchKind,
+  PatternInfo, PatternSettings, PrintSettings, Rgb, SpecialStitch, SpecialStitchModel, StitchSettings, StitchStrands,
+  SymbolSettings, Symbols,
+};
+
+use crate::extensions::FormatExtensions;
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Pattern {
+  pub info: PatternInfo,
+  pub fabric: Fabric,
+  pub palette: Vec<PaletteItem>,
+  pub fullstitches: Vec<FullStitch>,
+  pub partstitches: Vec<PartStitch>,
+  pub linestitches: Vec<LineStitch>,
+  pub nodestitches: Vec<NodeStitch>,
+  pub specialstitches: Vec<SpecialStitch>,
+  pub special_stitch_models: Vec<SpecialStitchModel>,
+  pub display: DisplayHints,
+  /// The data that only the format the pattern was read from can store.
+  #[cfg_attr(feature = "serde", serde(skip))]
+  pub extensions: FormatExtensions,
+}
+
+/// The hints on how to display the pattern.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DisplayHints {
+  /// The symbol of each palette item, if any.
+  pub symbols: Vec<Option<char>>,
+  /// The font of the symbols.
+  pub symbol_font: Option<String>,
+  /// The number of cells between the major grid lines.
+  pub major_grid_interval: u16,
+  /// The number of strands used when a palette item doesn't specify its own.
+  pub default_strands: StitchStrands<u8>,
+}
+
+impl Default for DisplayHints {
+  fn default() -> Self {
+    DisplayHints {
+      symbols: Vec::new(),
+      symbol_font: None,
+      major_grid_interval: 10,
+      default_strands: pmaker::StitchSettings::default().default_strands,
+    }
+  }
+}
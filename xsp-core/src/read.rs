@@ -0,0 +1,25 @@
+//! The helpers for reading the binary formats.
+
+use std::io;
+
+#[cfg(test)]
+#[path = "read.test.rs"]
+mod tests;
+
+/// Provides additional methods for reading binary pattern data.
+pub trait ReadFormatExt: io::Read {
+  /// Reads a C-style string with a specified length in the given encoding.
+  fn read_encoded_cstring(&mut self, length: usize, encoding: &'static encoding_rs::Encoding) -> io::Result<String> {
+    let mut buf = vec![0; length + 1]; // +1 for the null terminator.
+    self.read_exact(&mut buf)?;
+
+    match std::ffi::CStr::from_bytes_until_nul(&buf) {
+      Ok(cstr) => Ok(encoding.decode(cstr.to_bytes()).0.to_string()),
+      // This is an edge case when the string is full of trash data.
+      Err(_) => Ok(String::new()),
+    }
+  }
+}
+
+/// All types that implement `Read` get methods defined in `ReadFormatExt`.
+impl<R: io::Read + ?Sized> ReadFormatExt for R {}
@@ -0,0 +1,25 @@
+use std::io::Cursor;
+
+use super::ReadFormatExt as _;
+
+#[test]
+fn reads_encoded_cstring() {
+  let buf = vec![0x43, 0x72, 0xE8, 0x6D, 0x65, 0x00, 0x00];
+  assert_eq!(
+    Cursor::new(buf)
+      .read_encoded_cstring(6, encoding_rs::WINDOWS_1252)
+      .unwrap(),
+    String::from("Crème")
+  );
+}
+
+#[test]
+fn returns_empty_string_on_non_null_terminated_cstring() {
+  let not_nul_terminated_buf = vec![0x43, 0x6F, 0x66, 0x66, 0x65, 0x65];
+  assert_eq!(
+    Cursor::new(not_nul_terminated_buf)
+      .read_encoded_cstring(5, encoding_rs::WINDOWS_1252)
+      .unwrap(),
+    String::from("")
+  );
+}
@@ -0,0 +1,129 @@
+//! The conversion between the common model and Pattern Maker's patterns.
+
+use crate::extensions::FormatExtensions;
+use crate::pattern::*;
+
+#[cfg(test)]
+#[path = "xsd.test.rs"]
+mod tests;
+
+/// The name of the format the Pattern Maker data is stored under.
+const PATTERN_MAKER: &str = "Pattern Maker";
+
+/// The data that only Pattern Maker stores.
+///
+/// The display hints take precedence over the matching settings here when the pattern is written to XSD.
+#[derive(Debug, Clone, PartialEq)]
+struct PatternMakerData {
+  formats: Vec<Formats>,
+  symbols: Vec<Symbols>,
+  grid: Grid,
+  pattern_settings: PatternSettings,
+  stitch_settings: StitchSettings,
+  symbol_settings: SymbolSettings,
+  print_settings: PrintSettings,
+}
+
+impl From<pmaker::Pattern> for Pattern {
+  fn from(pattern: pmaker::Pattern) -> Self {
+    let display = DisplayHints {
+      symbols: pattern.symbols.iter().map(full_stitch_symbol).collect(),
+      symbol_font: Some(pattern.pattern_settings.default_stitch_font.clone()),
+      major_grid_interval: pattern.grid.major_lines_interval,
+      default_strands: pattern.stitch_settings.default_strands.clone(),
+    };
+    let mut extensions = FormatExtensions::default();
+    extensions.insert(
+      PATTERN_MAKER,
+      "the formats, the symbols other than the full stitch ones, the grid, the pattern, stitch, symbol and print settings",
+      PatternMakerData {
+        formats: pattern.formats,
+        symbols: pattern.symbols,
+        grid: pattern.grid,
+        pattern_settings: pattern.pattern_settings,
+        stitch_settings: pattern.stitch_settings,
+        symbol_settings: pattern.symbol_settings,
+        print_settings: pattern.print_settings,
+      },
+    );
+    Pattern {
+      info: pattern.info,
+      fabric: pattern.fabric,
+      palette: pattern.palette,
+      fullstitches: pattern.fullstitches,
+      partstitches: pattern.partstitches,
+      linestitches: pattern.linestitches,
+      nodestitches: pattern.nodestitches,
+      specialstitches: pattern.specialstitches,
+      special_stitch_models: pattern.special_stitch_models,
+      display,
+      extensions,
+    }
+  }
+}
+
+/// The Pattern Maker data of the pattern is restored and the display hints are applied on top of it.
+/// The patterns read from the other formats get the defaults of a new pattern instead.
+impl From<Pattern> for pmaker::Pattern {
+  fn from(pattern: Pattern) -> Self {
+    let display = pattern.display;
+    let mut data = pattern
+      .extensions
+      .get(PATTERN_MAKER)
+      .cloned()
+      .unwrap_or_else(|| PatternMakerData {
+        formats: Vec::new(),
+        symbols: Vec::new(),
+        grid: Grid::default(),
+        pattern_settings: PatternSettings::default(),
+        stitch_settings: StitchSettings::default(),
+        symbol_settings: SymbolSettings::default(),
+        print_settings: PrintSettings::default(),
+      });
+
+    if let Some(font) = display.symbol_font {
+      data.pattern_settings.default_stitch_font = font;
+    }
+    data.grid.major_lines_interval = display.major_grid_interval;
+    data.stitch_settings.default_strands = display.default_strands;
+
+    let palette_size = pattern.palette.len();
+    let default_stitch_font = data.pattern_settings.default_stitch_font.clone();
+    data.formats.resize_with(palette_size, || {
+      let mut formats = Formats::default();
+      formats.font.font_name = Some(default_stitch_font.clone());
+      formats
+    });
+    data.symbols.resize_with(palette_size, Symbols::default);
+    for (palindex, symbols) in data.symbols.iter_mut().enumerate() {
+      let symbol = display.symbols.get(palindex).copied().flatten();
+      // The codes that aren't valid characters are kept unless the symbol is changed.
+      if symbol != full_stitch_symbol(symbols) {
+        symbols.full = symbol.and_then(|symbol| u16::try_from(u32::from(symbol)).ok());
+      }
+    }
+
+    pmaker::Pattern {
+      info: pattern.info,
+      fabric: pattern.fabric,
+      palette: pattern.palette,
+      formats: data.formats,
+      symbols: data.symbols,
+      fullstitches: pattern.fullstitches,
+      partstitches: pattern.partstitches,
+      linestitches: pattern.linestitches,
+      nodestitches: pattern.nodestitches,
+      specialstitches: pattern.specialstitches,
+      special_stitch_models: pattern.special_stitch_models,
+      grid: data.grid,
+      pattern_settings: data.pattern_settings,
+      stitch_settings: data.stitch_settings,
+      symbol_settings: data.symbol_settings,
+      print_settings: data.print_settings,
+    }
+  }
+}
+
+fn full_stitch_symbol(symbols: &Symbols) -> Option<char> {
+  symbols.full.and_then(|code| char::from_u32(code.into()))
+}
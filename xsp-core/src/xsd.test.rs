@@ -0,0 +1,63 @@
+use super::*;
+
+fn load_pattern(name: &str) -> pmaker::Pattern {
+  let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+    .join("../pmaker/testdata/patterns")
+    .join(name);
+  pmaker::parse_xsd_pattern(path).unwrap()
+}
+
+#[test]
+fn converts_to_common_pattern() {
+  let pattern = load_pattern("piggies.xsd");
+  let converted = Pattern::from(pattern.clone());
+
+  assert_eq!(converted.fabric, pattern.fabric);
+  assert_eq!(converted.palette, pattern.palette);
+  assert_eq!(converted.fullstitches, pattern.fullstitches);
+  assert_eq!(converted.display.symbols.len(), pattern.palette.len());
+  assert_eq!(converted.display.symbols[0], Some('!'));
+  assert_eq!(converted.display.symbol_font.as_deref(), Some("CrossStitch3"));
+}
+
+#[test]
+fn converts_from_common_pattern_losslessly() {
+  for name in ["piggies.xsd", "specials.xsd"] {
+    let pattern = load_pattern(name);
+    let converted = pmaker::Pattern::from(Pattern::from(pattern.clone()));
+    assert_eq!(converted, pattern, "{name}");
+  }
+}
+
+#[test]
+fn applies_display_hints_on_conversion() {
+  let mut common = Pattern::from(load_pattern("piggies.xsd"));
+  common.display.symbols[0] = Some('#');
+  common.display.major_grid_interval = 5;
+  common.palette.push(common.palette[0].clone());
+  common.display.symbols.push(Some('@'));
+
+  let converted = pmaker::Pattern::from(common.clone());
+  assert_eq!(converted.symbols[0].full, Some(u16::from(b'#')));
+  assert_eq!(converted.grid.major_lines_interval, 5);
+  assert_eq!(converted.formats.len(), converted.palette.len());
+  assert_eq!(converted.symbols.last().unwrap().full, Some(u16::from(b'@')));
+  assert_eq!(converted.validate(), Ok(()));
+  // The Pattern Maker-only data is kept.
+  let data: &PatternMakerData = common.extensions.get(PATTERN_MAKER).unwrap();
+  assert_eq!(converted.print_settings, data.print_settings);
+  assert_eq!(converted.formats[..data.formats.len()], data.formats[..]);
+}
+
+#[test]
+fn converts_from_other_formats() {
+  let mut common = Pattern::from(load_pattern("piggies.xsd"));
+  common.extensions = FormatExtensions::default();
+
+  let converted = pmaker::Pattern::from(common.clone());
+  assert_eq!(converted.validate(), Ok(()));
+  assert_eq!(converted.formats.len(), common.palette.len());
+  assert_eq!(converted.symbols[0].full, Some(u16::from(b'!')));
+  assert_eq!(converted.print_settings, PrintSettings::default());
+  assert_eq!(converted.pattern_settings.default_stitch_font, "CrossStitch3");
+}
@@ -0,0 +1,92 @@
+//! The registry of the supported pattern formats and the format auto-detection.
+
+use std::path::Path;
+
+use anyhow::Result;
+use xsp_core::{Pattern, PatternReader, PatternWriter, WriteReport};
+
+#[cfg(test)]
+#[path = "format.test.rs"]
+mod tests;
+
+/// All the supported readers.
+pub static READERS: &[&dyn PatternReader] = &[
+  #[cfg(feature = "xsd")]
+  &crate::xsd::XsdFormat,
+  #[cfg(feature = "oxs")]
+  &crate::oxs::OxsFormat,
+  #[cfg(feature = "pat")]
+  &crate::pat::PatFormat,
+  #[cfg(feature = "chart")]
+  &crate::chart::ChartFormat,
+];
+
+/// All the supported writers.
+pub static WRITERS: &[&dyn PatternWriter] = &[
+  #[cfg(feature = "oxs")]
+  &crate::oxs::OxsFormat,
+];
+
+/// Opens a pattern file of any supported format.
+pub fn open<P: AsRef<Path>>(path: P) -> Result<Pattern> {
+  let path = path.as_ref();
+  let buf = std::fs::read(path)?;
+  let extension = path.extension().and_then(|extension| extension.to_str());
+  read(&buf, extension)
+}
+
+/// Reads a pattern of any supported format from the file content.
+///
+/// The format is detected by the magic bytes first, preferring the format of the file extension if several match.
+/// If none of the formats recognizes the content, the format of the file extension is used.
+pub fn read(buf: &[u8], extension: Option<&str>) -> Result<Pattern> {
+  let reader = detect_reader(buf, extension).ok_or_else(|| match extension {
+    Some(extension) => anyhow::anyhow!("Unsupported pattern format: {extension:?}"),
+    None => anyhow::anyhow!("Unsupported pattern format"),
+  })?;
+  log::debug!("Reading the pattern as {}", reader.name());
+  reader.read(buf)
+}
+
+/// Saves the pattern to a file in the format of its extension.
+pub fn save<P: AsRef<Path>>(path: P, pattern: &Pattern) -> Result<WriteReport> {
+  let path = path.as_ref();
+  let extension = path
+    .extension()
+    .and_then(|extension| extension.to_str())
+    .unwrap_or_default();
+  let writer = WRITERS
+    .iter()
+    .find(|writer| {
+      writer
+        .extensions()
+        .iter()
+        .any(|candidate| candidate.eq_ignore_ascii_case(extension))
+    })
+    .ok_or_else(|| anyhow::anyhow!("Unsupported pattern format: {extension:?}"))?;
+  log::debug!("Writing the pattern as {}", writer.name());
+
+  let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+  let report = writer.write(pattern, &mut file)?;
+  std::io::Write::flush(&mut file)?;
+  Ok(report)
+}
+
+fn detect_reader(buf: &[u8], extension: Option<&str>) -> Option<&'static dyn PatternReader> {
+  let has_extension = |reader: &&dyn PatternReader| {
+    extension.is_some_and(|extension| {
+      reader
+        .extensions()
+        .iter()
+        .any(|candidate| candidate.eq_ignore_ascii_case(extension))
+    })
+  };
+
+  let sniffed: Vec<_> = READERS.iter().copied().filter(|reader| reader.sniff(buf)).collect();
+  sniffed
+    .iter()
+    .copied()
+    .find(has_extension)
+    .or_else(|| sniffed.first().copied())
+    .or_else(|| READERS.iter().copied().find(has_extension))
+}
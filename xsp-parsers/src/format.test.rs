@@ -0,0 +1,86 @@
+use super::*;
+
+#[cfg(feature = "xsd")]
+fn test_pattern_path(name: &str) -> std::path::PathBuf {
+  std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+    .join("../pmaker/testdata/patterns")
+    .join(name)
+}
+
+#[test]
+#[cfg(feature = "xsd")]
+fn opens_patterns() {
+  let pattern = open(test_pattern_path("piggies.xsd")).unwrap();
+  assert_eq!(pattern.palette.len(), 8);
+}
+
+#[test]
+#[cfg(feature = "xsd")]
+fn detects_format_by_magic_bytes() {
+  let buf = std::fs::read(test_pattern_path("piggies.xsd")).unwrap();
+  assert!(read(&buf, Some("bin")).is_ok());
+  assert!(read(&buf, None).is_ok());
+}
+
+#[test]
+#[cfg(feature = "xsd")]
+fn detects_format_by_extension() {
+  let reader = detect_reader(&[0x00, 0x00], Some("XSD")).unwrap();
+  assert_eq!(reader.name(), "Pattern Maker");
+
+  // The XSD parser rejects the content, but it is chosen by the extension.
+  assert!(read(&[0x00, 0x00], Some("xsd")).is_err());
+}
+
+#[test]
+#[cfg(feature = "oxs")]
+fn detects_oxs_format() {
+  let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata/oxs/sample.oxs");
+  let buf = std::fs::read(&path).unwrap();
+  assert_eq!(detect_reader(&buf, Some("xml")).unwrap().name(), "Open Cross Stitch");
+  assert_eq!(open(path).unwrap().fabric.width, 10);
+}
+
+#[test]
+fn fails_on_unknown_format() {
+  let error = read(b"unknown", Some("txt")).unwrap_err();
+  assert_eq!(error.to_string(), "Unsupported pattern format: \"txt\"");
+  assert!(read(b"unknown", None).is_err());
+}
+
+#[test]
+#[cfg(feature = "oxs")]
+fn saves_patterns() {
+  let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata/oxs/sample.oxs");
+  let pattern = open(path).unwrap();
+
+  let output = std::env::temp_dir().join(format!("xsp-parsers-{}.oxs", std::process::id()));
+  let report = save(&output, &pattern).unwrap();
+  let saved = open(&output);
+  std::fs::remove_file(&output).unwrap();
+  assert!(report.is_lossless());
+  assert_eq!(saved.unwrap(), pattern);
+
+  let error = save("pattern.txt", &pattern).unwrap_err();
+  assert_eq!(error.to_string(), "Unsupported pattern format: \"txt\"");
+}
+
+#[test]
+#[cfg(feature = "pat")]
+fn detects_pat_format() {
+  let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../pcstitch/testdata/patterns/sample.pat");
+  let buf = std::fs::read(&path).unwrap();
+  assert!(detect_reader(&buf, None).is_none());
+  assert_eq!(detect_reader(&buf, Some("pat")).unwrap().name(), "PCStitch");
+  assert_eq!(open(path).unwrap().fabric.width, 4);
+}
+
+#[test]
+#[cfg(feature = "chart")]
+fn detects_chart_format() {
+  let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../winstitch/testdata/patterns/sample.chart");
+  let buf = std::fs::read(&path).unwrap();
+  assert!(detect_reader(&buf, None).is_none());
+  assert_eq!(detect_reader(&buf, Some("chart")).unwrap().name(), "WinStitch");
+  assert_eq!(open(path).unwrap().fabric.width, 5);
+}
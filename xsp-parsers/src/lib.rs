@@ -1 +1,33 @@
+//! The readers and the writers of the supported pattern formats.
+//!
+//! The tools of `pmaker` that are enabled by the features of this crate are re-exported.
+//! They work on Pattern Maker's patterns, which are converted from and to the common `Pattern` with `From`.
+
+#[cfg(feature = "chart")]
+pub mod chart;
+mod format;
+#[cfg(feature = "oxs")]
+pub mod oxs;
+#[cfg(feature = "pat")]
+pub mod pat;
+#[cfg(feature = "xsd")]
 pub mod xsd;
+
+pub use format::*;
+#[cfg(feature = "rendering")]
+pub use pmaker::{
+  AdjacentPages, ChartOptions, ChartPage, FractionalStitchShape, GridLines, POINTS_PER_INCH, PageLayout,
+  PageLayoutOptions, PaperSize, PrintArea, layout_pages, line_height, print_area, render_svg_chart,
+};
+#[cfg(feature = "catalogs")]
+pub use pmaker::{
+  CatalogThread, Catalogs, ConversionReport, PaletteConversion, SkipReason, SkippedThread, ThreadCatalog,
+  ThreadCollision, ThreadMatch, convert_palette,
+};
+#[cfg(feature = "image")]
+pub use pmaker::{Dithering, ImageConversionOptions, Quantization, convert_image, convert_rgba_image};
+#[cfg(feature = "pdf")]
+pub use pmaker::{PdfOptions, render_pdf_chart};
+#[cfg(feature = "preview")]
+pub use pmaker::{PreviewImage, PreviewOptions, render_preview};
+pub use xsp_core::*;
@@ -0,0 +1,40 @@
+//! The Open Cross Stitch (OXS) format.
+//!
+//! It is an XML-based interchange format supported by many cross-stitch editors.
+//! The palette item with the index `0` is the cloth, so the stitches of the cloth color are omitted.
+
+mod read;
+mod write;
+
+pub use read::parse_oxs_pattern;
+pub use write::write_oxs_pattern;
+use xsp_core::{Pattern, PatternFormat, PatternReader, PatternWriter, WriteReport};
+
+/// The Open Cross Stitch format.
+pub struct OxsFormat;
+
+impl PatternFormat for OxsFormat {
+  fn name(&self) -> &'static str {
+    "Open Cross Stitch"
+  }
+
+  fn extensions(&self) -> &'static [&'static str] {
+    &["oxs"]
+  }
+}
+
+impl PatternReader for OxsFormat {
+  fn sniff(&self, buf: &[u8]) -> bool {
+    read::has_oxs_root(buf)
+  }
+
+  fn read(&self, buf: &[u8]) -> anyhow::Result<Pattern> {
+    parse_oxs_pattern(buf)
+  }
+}
+
+impl PatternWriter for OxsFormat {
+  fn write(&self, pattern: &Pattern, writer: &mut dyn std::io::Write) -> anyhow::Result<WriteReport> {
+    write_oxs_pattern(pattern, writer)
+  }
+}
@@ -0,0 +1,376 @@
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use quick_xml::events::{BytesStart, Event};
+use xsp_core::*;
+
+#[cfg(test)]
+#[path = "read.test.rs"]
+mod tests;
+
+/// The number of bytes checked for the root element when sniffing the format.
+const SNIFF_LENGTH: usize = 1024;
+
+/// Checks whether the root element of the data is `chart`.
+/// The XML declaration, the comments and the document type declaration before it are skipped.
+pub(super) fn has_oxs_root(buf: &[u8]) -> bool {
+  let head = &buf[..buf.len().min(SNIFF_LENGTH)];
+  let head = head.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(head);
+  let mut reader = quick_xml::Reader::from_reader(head);
+  reader.config_mut().trim_text(true);
+
+  let mut buf = Vec::new();
+  loop {
+    match reader.read_event_into(&mut buf) {
+      Ok(Event::Start(element) | Event::Empty(element)) => return element.name().as_ref() == b"chart",
+      Ok(Event::Decl(_) | Event::Comment(_) | Event::DocType(_) | Event::PI(_)) => buf.clear(),
+      _ => return false,
+    }
+  }
+}
+
+pub fn parse_oxs_pattern(buf: &[u8]) -> Result<Pattern> {
+  let mut reader = quick_xml::Reader::from_reader(buf);
+  reader.config_mut().trim_text(true);
+
+  let mut pattern = Pattern {
+    info: PatternInfo::default(),
+    fabric: Fabric {
+      width: 0,
+      height: 0,
+      stitches_per_inch: (14, 14),
+      kind: String::from("Aida"),
+      name: String::from("White"),
+      color: Rgb::WHITE,
+    },
+    palette: Vec::new(),
+    fullstitches: Vec::new(),
+    partstitches: Vec::new(),
+    linestitches: Vec::new(),
+    nodestitches: Vec::new(),
+    specialstitches: Vec::new(),
+    special_stitch_models: Vec::new(),
+    display: DisplayHints::default(),
+    extensions: Default::default(),
+  };
+  // Maps the OXS palette indexes to the palette indexes of the pattern.
+  // The cloth is mapped to `None`.
+  let mut palindexes: HashMap<String, Option<u8>> = HashMap::new();
+  let mut has_chart = false;
+
+  let mut buf = Vec::new();
+  loop {
+    let element = match reader.read_event_into(&mut buf)? {
+      Event::Start(element) | Event::Empty(element) => element,
+      Event::Eof => break,
+      _ => {
+        buf.clear();
+        continue;
+      }
+    };
+    let attributes = read_attributes(&element)?;
+
+    match element.name().as_ref() {
+      b"chart" => has_chart = true,
+      b"properties" => read_properties(&attributes, &mut pattern)?,
+      b"palette_item" => {
+        let index = attributes.get("index").map(|index| index.trim().to_string());
+        let palindex = read_palette_item(&attributes, &mut pattern)?;
+        palindexes.insert(index.unwrap_or_default(), palindex);
+      }
+      b"stitch" => {
+        let (x, y) = (read_cell(&attributes, "x")?, read_cell(&attributes, "y")?);
+        if let Some(palindex) = read_palindex(&attributes, "palindex", &palindexes)? {
+          pattern.fullstitches.push(FullStitch {
+            x,
+            y,
+            palindex,
+            kind: FullStitchKind::Full,
+          });
+        }
+      }
+      b"partstitch" => read_partstitch(&attributes, &palindexes, &mut pattern)?,
+      b"backstitch" => {
+        if let Some(palindex) = read_palindex(&attributes, "palindex", &palindexes)? {
+          let kind = match attributes.get("objecttype").map(String::as_str) {
+            Some("straightstitch") => LineStitchKind::Straight,
+            _ => LineStitchKind::Back,
+          };
+          pattern.linestitches.push(LineStitch {
+            x: (read_coord(&attributes, "x1")?, read_coord(&attributes, "x2")?),
+            y: (read_coord(&attributes, "y1")?, read_coord(&attributes, "y2")?),
+            palindex,
+            kind,
+          });
+        }
+      }
+      b"object" => read_ornament(&attributes, &palindexes, &mut pattern)?,
+      _ => {}
+    }
+    buf.clear();
+  }
+
+  if !has_chart {
+    anyhow::bail!("The OXS document has no chart element");
+  }
+  Ok(pattern)
+}
+
+fn read_attributes(element: &BytesStart) -> Result<HashMap<String, String>> {
+  let mut attributes = HashMap::new();
+  for attribute in element.attributes() {
+    let attribute = attribute?;
+    let key = String::from_utf8_lossy(attribute.key.as_ref()).into_owned();
+    let value = attribute.unescape_value()?.into_owned();
+    attributes.insert(key, value);
+  }
+  Ok(attributes)
+}
+
+fn read_properties(attributes: &HashMap<String, String>, pattern: &mut Pattern) -> Result<()> {
+  log::trace!("Reading properties");
+  let text = |name: &str| attributes.get(name).cloned().unwrap_or_default();
+
+  pattern.info = PatternInfo {
+    title: text("charttitle"),
+    author: text("author"),
+    company: text("company"),
+    copyright: text("copyright"),
+    description: text("instructions"),
+  };
+  pattern.fabric.width = read_number(attributes, "chartwidth")?;
+  pattern.fabric.height = read_number(attributes, "chartheight")?;
+  if attributes.contains_key("stitchesperinch") {
+    let stitches_per_inch = read_number(attributes, "stitchesperinch")?;
+    let stitches_per_inch_y = match attributes.get("stitchesperinch_y") {
+      Some(_) => read_number(attributes, "stitchesperinch_y")?,
+      None => stitches_per_inch,
+    };
+    pattern.fabric.stitches_per_inch = (stitches_per_inch, stitches_per_inch_y);
+  }
+  Ok(())
+}
+
+/// Reads a palette item and returns its palette index, or `None` for the cloth.
+fn read_palette_item(attributes: &HashMap<String, String>, pattern: &mut Pattern) -> Result<Option<u8>> {
+  let text = |name: &str| attributes.get(name).cloned().unwrap_or_default();
+  let color = Rgb::from_hex(&text("color")).context("Invalid palette item color")?;
+
+  if text("index").trim() == "0" {
+    pattern.fabric.name = text("name");
+    pattern.fabric.color = color;
+    return Ok(None);
+  }
+
+  // The number includes the brand, like `DMC 310`.
+  let number = text("number");
+  let (brand, number) = match number.trim().rsplit_once(' ') {
+    Some((brand, number)) => (brand.trim().to_owned(), number.to_owned()),
+    None => (String::new(), number.trim().to_owned()),
+  };
+
+  let strands = attributes.get("strands").and_then(|strands| strands.parse().ok());
+  let line_strands = attributes.get("bsstrands").and_then(|strands| strands.parse().ok());
+  let strands = (strands.is_some() || line_strands.is_some()).then_some(StitchStrands {
+    full: strands,
+    petite: strands,
+    half: strands,
+    quarter: strands,
+    back: line_strands,
+    straight: line_strands,
+    french_knot: strands,
+    special: strands,
+  });
+
+  let palindex = u8::try_from(pattern.palette.len()).context("The palette has too many colors")?;
+  pattern.palette.push(PaletteItem {
+    brand,
+    number,
+    name: text("name"),
+    color,
+    blends: None,
+    bead: None,
+    strands,
+  });
+  pattern
+    .display
+    .symbols
+    .push(attributes.get("symbol").and_then(|symbol| read_symbol(symbol)));
+  Ok(Some(palindex))
+}
+
+/// Reads a symbol, which is either a character or its code.
+fn read_symbol(symbol: &str) -> Option<char> {
+  match symbol.parse::<u32>() {
+    Ok(code) => char::from_u32(code),
+    Err(_) => symbol.chars().next(),
+  }
+}
+
+/// A corner of a cell.
+#[derive(Clone, Copy)]
+enum Corner {
+  TopLeft,
+  TopRight,
+  BottomLeft,
+  BottomRight,
+}
+
+/// Reads a part stitch, which is a cell divided diagonally into two triangles of different colors.
+///
+/// The direction specifies the division and the triangle of `palindex1`:
+/// `1` and `3` are divided by `\`, `2` and `4` by `/`;
+/// `palindex1` is the top triangle for `1` and `2`, and the bottom one for `3` and `4`.
+///
+/// Every triangle gets a quarter stitch in its free corner.
+/// The triangle of `palindex1` also gets the half stitch along the division, which makes it a three-quarter stitch.
+fn read_partstitch(
+  attributes: &HashMap<String, String>,
+  palindexes: &HashMap<String, Option<u8>>,
+  pattern: &mut Pattern,
+) -> Result<()> {
+  let (x, y) = (read_cell(attributes, "x")?, read_cell(attributes, "y")?);
+  let direction: u8 = read_number(attributes, "direction")?;
+  let (division, first, second) = match direction {
+    1 => (PartStitchDirection::Backward, Corner::TopRight, Corner::BottomLeft),
+    2 => (PartStitchDirection::Forward, Corner::TopLeft, Corner::BottomRight),
+    3 => (PartStitchDirection::Backward, Corner::BottomLeft, Corner::TopRight),
+    4 => (PartStitchDirection::Forward, Corner::BottomRight, Corner::TopLeft),
+    _ => anyhow::bail!("Unknown part stitch direction {direction}"),
+  };
+
+  if let Some(palindex) = read_palindex(attributes, "palindex1", palindexes)? {
+    pattern.partstitches.push(PartStitch {
+      x,
+      y,
+      palindex,
+      direction: division,
+      kind: PartStitchKind::Half,
+    });
+    pattern.partstitches.push(quarter_stitch(x, y, first, palindex));
+  }
+  if let Some(palindex) = read_palindex(attributes, "palindex2", palindexes)? {
+    pattern.partstitches.push(quarter_stitch(x, y, second, palindex));
+  }
+  Ok(())
+}
+
+fn quarter_stitch(x: Coord, y: Coord, corner: Corner, palindex: u8) -> PartStitch {
+  let (x, y, direction) = match corner {
+    Corner::TopLeft => (x, y, PartStitchDirection::Backward),
+    Corner::TopRight => (x + Coord::HALF, y, PartStitchDirection::Forward),
+    Corner::BottomLeft => (x, y + Coord::HALF, PartStitchDirection::Forward),
+    Corner::BottomRight => (x + Coord::HALF, y + Coord::HALF, PartStitchDirection::Backward),
+  };
+  PartStitch {
+    x,
+    y,
+    palindex,
+    direction,
+    kind: PartStitchKind::Quarter,
+  }
+}
+
+/// Reads an ornament, which is a French knot, a bead or a stitch placed at any point of the chart.
+fn read_ornament(
+  attributes: &HashMap<String, String>,
+  palindexes: &HashMap<String, Option<u8>>,
+  pattern: &mut Pattern,
+) -> Result<()> {
+  let Some(palindex) = read_palindex(attributes, "palindex", palindexes)? else {
+    return Ok(());
+  };
+  let (x, y) = (
+    read_ornament_coord(attributes, "x1")?,
+    read_ornament_coord(attributes, "y1")?,
+  );
+
+  match attributes.get("objecttype").map(String::as_str).unwrap_or_default() {
+    "knot" => pattern.nodestitches.push(NodeStitch {
+      x,
+      y,
+      rotated: false,
+      palindex,
+      kind: NodeStitchKind::FrenchKnot,
+    }),
+    "bead" => pattern.nodestitches.push(NodeStitch {
+      x,
+      y,
+      rotated: false,
+      palindex,
+      kind: NodeStitchKind::Bead,
+    }),
+    "petite" => pattern.fullstitches.push(FullStitch {
+      x,
+      y,
+      palindex,
+      kind: FullStitchKind::Petite,
+    }),
+    "quarter" => {
+      let corner = match (x.halves() % 2 != 0, y.halves() % 2 != 0) {
+        (false, false) => Corner::TopLeft,
+        (true, false) => Corner::TopRight,
+        (false, true) => Corner::BottomLeft,
+        (true, true) => Corner::BottomRight,
+      };
+      let cell = (Coord::from_cells(x.cell()), Coord::from_cells(y.cell()));
+      pattern
+        .partstitches
+        .push(quarter_stitch(cell.0, cell.1, corner, palindex));
+    }
+    "fullstitch" => pattern.fullstitches.push(FullStitch {
+      x,
+      y,
+      palindex,
+      kind: FullStitchKind::Full,
+    }),
+    objecttype => log::warn!("Skipping the unsupported ornament {objecttype:?}"),
+  }
+  Ok(())
+}
+
+/// Reads the palette index of a stitch. Returns `None` for the cloth.
+fn read_palindex(
+  attributes: &HashMap<String, String>,
+  name: &str,
+  palindexes: &HashMap<String, Option<u8>>,
+) -> Result<Option<u8>> {
+  let index = attributes
+    .get(name)
+    .with_context(|| format!("The {name} attribute is missing"))?;
+  palindexes
+    .get(index.trim())
+    .copied()
+    .with_context(|| format!("The palette index {index} is unknown"))
+}
+
+fn read_number<T: std::str::FromStr>(attributes: &HashMap<String, String>, name: &str) -> Result<T> {
+  let value = attributes
+    .get(name)
+    .with_context(|| format!("The {name} attribute is missing"))?;
+  value
+    .trim()
+    .parse()
+    .map_err(|_| anyhow::anyhow!("The {name} attribute is not a valid number: {value:?}"))
+}
+
+/// Reads a coordinate in cells, which may be fractional.
+fn read_coord(attributes: &HashMap<String, String>, name: &str) -> Result<Coord> {
+  Coord::try_from(read_number::<f32>(attributes, name)?)
+}
+
+/// Reads a coordinate of an ornament, rounding it to the nearest half-cell.
+/// Some editors place the ornaments freely, so they are moved to the grid instead of failing the whole document.
+fn read_ornament_coord(attributes: &HashMap<String, String>, name: &str) -> Result<Coord> {
+  let value = read_number::<f32>(attributes, name)?;
+  let rounded = (value * 2.0).round() / 2.0;
+  if rounded != value {
+    log::warn!("Moving the ornament from the {name} coordinate {value} to {rounded}");
+  }
+  Coord::try_from(rounded)
+}
+
+/// Reads a coordinate of a cell.
+fn read_cell(attributes: &HashMap<String, String>, name: &str) -> Result<Coord> {
+  Ok(Coord::from_cells(read_number(attributes, name)?))
+}
@@ -0,0 +1,239 @@
+use super::*;
+
+fn load_sample(name: &str) -> Vec<u8> {
+  let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+    .join("testdata/oxs")
+    .join(name);
+  std::fs::read(path).unwrap()
+}
+
+fn coord(value: f32) -> Coord {
+  Coord::try_from(value).unwrap()
+}
+
+#[test]
+fn sniffs_oxs_documents() {
+  assert!(has_oxs_root(&load_sample("sample.oxs")));
+  assert!(has_oxs_root(b"\xEF\xBB\xBF<?xml version=\"1.0\"?>\n<chart></chart>"));
+  assert!(has_oxs_root(b"<chart><format comments01=\"\" /></chart>"));
+  assert!(has_oxs_root(b"<!-- Saved by an editor -->\n<chart/>"));
+  assert!(!has_oxs_root(b"<?xml version=\"1.0\"?><svg></svg>"));
+  assert!(!has_oxs_root(b"<charts></charts>"));
+  assert!(!has_oxs_root(b"Not a chart: <chart>"));
+  assert!(!has_oxs_root(&[0x10, 0x05]));
+}
+
+#[test]
+fn reads_properties() {
+  let pattern = parse_oxs_pattern(&load_sample("sample.oxs")).unwrap();
+  assert_eq!(
+    pattern.info,
+    PatternInfo {
+      title: String::from("Sample & Test"),
+      author: String::from("Nazar Antoniuk"),
+      company: String::new(),
+      copyright: String::from("Embroidery Studio"),
+      description: String::from("Shows different stitch types"),
+    }
+  );
+  assert_eq!(
+    pattern.fabric,
+    Fabric {
+      width: 10,
+      height: 5,
+      stitches_per_inch: (18, 18),
+      kind: String::from("Aida"),
+      name: String::from("Ecru"),
+      color: Rgb::new(0xF0, 0xEA, 0xDA),
+    }
+  );
+}
+
+#[test]
+fn reads_palette() {
+  let pattern = parse_oxs_pattern(&load_sample("sample.oxs")).unwrap();
+  assert_eq!(
+    pattern.palette,
+    [
+      PaletteItem {
+        brand: String::from("DMC"),
+        number: String::from("310"),
+        name: String::from("Black"),
+        color: Rgb::BLACK,
+        blends: None,
+        bead: None,
+        strands: Some(StitchStrands {
+          full: Some(2),
+          petite: Some(2),
+          half: Some(2),
+          quarter: Some(2),
+          back: Some(1),
+          straight: Some(1),
+          french_knot: Some(2),
+          special: Some(2),
+        }),
+      },
+      PaletteItem {
+        brand: String::from("Anchor"),
+        number: String::from("1"),
+        name: String::from("White"),
+        color: Rgb::WHITE,
+        blends: None,
+        bead: None,
+        strands: None,
+      },
+    ]
+  );
+  assert_eq!(pattern.display.symbols, [Some('!'), Some('+')]);
+}
+
+#[test]
+fn reads_stitches() {
+  let pattern = parse_oxs_pattern(&load_sample("sample.oxs")).unwrap();
+  assert_eq!(
+    pattern.fullstitches,
+    [
+      FullStitch {
+        x: coord(0.0),
+        y: coord(0.0),
+        palindex: 0,
+        kind: FullStitchKind::Full,
+      },
+      FullStitch {
+        x: coord(9.0),
+        y: coord(4.0),
+        palindex: 1,
+        kind: FullStitchKind::Full,
+      },
+      FullStitch {
+        x: coord(6.0),
+        y: coord(3.5),
+        palindex: 1,
+        kind: FullStitchKind::Petite,
+      },
+    ]
+  );
+  assert_eq!(
+    pattern.partstitches,
+    [
+      PartStitch {
+        x: coord(2.0),
+        y: coord(1.0),
+        palindex: 0,
+        direction: PartStitchDirection::Backward,
+        kind: PartStitchKind::Half,
+      },
+      PartStitch {
+        x: coord(2.5),
+        y: coord(1.0),
+        palindex: 0,
+        direction: PartStitchDirection::Forward,
+        kind: PartStitchKind::Quarter,
+      },
+      PartStitch {
+        x: coord(2.0),
+        y: coord(1.5),
+        palindex: 1,
+        direction: PartStitchDirection::Forward,
+        kind: PartStitchKind::Quarter,
+      },
+      PartStitch {
+        x: coord(3.0),
+        y: coord(1.0),
+        palindex: 0,
+        direction: PartStitchDirection::Backward,
+        kind: PartStitchKind::Quarter,
+      },
+      PartStitch {
+        x: coord(5.5),
+        y: coord(3.0),
+        palindex: 0,
+        direction: PartStitchDirection::Forward,
+        kind: PartStitchKind::Quarter,
+      },
+    ]
+  );
+  assert_eq!(
+    pattern.linestitches,
+    [
+      LineStitch {
+        x: (coord(1.0), coord(2.0)),
+        y: (coord(1.0), coord(2.5)),
+        palindex: 0,
+        kind: LineStitchKind::Back,
+      },
+      LineStitch {
+        x: (coord(4.0), coord(6.0)),
+        y: (coord(0.0), coord(0.0)),
+        palindex: 1,
+        kind: LineStitchKind::Straight,
+      },
+    ]
+  );
+  assert_eq!(
+    pattern.nodestitches,
+    [
+      NodeStitch {
+        x: coord(1.5),
+        y: coord(1.5),
+        rotated: false,
+        palindex: 0,
+        kind: NodeStitchKind::FrenchKnot,
+      },
+      NodeStitch {
+        x: coord(2.0),
+        y: coord(2.0),
+        rotated: false,
+        palindex: 1,
+        kind: NodeStitchKind::Bead,
+      },
+    ]
+  );
+}
+
+#[test]
+fn reads_minimal_documents() {
+  let pattern = parse_oxs_pattern(&load_sample("fullstitches.oxs")).unwrap();
+  assert_eq!(pattern.info.title, "Checkerboard");
+  assert_eq!(pattern.fabric.stitches_per_inch, (14, 14));
+  assert_eq!(pattern.palette[0].brand, "");
+  assert_eq!(pattern.palette[0].number, "310");
+  assert_eq!(pattern.fullstitches.len(), 2);
+  assert!(pattern.partstitches.is_empty());
+}
+
+#[test]
+fn reads_padded_indexes_and_off_grid_ornaments() {
+  let document = br#"<?xml version="1.0"?>
+    <chart>
+      <properties chartheight="5" chartwidth="5" />
+      <palette>
+        <palette_item index=" 0" number="cloth" name="cloth" color="FFFFFF" />
+        <palette_item index=" 1 " number="310" name="Black" color="000000" />
+      </palette>
+      <fullstitches><stitch x="0" y="0" palindex="1" /></fullstitches>
+      <ornaments_inc_knots_and_beads>
+        <object x1="1.3" y1="2.8" palindex="1" objecttype="knot" />
+      </ornaments_inc_knots_and_beads>
+    </chart>"#;
+  let pattern = parse_oxs_pattern(document).unwrap();
+  assert_eq!(pattern.fullstitches[0].palindex, 0);
+  assert_eq!(
+    (pattern.nodestitches[0].x, pattern.nodestitches[0].y),
+    (coord(1.5), coord(3.0))
+  );
+}
+
+#[test]
+fn fails_on_invalid_documents() {
+  assert!(parse_oxs_pattern(b"<?xml version=\"1.0\"?><svg/>").is_err());
+
+  let unknown_palindex = br#"<?xml version="1.0"?>
+    <chart>
+      <properties chartheight="1" chartwidth="1" />
+      <palette><palette_item index="0" number="cloth" name="cloth" color="FFFFFF" /></palette>
+      <fullstitches><stitch x="0" y="0" palindex="3" /></fullstitches>
+    </chart>"#;
+  let error = parse_oxs_pattern(unknown_palindex).unwrap_err();
+  assert_eq!(error.to_string(), "The palette index 3 is unknown");
+}
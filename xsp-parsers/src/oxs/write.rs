@@ -0,0 +1,438 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, Event};
+use xsp_core::*;
+
+#[cfg(test)]
+#[path = "write.test.rs"]
+mod tests;
+
+type XmlWriter<W> = quick_xml::Writer<W>;
+
+/// Writes the pattern as an OXS document.
+/// Returns the report of the data that OXS can't represent.
+pub fn write_oxs_pattern<W: std::io::Write>(pattern: &Pattern, writer: W) -> Result<WriteReport> {
+  let mut report = WriteReport::default();
+  let mut writer = quick_xml::Writer::new_with_indent(writer, b' ', 2);
+
+  writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))?;
+  writer.write_event(Event::Start(BytesStart::new("chart")))?;
+  write_empty(
+    &mut writer,
+    "format",
+    &[(
+      "comments01",
+      String::from("Designed to allow interchange of basic pattern data between any cross stitch style software"),
+    )],
+  )?;
+  write_properties(&mut writer, pattern, &mut report)?;
+  write_palette(&mut writer, pattern, &mut report)?;
+  write_fullstitches(&mut writer, pattern)?;
+  let ornaments = write_partstitches(&mut writer, pattern, &mut report)?;
+  write_backstitches(&mut writer, pattern, &mut report)?;
+  write_ornaments(&mut writer, pattern, ornaments, &mut report)?;
+  writer.write_event(Event::End(BytesEnd::new("chart")))?;
+
+  for (format, description) in pattern.extensions.iter() {
+    report
+      .unmapped
+      .push(format!("The {format} data is not supported: {description}"));
+  }
+
+  Ok(report)
+}
+
+fn write_empty<W: std::io::Write>(writer: &mut XmlWriter<W>, name: &str, attributes: &[(&str, String)]) -> Result<()> {
+  let mut element = BytesStart::new(name);
+  for (key, value) in attributes {
+    element.push_attribute((*key, value.as_str()));
+  }
+  writer.write_event(Event::Empty(element))?;
+  Ok(())
+}
+
+/// Writes a section with its elements.
+fn write_section<W: std::io::Write>(
+  writer: &mut XmlWriter<W>,
+  name: &str,
+  elements: &[(&str, Vec<(&str, String)>)],
+) -> Result<()> {
+  if elements.is_empty() {
+    writer.write_event(Event::Empty(BytesStart::new(name)))?;
+    return Ok(());
+  }
+  writer.write_event(Event::Start(BytesStart::new(name)))?;
+  for (element, attributes) in elements {
+    write_empty(writer, element, attributes)?;
+  }
+  writer.write_event(Event::End(BytesEnd::new(name)))?;
+  Ok(())
+}
+
+/// Converts a palette index of the pattern to the OXS one, which is shifted by the cloth.
+fn oxs_palindex(palindex: u8) -> String {
+  (palindex as u16 + 1).to_string()
+}
+
+fn write_properties<W: std::io::Write>(
+  writer: &mut XmlWriter<W>,
+  pattern: &Pattern,
+  report: &mut WriteReport,
+) -> Result<()> {
+  log::trace!("Writing properties");
+  let fabric = &pattern.fabric;
+  if fabric.kind != "Aida" {
+    report
+      .unmapped
+      .push(format!("The fabric kind {:?} is not supported", fabric.kind));
+  }
+  if pattern.display.major_grid_interval != DisplayHints::default().major_grid_interval {
+    report
+      .unmapped
+      .push(String::from("The major grid interval is not supported"));
+  }
+
+  write_empty(
+    writer,
+    "properties",
+    &[
+      ("oxsversion", String::from("1.0")),
+      ("software", String::from("xsp-parsers")),
+      ("chartheight", fabric.height.to_string()),
+      ("chartwidth", fabric.width.to_string()),
+      ("charttitle", pattern.info.title.clone()),
+      ("author", pattern.info.author.clone()),
+      ("company", pattern.info.company.clone()),
+      ("copyright", pattern.info.copyright.clone()),
+      ("instructions", pattern.info.description.clone()),
+      ("stitchesperinch", fabric.stitches_per_inch.0.to_string()),
+      ("stitchesperinch_y", fabric.stitches_per_inch.1.to_string()),
+      ("palettecount", pattern.palette.len().to_string()),
+    ],
+  )
+}
+
+fn write_palette<W: std::io::Write>(
+  writer: &mut XmlWriter<W>,
+  pattern: &Pattern,
+  report: &mut WriteReport,
+) -> Result<()> {
+  log::trace!("Writing palette");
+  if let Some(font) = &pattern.display.symbol_font {
+    report
+      .unmapped
+      .push(format!("The symbol font {font:?} is not supported"));
+  }
+
+  let mut items = vec![(
+    "palette_item",
+    vec![
+      ("index", String::from("0")),
+      ("number", String::from("cloth")),
+      ("name", pattern.fabric.name.clone()),
+      ("color", pattern.fabric.color.to_hex()),
+    ],
+  )];
+
+  for (palindex, palitem) in pattern.palette.iter().enumerate() {
+    let mut attributes = vec![
+      ("index", oxs_palindex(palindex as u8)),
+      (
+        "number",
+        format!("{} {}", palitem.brand, palitem.number).trim().to_owned(),
+      ),
+      ("name", palitem.name.clone()),
+      ("color", palitem.color.to_hex()),
+    ];
+
+    if let Some(blends) = &palitem.blends {
+      let components = blends
+        .iter()
+        .map(|blend| format!("{} {} x{}", blend.brand, blend.number, blend.strands))
+        .collect::<Vec<_>>()
+        .join(" + ");
+      attributes.push(("comments", components));
+      report
+        .unmapped
+        .push(format!("The blend of palette item #{palindex} is written as a comment"));
+    }
+    if palitem.bead.is_some() {
+      report
+        .unmapped
+        .push(format!("The bead size of palette item #{palindex} is not supported"));
+    }
+
+    if let Some(strands) = &palitem.strands {
+      if let Some(full) = strands.full {
+        attributes.push(("strands", full.to_string()));
+      }
+      if let Some(back) = strands.back {
+        attributes.push(("bsstrands", back.to_string()));
+      }
+      let cross_strands = [
+        strands.petite,
+        strands.half,
+        strands.quarter,
+        strands.french_knot,
+        strands.special,
+      ];
+      if cross_strands.iter().any(|value| *value != strands.full) || strands.straight != strands.back {
+        report.unmapped.push(format!(
+          "The per-stitch strands of palette item #{palindex} are simplified"
+        ));
+      }
+    }
+
+    if let Some(symbol) = pattern.display.symbols.get(palindex).copied().flatten() {
+      // Digits are written as codes, because a numeric symbol is read as a character code.
+      let symbol = if symbol.is_ascii_digit() {
+        u32::from(symbol).to_string()
+      } else {
+        symbol.to_string()
+      };
+      attributes.push(("symbol", symbol));
+    }
+
+    items.push(("palette_item", attributes));
+  }
+
+  write_section(writer, "palette", &items)
+}
+
+fn write_fullstitches<W: std::io::Write>(writer: &mut XmlWriter<W>, pattern: &Pattern) -> Result<()> {
+  log::trace!("Writing full stitches");
+  let stitches: Vec<_> = pattern
+    .fullstitches
+    .iter()
+    .filter(|stitch| stitch.kind == FullStitchKind::Full)
+    .map(|stitch| {
+      (
+        "stitch",
+        vec![
+          ("x", stitch.x.cell().to_string()),
+          ("y", stitch.y.cell().to_string()),
+          ("palindex", oxs_palindex(stitch.palindex)),
+        ],
+      )
+    })
+    .collect();
+  write_section(writer, "fullstitches", &stitches)
+}
+
+/// Writes the half stitches with the adjacent quarter stitches as the OXS part stitches.
+/// Returns the rest of the quarter stitches, which are written as ornaments.
+///
+/// This is the reverse of the mapping described in the reader.
+fn write_partstitches<'a, W: std::io::Write>(
+  writer: &mut XmlWriter<W>,
+  pattern: &'a Pattern,
+  report: &mut WriteReport,
+) -> Result<Vec<&'a PartStitch>> {
+  log::trace!("Writing part stitches");
+  let mut quarters: HashMap<(Coord, Coord), Vec<usize>> = HashMap::new();
+  for (index, stitch) in pattern.partstitches.iter().enumerate() {
+    if stitch.kind == PartStitchKind::Quarter {
+      quarters.entry((stitch.x, stitch.y)).or_default().push(index);
+    }
+  }
+  let mut take_quarter = |x: Coord, y: Coord, palindex: Option<u8>| {
+    let indexes = quarters.get_mut(&(x, y))?;
+    let position = indexes
+      .iter()
+      .position(|&index| palindex.is_none_or(|palindex| pattern.partstitches[index].palindex == palindex))?;
+    Some(&pattern.partstitches[indexes.remove(position)])
+  };
+
+  let mut partstitches = Vec::new();
+  let mut extended_halves = 0;
+  for stitch in pattern.partstitches.iter() {
+    if stitch.kind != PartStitchKind::Half {
+      continue;
+    }
+
+    let (x, y) = (stitch.x, stitch.y);
+    // The corners of the triangles of `palindex1` and `palindex2`.
+    let (direction, first, second) = match stitch.direction {
+      PartStitchDirection::Backward => (1, (x + Coord::HALF, y), (x, y + Coord::HALF)),
+      PartStitchDirection::Forward => (2, (x, y), (x + Coord::HALF, y + Coord::HALF)),
+    };
+    if take_quarter(first.0, first.1, Some(stitch.palindex)).is_none() {
+      extended_halves += 1;
+    }
+    let palindex2 =
+      take_quarter(second.0, second.1, None).map_or(String::from("0"), |stitch| oxs_palindex(stitch.palindex));
+
+    partstitches.push((
+      "partstitch",
+      vec![
+        ("x", x.cell().to_string()),
+        ("y", y.cell().to_string()),
+        ("palindex1", oxs_palindex(stitch.palindex)),
+        ("palindex2", palindex2),
+        ("direction", direction.to_string()),
+      ],
+    ));
+  }
+  if extended_halves != 0 {
+    report.unmapped.push(format!(
+      "{extended_halves} half stitch(es) are written as three-quarter stitches"
+    ));
+  }
+  write_section(writer, "partstitches", &partstitches)?;
+
+  // Keep the original order of the rest of the quarter stitches.
+  let mut rest: Vec<_> = quarters.into_values().flatten().collect();
+  rest.sort_unstable();
+  Ok(rest.into_iter().map(|index| &pattern.partstitches[index]).collect())
+}
+
+fn write_backstitches<W: std::io::Write>(
+  writer: &mut XmlWriter<W>,
+  pattern: &Pattern,
+  report: &mut WriteReport,
+) -> Result<()> {
+  log::trace!("Writing back stitches");
+  let mut backstitches = Vec::new();
+  let mut push = |stitch: &LineStitch, offset: (Coord, Coord)| {
+    let objecttype = match stitch.kind {
+      LineStitchKind::Back => "backstitch",
+      LineStitchKind::Straight => "straightstitch",
+    };
+    let sequence = backstitches.len().to_string();
+    backstitches.push((
+      "backstitch",
+      vec![
+        ("x1", (stitch.x.0 + offset.0).to_string()),
+        ("x2", (stitch.x.1 + offset.0).to_string()),
+        ("y1", (stitch.y.0 + offset.1).to_string()),
+        ("y2", (stitch.y.1 + offset.1).to_string()),
+        ("palindex", oxs_palindex(stitch.palindex)),
+        ("objecttype", objecttype.to_owned()),
+        ("sequence", sequence),
+      ],
+    ));
+  };
+
+  for stitch in pattern.linestitches.iter() {
+    push(stitch, (Coord::ZERO, Coord::ZERO));
+  }
+
+  // The special stitches are flattened into their line stitches.
+  let mut flattened = 0;
+  for (index, stitch) in pattern.specialstitches.iter().enumerate() {
+    let Some(model) = flattenable_model(pattern, index, stitch, report) else {
+      continue;
+    };
+    flattened += 1;
+    for linestitch in model.linestitches.iter() {
+      let linestitch = LineStitch {
+        palindex: stitch.palindex,
+        ..linestitch.clone()
+      };
+      push(&linestitch, (stitch.x, stitch.y));
+    }
+  }
+
+  if flattened != 0 {
+    report.unmapped.push(format!(
+      "{flattened} special stitch(es) are flattened into line and node stitches"
+    ));
+  }
+
+  write_section(writer, "backstitches", &backstitches)
+}
+
+fn write_ornaments<W: std::io::Write>(
+  writer: &mut XmlWriter<W>,
+  pattern: &Pattern,
+  quarters: Vec<&PartStitch>,
+  report: &mut WriteReport,
+) -> Result<()> {
+  log::trace!("Writing ornaments");
+  let ornament = |objecttype: &str, x: Coord, y: Coord, palindex: u8| {
+    (
+      "object",
+      vec![
+        ("objecttype", objecttype.to_owned()),
+        ("x1", x.to_string()),
+        ("y1", y.to_string()),
+        ("palindex", oxs_palindex(palindex)),
+      ],
+    )
+  };
+
+  let mut ornaments = Vec::new();
+  for stitch in pattern.fullstitches.iter() {
+    if stitch.kind == FullStitchKind::Petite {
+      ornaments.push(ornament("petite", stitch.x, stitch.y, stitch.palindex));
+    }
+  }
+  for stitch in quarters {
+    ornaments.push(ornament("quarter", stitch.x, stitch.y, stitch.palindex));
+  }
+
+  let node_objecttype = |kind: &NodeStitchKind| match kind {
+    NodeStitchKind::FrenchKnot => "knot",
+    NodeStitchKind::Bead => "bead",
+  };
+  let mut rotated = 0;
+  for stitch in pattern.nodestitches.iter() {
+    if stitch.rotated {
+      rotated += 1;
+    }
+    ornaments.push(ornament(
+      node_objecttype(&stitch.kind),
+      stitch.x,
+      stitch.y,
+      stitch.palindex,
+    ));
+  }
+  if rotated != 0 {
+    report
+      .unmapped
+      .push(format!("The rotation of {rotated} node stitch(es) is not supported"));
+  }
+
+  for (index, stitch) in pattern.specialstitches.iter().enumerate() {
+    // The problems are already reported when writing the line stitches.
+    let Some(model) = flattenable_model(pattern, index, stitch, &mut WriteReport::default()) else {
+      continue;
+    };
+    for nodestitch in model.nodestitches.iter() {
+      ornaments.push(ornament(
+        node_objecttype(&nodestitch.kind),
+        nodestitch.x + stitch.x,
+        nodestitch.y + stitch.y,
+        stitch.palindex,
+      ));
+    }
+  }
+
+  write_section(writer, "ornaments_inc_knots_and_beads", &ornaments)
+}
+
+/// Returns the model of the special stitch if it can be flattened into the line and node stitches.
+fn flattenable_model<'a>(
+  pattern: &'a Pattern,
+  index: usize,
+  stitch: &SpecialStitch,
+  report: &mut WriteReport,
+) -> Option<&'a SpecialStitchModel> {
+  let Some(model) = pattern.special_stitch_models.get(stitch.modindex as usize) else {
+    report.unmapped.push(format!("Special stitch #{index} has no model"));
+    return None;
+  };
+  if stitch.rotation != 0 || stitch.flip != (false, false) {
+    report.unmapped.push(format!(
+      "Special stitch #{index} is rotated or flipped, which is not supported"
+    ));
+    return None;
+  }
+  if !model.curvedstitches.is_empty() {
+    report.unmapped.push(format!(
+      "The curved stitches of special stitch #{index} are not supported"
+    ));
+  }
+  Some(model)
+}
@@ -0,0 +1,117 @@
+use super::*;
+use crate::oxs::parse_oxs_pattern;
+
+fn load_oxs_sample(name: &str) -> Pattern {
+  let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+    .join("testdata/oxs")
+    .join(name);
+  parse_oxs_pattern(&std::fs::read(path).unwrap()).unwrap()
+}
+
+fn write(pattern: &Pattern) -> (Vec<u8>, WriteReport) {
+  let mut buf = Vec::new();
+  let report = write_oxs_pattern(pattern, &mut buf).unwrap();
+  (buf, report)
+}
+
+#[test]
+fn round_trips_oxs_samples() {
+  for name in ["sample.oxs", "fullstitches.oxs"] {
+    let pattern = load_oxs_sample(name);
+    let (buf, report) = write(&pattern);
+    assert_eq!(
+      parse_oxs_pattern(&buf).unwrap(),
+      pattern,
+      "{name} changed after the round trip"
+    );
+    assert!(report.is_lossless(), "{name}: {:?}", report.unmapped);
+  }
+}
+
+#[test]
+fn writes_palette() {
+  let pattern = load_oxs_sample("fullstitches.oxs");
+  let (buf, _) = write(&pattern);
+  let document = String::from_utf8(buf).unwrap();
+  assert!(document.contains(r#"<palette_item index="0" number="cloth" name="cloth" color="FFFFFF"/>"#));
+  assert!(document.contains(r#"<palette_item index="1" number="310" name="Black" color="000000"/>"#));
+  assert!(document.contains(r#"<stitch x="1" y="1" palindex="1"/>"#));
+}
+
+#[test]
+fn reports_unmapped_data() {
+  let mut pattern = load_oxs_sample("sample.oxs");
+  pattern.fabric.kind = String::from("Evenweave");
+  pattern.palette[1].blends = Some(vec![
+    Blend {
+      brand: String::from("DMC"),
+      number: String::from("964"),
+      strands: 1,
+    },
+    Blend {
+      brand: String::from("DMC"),
+      number: String::from("3766"),
+      strands: 1,
+    },
+  ]);
+  pattern.partstitches.push(PartStitch {
+    x: Coord::from_cells(7),
+    y: Coord::from_cells(1),
+    palindex: 0,
+    direction: PartStitchDirection::Forward,
+    kind: PartStitchKind::Half,
+  });
+  pattern.nodestitches[0].rotated = true;
+
+  let (buf, report) = write(&pattern);
+  assert_eq!(
+    report.unmapped,
+    [
+      "The fabric kind \"Evenweave\" is not supported",
+      "The blend of palette item #1 is written as a comment",
+      "1 half stitch(es) are written as three-quarter stitches",
+      "The rotation of 1 node stitch(es) is not supported",
+    ]
+  );
+  let document = String::from_utf8(buf).unwrap();
+  assert!(document.contains(r#"comments="DMC 964 x1 + DMC 3766 x1""#));
+}
+
+#[cfg(feature = "xsd")]
+#[test]
+fn exports_xsd_patterns() {
+  for name in ["piggies.xsd", "specials.xsd"] {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+      .join("../pmaker/testdata/patterns")
+      .join(name);
+    let pattern = Pattern::from(pmaker::parse_xsd_pattern(path).unwrap());
+    let (buf, report) = write(&pattern);
+    let exported = parse_oxs_pattern(&buf).unwrap();
+
+    assert_eq!(exported.info, pattern.info, "{name}");
+    assert_eq!(exported.fabric, pattern.fabric, "{name}");
+    assert_eq!(exported.fullstitches, pattern.fullstitches, "{name}");
+    assert!(exported.nodestitches.len() >= pattern.nodestitches.len(), "{name}");
+    assert_eq!(exported.display.symbols, pattern.display.symbols, "{name}");
+    for (exported, palitem) in exported.palette.iter().zip(pattern.palette.iter()) {
+      assert_eq!(
+        (&exported.name, exported.color),
+        (&palitem.name, palitem.color),
+        "{name}"
+      );
+    }
+    assert!(
+      report
+        .unmapped
+        .contains(&String::from("The symbol font \"CrossStitch3\" is not supported")),
+      "{name}"
+    );
+    assert!(
+      report
+        .unmapped
+        .iter()
+        .any(|unmapped| unmapped.starts_with("The Pattern Maker data is not supported: ")),
+      "{name}"
+    );
+  }
+}
@@ -1 +1,25 @@
 pub use pmaker::*;
+use xsp_core::{PatternFormat, PatternReader};
+
+/// The Pattern Maker v4 format.
+pub struct XsdFormat;
+
+impl PatternFormat for XsdFormat {
+  fn name(&self) -> &'static str {
+    "Pattern Maker"
+  }
+
+  fn extensions(&self) -> &'static [&'static str] {
+    &["xsd"]
+  }
+}
+
+impl PatternReader for XsdFormat {
+  fn sniff(&self, buf: &[u8]) -> bool {
+    has_xsd_signature(buf)
+  }
+
+  fn read(&self, buf: &[u8]) -> anyhow::Result<xsp_core::Pattern> {
+    Ok(parse_xsd_pattern_from_bytes(buf)?.into())
+  }
+}